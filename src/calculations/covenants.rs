@@ -0,0 +1,313 @@
+//! Loan covenants: debt service coverage, interest coverage, debt yield and loan-to-value by period
+
+use crate::{FinanceError, FinanceResult, validate_finite, validate_positive};
+use crate::calculations::{calculate_loan_payment, AmortizationPayment};
+use serde::Deserialize;
+use std::io::Read;
+
+/// One period of the borrower's cash-flow projection
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CashFlowPeriod {
+    pub period: String,
+    /// Cash flow available for debt service (NOI or EBITDA)
+    pub cash_flow: f64,
+    /// Collateral value, needed for loan-to-value
+    #[serde(default)]
+    pub value: Option<f64>,
+}
+
+/// A covenant the lender tests each period
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Covenant {
+    DebtServiceCoverage,
+    InterestCoverage,
+    DebtYield,
+    LoanToValue,
+}
+
+impl Covenant {
+    /// Short name of the covenant
+    pub fn name(&self) -> &'static str {
+        match self {
+            Covenant::DebtServiceCoverage => "DSCR",
+            Covenant::InterestCoverage => "Interest Coverage",
+            Covenant::DebtYield => "Debt Yield",
+            Covenant::LoanToValue => "LTV",
+        }
+    }
+}
+
+/// Covenant levels; a covenant left as `None` is not tested
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CovenantThresholds {
+    pub min_debt_service_coverage: Option<f64>,
+    pub min_interest_coverage: Option<f64>,
+    /// Minimum annualized cash flow over the loan balance (as a decimal)
+    pub min_debt_yield: Option<f64>,
+    /// Maximum loan balance over collateral value (as a decimal)
+    pub max_loan_to_value: Option<f64>,
+}
+
+impl Default for CovenantThresholds {
+    fn default() -> Self {
+        CovenantThresholds {
+            min_debt_service_coverage: Some(1.25),
+            min_interest_coverage: Some(2.0),
+            min_debt_yield: Some(0.08),
+            max_loan_to_value: Some(0.75),
+        }
+    }
+}
+
+/// Covenant measures for one period and any breaches
+#[derive(Debug, Clone, PartialEq)]
+pub struct CovenantPeriod {
+    pub period: String,
+    pub cash_flow: f64,
+    /// Principal plus interest paid during the period
+    pub debt_service: f64,
+    pub interest: f64,
+    /// Loan balance at the start of the period
+    pub balance: f64,
+    /// Measures are `None` once the loan is repaid or when their inputs are missing
+    pub debt_service_coverage: Option<f64>,
+    pub interest_coverage: Option<f64>,
+    pub debt_yield: Option<f64>,
+    pub loan_to_value: Option<f64>,
+    pub breaches: Vec<Covenant>,
+}
+
+impl CovenantPeriod {
+    /// Whether every tested covenant holds
+    pub fn passed(&self) -> bool {
+        self.breaches.is_empty()
+    }
+}
+
+/// Reads a projection from CSV with the header `period,cash_flow,value`
+/// 
+/// The `value` column is optional and may be left empty.
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::read_cash_flow_projection_csv;
+/// 
+/// let csv = "period,cash_flow,value\n\
+///            Year 1,120000,1500000\n\
+///            Year 2,125000,\n";
+/// let periods = read_cash_flow_projection_csv(csv.as_bytes()).unwrap();
+/// assert_eq!(periods[0].value, Some(1500000.0));
+/// assert_eq!(periods[1].value, None);
+/// ```
+pub fn read_cash_flow_projection_csv<R: Read>(reader: R) -> FinanceResult<Vec<CashFlowPeriod>> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(reader);
+    
+    csv_reader
+        .deserialize()
+        .enumerate()
+        .map(|(index, record)| {
+            record.map_err(|e| FinanceError::InvalidInput(format!("Invalid period on row {}: {}", index + 1, e)))
+        })
+        .collect()
+}
+
+/// Tests covenants for each projection period against an amortization schedule
+/// 
+/// Each period covers `months_per_period` monthly payments of the schedule, in
+/// order. Debt yield annualizes the period's cash flow and, with loan-to-value,
+/// uses the balance outstanding at the start of the period.
+/// 
+/// # Arguments
+/// * `periods` - Cash flow available for debt service by period
+/// * `schedule` - Monthly amortization schedule of the loan
+/// * `months_per_period` - Length of each projection period in months
+/// * `thresholds` - Covenant levels to test
+/// 
+/// # Examples
+/// ```
+/// use chrono::NaiveDate;
+/// use rusty_finance::calculations::{check_covenants, generate_amortization_schedule, CashFlowPeriod, Covenant, CovenantThresholds};
+/// 
+/// let schedule = generate_amortization_schedule(1000000.0, 6.0, 25, NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()).unwrap();
+/// let periods = vec![
+///     CashFlowPeriod { period: "Year 1".into(), cash_flow: 125000.0, value: Some(1500000.0) },
+///     CashFlowPeriod { period: "Year 2".into(), cash_flow: 80000.0, value: Some(1200000.0) },
+/// ];
+/// 
+/// let results = check_covenants(&periods, &schedule, 12, &CovenantThresholds::default()).unwrap();
+/// assert!(results[0].passed());
+/// assert!(results[1].breaches.contains(&Covenant::DebtServiceCoverage));
+/// assert!(results[1].breaches.contains(&Covenant::LoanToValue));
+/// ```
+pub fn check_covenants(
+    periods: &[CashFlowPeriod],
+    schedule: &[AmortizationPayment],
+    months_per_period: u32,
+    thresholds: &CovenantThresholds
+) -> FinanceResult<Vec<CovenantPeriod>> {
+    if periods.is_empty() {
+        return Err(FinanceError::InvalidInput("At least one projection period is required".into()));
+    }
+    if schedule.is_empty() {
+        return Err(FinanceError::InvalidInput("The amortization schedule cannot be empty".into()));
+    }
+    if months_per_period == 0 {
+        return Err(FinanceError::InvalidInput("Periods must be at least one month long".into()));
+    }
+    for (value, name) in [
+        (thresholds.min_debt_service_coverage, "DSCR covenant"),
+        (thresholds.min_interest_coverage, "Interest coverage covenant"),
+        (thresholds.min_debt_yield, "Debt yield covenant"),
+        (thresholds.max_loan_to_value, "LTV covenant"),
+    ] {
+        if let Some(value) = value {
+            validate_positive(value, name)?;
+        }
+    }
+    
+    let loan_amount = schedule[0].remaining_balance + schedule[0].principal_payment;
+    let months = months_per_period as usize;
+    let periods_per_year = 12.0 / months_per_period as f64;
+    
+    periods.iter().enumerate().map(|(index, period)| {
+        validate_finite(period.cash_flow, &format!("Cash flow for {}", period.period))?;
+        if let Some(value) = period.value {
+            validate_positive(value, &format!("Value for {}", period.period))?;
+        }
+        
+        let start = (index * months).min(schedule.len());
+        let payments = &schedule[start..((index + 1) * months).min(schedule.len())];
+        let balance = if start == 0 { loan_amount } else { schedule[start - 1].remaining_balance };
+        let interest: f64 = payments.iter().map(|payment| payment.interest_payment).sum();
+        let debt_service = interest + payments.iter().map(|payment| payment.principal_payment).sum::<f64>();
+        
+        let ratio = |numerator: f64, denominator: f64| (denominator > 0.0).then(|| numerator / denominator);
+        let debt_service_coverage = ratio(period.cash_flow, debt_service);
+        let interest_coverage = ratio(period.cash_flow, interest);
+        let debt_yield = ratio(period.cash_flow * periods_per_year, balance);
+        let loan_to_value = period.value.filter(|_| balance > 0.0).map(|value| balance / value);
+        
+        let below = |measure: Option<f64>, minimum: Option<f64>| matches!((measure, minimum), (Some(m), Some(min)) if m < min);
+        let mut breaches = Vec::new();
+        if below(debt_service_coverage, thresholds.min_debt_service_coverage) {
+            breaches.push(Covenant::DebtServiceCoverage);
+        }
+        if below(interest_coverage, thresholds.min_interest_coverage) {
+            breaches.push(Covenant::InterestCoverage);
+        }
+        if below(debt_yield, thresholds.min_debt_yield) {
+            breaches.push(Covenant::DebtYield);
+        }
+        if matches!((loan_to_value, thresholds.max_loan_to_value), (Some(ltv), Some(max)) if ltv > max) {
+            breaches.push(Covenant::LoanToValue);
+        }
+        
+        Ok(CovenantPeriod {
+            period: period.period.clone(),
+            cash_flow: period.cash_flow,
+            debt_service,
+            interest,
+            balance,
+            debt_service_coverage,
+            interest_coverage,
+            debt_yield,
+            loan_to_value,
+            breaches,
+        })
+    }).collect()
+}
+
+/// Calculates the largest level-payment loan an annual cash flow supports at a target DSCR
+/// 
+/// # Arguments
+/// * `annual_cash_flow` - Annual cash flow available for debt service
+/// * `target_dscr` - Required debt service coverage ratio
+/// * `annual_interest_rate` - The annual interest rate as a percentage
+/// * `loan_term_years` - The amortization term in years
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{calculate_loan_payment, calculate_max_loan_for_dscr};
+/// 
+/// let max_loan = calculate_max_loan_for_dscr(125000.0, 1.25, 6.0, 25.0).unwrap();
+/// let payment = calculate_loan_payment(max_loan, 6.0, 25.0).unwrap();
+/// assert!((payment * 12.0 - 100000.0).abs() < 1e-6);
+/// ```
+pub fn calculate_max_loan_for_dscr(
+    annual_cash_flow: f64,
+    target_dscr: f64,
+    annual_interest_rate: f64,
+    loan_term_years: f64
+) -> FinanceResult<f64> {
+    validate_positive(annual_cash_flow, "Annual cash flow")?;
+    validate_positive(target_dscr, "Target DSCR")?;
+    
+    let payment_per_dollar = calculate_loan_payment(1.0, annual_interest_rate, loan_term_years)?;
+    Ok(annual_cash_flow / target_dscr / 12.0 / payment_per_dollar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculations::generate_amortization_schedule;
+    use chrono::NaiveDate;
+
+    fn schedule(years: i32) -> Vec<AmortizationPayment> {
+        generate_amortization_schedule(1000000.0, 6.0, years, NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()).unwrap()
+    }
+
+    fn period(name: &str, cash_flow: f64, value: Option<f64>) -> CashFlowPeriod {
+        CashFlowPeriod { period: name.into(), cash_flow, value }
+    }
+
+    #[test]
+    fn test_measures_use_the_period_of_the_schedule() {
+        let schedule = schedule(25);
+        let results = check_covenants(&[period("Y1", 120000.0, Some(1600000.0)), period("Y2", 120000.0, None)], &schedule, 12, &CovenantThresholds::default()).unwrap();
+        
+        let annual_payment = calculate_loan_payment(1000000.0, 6.0, 25.0).unwrap() * 12.0;
+        assert!((results[0].debt_service - annual_payment).abs() < 1e-6);
+        assert!((results[0].debt_service_coverage.unwrap() - 120000.0 / annual_payment).abs() < 1e-9);
+        assert_eq!(results[0].balance, 1000000.0);
+        assert_eq!(results[0].loan_to_value, Some(0.625));
+        assert!((results[0].debt_yield.unwrap() - 0.12).abs() < 1e-12);
+        
+        assert_eq!(results[1].balance, schedule[11].remaining_balance);
+        assert_eq!(results[1].loan_to_value, None);
+        assert!(results.iter().all(CovenantPeriod::passed));
+    }
+
+    #[test]
+    fn test_quarterly_periods_annualize_debt_yield() {
+        let results = check_covenants(&[period("Q1", 25000.0, None)], &schedule(25), 3, &CovenantThresholds::default()).unwrap();
+        
+        assert!((results[0].debt_yield.unwrap() - 0.10).abs() < 1e-12);
+        assert!(results[0].interest > 14900.0 && results[0].interest < 15000.0);
+        assert!(results[0].breaches.contains(&Covenant::InterestCoverage));
+    }
+
+    #[test]
+    fn test_untested_covenants_and_repaid_loans_never_breach() {
+        let thresholds = CovenantThresholds { min_debt_service_coverage: Some(1.25), min_interest_coverage: None, min_debt_yield: None, max_loan_to_value: None };
+        let periods = [period("Y1", 1.0, Some(1.0)), period("Y2", 1.0, Some(1.0))];
+        let results = check_covenants(&periods, &schedule(1), 12, &thresholds).unwrap();
+        
+        assert_eq!(results[0].breaches, vec![Covenant::DebtServiceCoverage]);
+        assert_eq!(results[1].debt_service, 0.0);
+        assert_eq!(results[1].debt_service_coverage, None);
+        assert!(results[1].passed());
+    }
+
+    #[test]
+    fn test_max_loan_sizes_to_the_target_dscr() {
+        let max_loan = calculate_max_loan_for_dscr(150000.0, 1.25, 6.0, 25.0).unwrap();
+        let schedule = generate_amortization_schedule(max_loan, 6.0, 25, NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()).unwrap();
+        let results = check_covenants(&[period("Y1", 150000.0, None)], &schedule, 12, &CovenantThresholds::default()).unwrap();
+        
+        assert!((results[0].debt_service_coverage.unwrap() - 1.25).abs() < 1e-9);
+        assert!(calculate_max_loan_for_dscr(150000.0, 0.0, 6.0, 25.0).is_err());
+    }
+}
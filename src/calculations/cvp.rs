@@ -0,0 +1,409 @@
+//! Cost-volume-profit analysis: sales-mix break-even, target profit, margin of safety and operating leverage
+
+use crate::{FinanceError, FinanceResult, validate_non_negative, validate_positive};
+use crate::calculations::{calculate_break_even_units, SensitivityRange};
+use std::str::FromStr;
+
+/// A product's price, variable cost and share of units sold
+#[derive(Debug, Clone, PartialEq)]
+pub struct Product {
+    pub name: String,
+    pub price: f64,
+    pub variable_cost: f64,
+    /// Relative weight in the sales mix; weights need not sum to one
+    pub mix: f64,
+}
+
+impl FromStr for Product {
+    type Err = FinanceError;
+
+    /// Parses `name=price:variable_cost:mix`, e.g. `standard=20:12:3`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, terms) = s.split_once('=')
+            .ok_or_else(|| FinanceError::InvalidInput(format!("Product must be name=price:variable_cost:mix: {}", s)))?;
+        
+        let terms: Vec<f64> = terms
+            .split(':')
+            .map(|term| term.trim().parse::<f64>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| FinanceError::InvalidInput(format!("Invalid terms for product {}: {}", name, terms)))?;
+        
+        let [price, variable_cost, mix] = terms[..] else {
+            return Err(FinanceError::InvalidInput(format!("Product must be name=price:variable_cost:mix: {}", s)));
+        };
+        
+        Ok(Product { name: name.trim().to_string(), price, variable_cost, mix })
+    }
+}
+
+/// Products sold together in fixed proportions, treated as one composite unit
+#[derive(Debug, Clone, PartialEq)]
+pub struct SalesMix {
+    products: Vec<Product>,
+    total_mix: f64,
+}
+
+/// Units and revenue of one product at a given total volume
+#[derive(Debug, Clone)]
+pub struct ProductVolume {
+    pub name: String,
+    pub units: f64,
+    pub revenue: f64,
+}
+
+impl SalesMix {
+    /// Validates the products and their mix weights
+    /// 
+    /// # Examples
+    /// ```
+    /// use rusty_finance::calculations::{SalesMix, Product};
+    /// 
+    /// let mix = SalesMix::new(vec![
+    ///     "standard=20:12:3".parse().unwrap(),
+    ///     "deluxe=50:30:1".parse().unwrap(),
+    /// ]).unwrap();
+    /// // (3 × $8 + 1 × $20) / 4 units
+    /// assert_eq!(mix.contribution_margin(), 11.0);
+    /// ```
+    pub fn new(products: Vec<Product>) -> FinanceResult<Self> {
+        if products.is_empty() {
+            return Err(FinanceError::InvalidInput("At least one product is required".into()));
+        }
+        
+        for product in &products {
+            validate_positive(product.price, &format!("Price of {}", product.name))?;
+            validate_non_negative(product.variable_cost, &format!("Variable cost of {}", product.name))?;
+            validate_positive(product.mix, &format!("Sales mix of {}", product.name))?;
+        }
+        
+        let total_mix = products.iter().map(|product| product.mix).sum();
+        Ok(SalesMix { products, total_mix })
+    }
+
+    /// A mix of one product
+    pub fn single(price: f64, variable_cost: f64) -> FinanceResult<Self> {
+        SalesMix::new(vec![Product { name: "Product".to_string(), price, variable_cost, mix: 1.0 }])
+    }
+
+    /// The products in the mix
+    pub fn products(&self) -> &[Product] {
+        &self.products
+    }
+
+    /// Mix-weighted selling price per unit
+    pub fn price(&self) -> f64 {
+        self.weighted(|product| product.price)
+    }
+
+    /// Mix-weighted variable cost per unit
+    pub fn variable_cost(&self) -> f64 {
+        self.weighted(|product| product.variable_cost)
+    }
+
+    /// Mix-weighted contribution margin per unit
+    pub fn contribution_margin(&self) -> f64 {
+        self.price() - self.variable_cost()
+    }
+
+    /// Contribution margin as a share of revenue
+    pub fn contribution_margin_ratio(&self) -> f64 {
+        self.contribution_margin() / self.price()
+    }
+
+    /// Splits a total unit volume across the products in mix proportions
+    pub fn split(&self, units: f64) -> Vec<ProductVolume> {
+        self.products
+            .iter()
+            .map(|product| {
+                let product_units = units * product.mix / self.total_mix;
+                ProductVolume {
+                    name: product.name.clone(),
+                    units: product_units,
+                    revenue: product_units * product.price,
+                }
+            })
+            .collect()
+    }
+
+    fn weighted(&self, value: impl Fn(&Product) -> f64) -> f64 {
+        self.products.iter().map(|product| value(product) * product.mix).sum::<f64>() / self.total_mix
+    }
+}
+
+/// Calculates the break-even volume of each product in a sales mix
+/// 
+/// Formula: Total units = Fixed Costs / Weighted Contribution Margin per Unit
+/// 
+/// # Arguments
+/// * `fixed_costs` - Total fixed costs shared by the products
+/// * `mix` - The products and their sales mix
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{calculate_sales_mix_break_even, SalesMix};
+/// 
+/// let mix = SalesMix::new(vec!["standard=20:12:3".parse().unwrap(), "deluxe=50:30:1".parse().unwrap()]).unwrap();
+/// let volumes = calculate_sales_mix_break_even(44000.0, &mix).unwrap();
+/// assert!((volumes[0].units - 3000.0).abs() < 1e-9);
+/// assert!((volumes[1].units - 1000.0).abs() < 1e-9);
+/// ```
+pub fn calculate_sales_mix_break_even(fixed_costs: f64, mix: &SalesMix) -> FinanceResult<Vec<ProductVolume>> {
+    let units = calculate_target_profit_units(fixed_costs, mix.variable_cost(), mix.price(), 0.0)?;
+    Ok(mix.split(units))
+}
+
+/// Calculates the units needed to earn a target operating profit
+/// 
+/// Formula: Units = (Fixed Costs + Target Profit) / (Price per Unit - Variable Cost per Unit)
+/// 
+/// # Arguments
+/// * `fixed_costs` - Total fixed costs
+/// * `variable_cost_per_unit` - Variable cost per unit
+/// * `price_per_unit` - Selling price per unit
+/// * `target_profit` - Operating profit before tax to aim for
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_target_profit_units;
+/// 
+/// let units = calculate_target_profit_units(1000.0, 10.0, 20.0, 500.0).unwrap();
+/// assert_eq!(units, 150.0);
+/// ```
+pub fn calculate_target_profit_units(
+    fixed_costs: f64,
+    variable_cost_per_unit: f64,
+    price_per_unit: f64,
+    target_profit: f64
+) -> FinanceResult<f64> {
+    validate_non_negative(target_profit, "Target profit")?;
+    // Shares its validation with break-even, which is the zero-profit case
+    let break_even_units = calculate_break_even_units(fixed_costs, variable_cost_per_unit, price_per_unit)?;
+    
+    Ok(break_even_units + target_profit / (price_per_unit - variable_cost_per_unit))
+}
+
+/// Calculates the units needed to earn a target net income after tax
+/// 
+/// Formula: Units = (Fixed Costs + Target Net Income / (1 - Tax Rate)) / Contribution Margin per Unit
+/// 
+/// # Arguments
+/// * `fixed_costs` - Total fixed costs
+/// * `variable_cost_per_unit` - Variable cost per unit
+/// * `price_per_unit` - Selling price per unit
+/// * `target_net_income` - Profit after tax to aim for
+/// * `tax_rate` - Income tax rate (as a decimal)
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_after_tax_target_units;
+/// 
+/// // $375 after a 25% tax needs $500 before tax
+/// let units = calculate_after_tax_target_units(1000.0, 10.0, 20.0, 375.0, 0.25).unwrap();
+/// assert_eq!(units, 150.0);
+/// ```
+pub fn calculate_after_tax_target_units(
+    fixed_costs: f64,
+    variable_cost_per_unit: f64,
+    price_per_unit: f64,
+    target_net_income: f64,
+    tax_rate: f64
+) -> FinanceResult<f64> {
+    validate_non_negative(tax_rate, "Tax rate")?;
+    if tax_rate >= 1.0 {
+        return Err(FinanceError::InvalidInput(format!("Tax rate must be below 100%: {}", tax_rate)));
+    }
+    
+    calculate_target_profit_units(fixed_costs, variable_cost_per_unit, price_per_unit, target_net_income / (1.0 - tax_rate))
+}
+
+/// Calculates how far sales can fall before reaching break-even
+/// 
+/// Works in units or revenue, as long as both arguments use the same measure.
+/// 
+/// # Arguments
+/// * `sales` - Actual or budgeted sales
+/// * `break_even_sales` - Sales at break-even
+/// 
+/// # Returns
+/// * A tuple containing (margin_of_safety, margin_of_safety_ratio)
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_margin_of_safety;
+/// 
+/// let (margin, ratio) = calculate_margin_of_safety(125.0, 100.0).unwrap();
+/// assert_eq!(margin, 25.0);
+/// assert_eq!(ratio, 0.2);
+/// ```
+pub fn calculate_margin_of_safety(sales: f64, break_even_sales: f64) -> FinanceResult<(f64, f64)> {
+    validate_positive(sales, "Sales")?;
+    validate_non_negative(break_even_sales, "Break-even sales")?;
+    
+    let margin = sales - break_even_sales;
+    Ok((margin, margin / sales))
+}
+
+/// Calculates the degree of operating leverage (DOL) at a sales volume
+/// 
+/// Formula: DOL = Contribution Margin / Operating Income
+/// 
+/// # Arguments
+/// * `units` - Units sold
+/// * `fixed_costs` - Total fixed costs
+/// * `variable_cost_per_unit` - Variable cost per unit
+/// * `price_per_unit` - Selling price per unit
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_degree_of_operating_leverage;
+/// 
+/// // $2,500 contribution margin on $1,500 operating income
+/// let dol = calculate_degree_of_operating_leverage(250.0, 1000.0, 10.0, 20.0).unwrap();
+/// assert!((dol - 2500.0 / 1500.0).abs() < 1e-12);
+/// ```
+pub fn calculate_degree_of_operating_leverage(
+    units: f64,
+    fixed_costs: f64,
+    variable_cost_per_unit: f64,
+    price_per_unit: f64
+) -> FinanceResult<f64> {
+    validate_positive(units, "Units")?;
+    validate_non_negative(fixed_costs, "Fixed costs")?;
+    
+    let contribution_margin = units * (price_per_unit - variable_cost_per_unit);
+    let operating_income = contribution_margin - fixed_costs;
+    
+    if operating_income.abs() < f64::EPSILON {
+        return Err(FinanceError::DivisionByZero);
+    }
+    
+    Ok(contribution_margin / operating_income)
+}
+
+/// A range of unit volumes written `min:max:step`
+#[derive(Debug, Clone, PartialEq)]
+pub struct VolumeRange {
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+}
+
+impl FromStr for VolumeRange {
+    type Err = FinanceError;
+
+    /// Parses a range written as `min:max:step`, e.g. `0:2000:250`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SensitivityRange::from_str(&format!("volume={}", s))
+            .map(|range| VolumeRange { min: range.min, max: range.max, step: range.step })
+    }
+}
+
+impl VolumeRange {
+    /// Volumes from `min` to `max` inclusive in increments of `step`
+    pub fn volumes(&self) -> FinanceResult<Vec<f64>> {
+        validate_non_negative(self.min, "Minimum volume")?;
+        SensitivityRange { parameter: "volume".to_string(), min: self.min, max: self.max, step: self.step }.values()
+    }
+}
+
+/// Revenue, costs and profit at one volume
+#[derive(Debug, Clone)]
+pub struct ProfitVolumePoint {
+    pub units: f64,
+    pub revenue: f64,
+    pub variable_costs: f64,
+    pub total_costs: f64,
+    pub operating_income: f64,
+}
+
+/// Tabulates operating income across a range of volumes
+/// 
+/// # Arguments
+/// * `fixed_costs` - Total fixed costs
+/// * `mix` - The products and their sales mix
+/// * `range` - The volumes to evaluate
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{profit_volume_table, SalesMix};
+/// 
+/// let mix = SalesMix::single(20.0, 10.0).unwrap();
+/// let table = profit_volume_table(1000.0, &mix, &"0:200:100".parse().unwrap()).unwrap();
+/// let profits: Vec<f64> = table.iter().map(|point| point.operating_income).collect();
+/// assert_eq!(profits, vec![-1000.0, 0.0, 1000.0]);
+/// ```
+pub fn profit_volume_table(fixed_costs: f64, mix: &SalesMix, range: &VolumeRange) -> FinanceResult<Vec<ProfitVolumePoint>> {
+    validate_non_negative(fixed_costs, "Fixed costs")?;
+    
+    Ok(range
+        .volumes()?
+        .into_iter()
+        .map(|units| {
+            let revenue = units * mix.price();
+            let variable_costs = units * mix.variable_cost();
+            let total_costs = fixed_costs + variable_costs;
+            ProfitVolumePoint { units, revenue, variable_costs, total_costs, operating_income: revenue - total_costs }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_product_mix() -> SalesMix {
+        SalesMix::new(vec![
+            Product { name: "standard".into(), price: 20.0, variable_cost: 12.0, mix: 3.0 },
+            Product { name: "deluxe".into(), price: 50.0, variable_cost: 30.0, mix: 1.0 },
+        ]).unwrap()
+    }
+
+    #[test]
+    fn test_sales_mix_break_even_covers_fixed_costs() {
+        let mix = two_product_mix();
+        let volumes = calculate_sales_mix_break_even(44000.0, &mix).unwrap();
+        
+        let contribution: f64 = volumes
+            .iter()
+            .zip(mix.products())
+            .map(|(volume, product)| volume.units * (product.price - product.variable_cost))
+            .sum();
+        assert!((contribution - 44000.0).abs() < 1e-6);
+        assert!((mix.contribution_margin_ratio() - 11.0 / 27.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_target_profit_units() {
+        assert_eq!(calculate_target_profit_units(1000.0, 10.0, 20.0, 0.0).unwrap(), 100.0);
+        assert_eq!(calculate_after_tax_target_units(1000.0, 10.0, 20.0, 0.0, 0.3).unwrap(), 100.0);
+        
+        assert!(calculate_target_profit_units(1000.0, 20.0, 20.0, 500.0).is_err());
+        assert!(calculate_after_tax_target_units(1000.0, 10.0, 20.0, 500.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_operating_leverage_predicts_profit_change() {
+        // A 10% rise in volume raises operating income by DOL × 10%
+        let dol = calculate_degree_of_operating_leverage(250.0, 1000.0, 10.0, 20.0).unwrap();
+        let income = |units: f64| units * 10.0 - 1000.0;
+        let change = income(275.0) / income(250.0) - 1.0;
+        assert!((change - dol * 0.10).abs() < 1e-12);
+        
+        assert!(matches!(
+            calculate_degree_of_operating_leverage(100.0, 1000.0, 10.0, 20.0),
+            Err(FinanceError::DivisionByZero)
+        ));
+    }
+
+    #[test]
+    fn test_parse_product_and_range() {
+        let product: Product = "deluxe=50:30:1".parse().unwrap();
+        assert_eq!(product.variable_cost, 30.0);
+        assert!("deluxe=50:30".parse::<Product>().is_err());
+        assert!(SalesMix::new(vec!["free=0:0:1".parse().unwrap()]).is_err());
+        
+        let range: VolumeRange = "0:1000:250".parse().unwrap();
+        assert_eq!(range.volumes().unwrap().len(), 5);
+        assert!("1000:0:250".parse::<VolumeRange>().is_err());
+    }
+}
@@ -0,0 +1,350 @@
+//! Multi-debt payoff planning (snowball, avalanche and custom ordering)
+
+use crate::{FinanceError, FinanceResult, validate_positive, validate_non_negative};
+use serde::Deserialize;
+use std::io::Read;
+
+/// Longest plan simulated before giving up (100 years)
+const MAX_PAYOFF_MONTHS: u32 = 1200;
+
+/// A single debt in a payoff plan
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Debt {
+    pub name: String,
+    pub balance: f64,
+    /// The annual percentage rate as a percentage
+    pub apr: f64,
+    pub minimum_payment: f64,
+}
+
+/// Order in which extra payments are directed at debts
+#[derive(Debug, Clone, PartialEq)]
+pub enum PayoffStrategy {
+    /// Minimum payments only, with no extra payments or rollover
+    MinimumPayments,
+    /// Smallest balance first
+    Snowball,
+    /// Highest APR first
+    Avalanche,
+    /// Debts in the given order of names; unlisted debts follow in input order
+    Custom(Vec<String>),
+}
+
+impl PayoffStrategy {
+    /// Human-readable name of the strategy
+    pub fn name(&self) -> &'static str {
+        match self {
+            PayoffStrategy::MinimumPayments => "Minimum Payments",
+            PayoffStrategy::Snowball => "Snowball",
+            PayoffStrategy::Avalanche => "Avalanche",
+            PayoffStrategy::Custom(_) => "Custom",
+        }
+    }
+}
+
+/// Outcome for one debt under a payoff plan
+#[derive(Debug, Clone)]
+pub struct DebtPayoff {
+    pub name: String,
+    /// Month (1-based) in which the debt is paid off; `None` when it had no balance to begin with
+    pub payoff_month: Option<u32>,
+    pub interest_paid: f64,
+}
+
+/// Month-by-month result of a payoff strategy
+#[derive(Debug, Clone)]
+pub struct PayoffPlan {
+    pub strategy: PayoffStrategy,
+    /// Per-debt results, in input order
+    pub debts: Vec<DebtPayoff>,
+    /// Months until every debt is paid off
+    pub months: u32,
+    pub total_interest: f64,
+}
+
+/// Reads debts from CSV with the header `name,balance,apr,minimum_payment`
+/// 
+/// # Arguments
+/// * `reader` - The CSV source
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::read_debts_csv;
+/// 
+/// let csv = "name,balance,apr,minimum_payment\nVisa,5000,22.9,150\n";
+/// let debts = read_debts_csv(csv.as_bytes()).unwrap();
+/// assert_eq!(debts[0].name, "Visa");
+/// ```
+pub fn read_debts_csv<R: Read>(reader: R) -> FinanceResult<Vec<Debt>> {
+    let mut csv_reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(reader);
+    
+    csv_reader
+        .deserialize()
+        .enumerate()
+        .map(|(index, record)| {
+            record.map_err(|e| FinanceError::InvalidInput(format!("Invalid debt on row {}: {}", index + 1, e)))
+        })
+        .collect()
+}
+
+/// Simulates paying off several debts month by month under a strategy
+/// 
+/// Each month interest accrues, every debt receives its minimum payment and
+/// whatever is left of the budget goes to the highest-priority debt. Minimums
+/// freed up by paid-off debts roll into the extra payment, except under
+/// `PayoffStrategy::MinimumPayments`. Debts that start with a zero balance
+/// need no minimum payment and are reported as already paid.
+/// 
+/// # Arguments
+/// * `debts` - The debts to pay off
+/// * `monthly_budget` - Total amount available for debt payments each month
+/// * `strategy` - The order in which extra payments are applied
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{simulate_debt_payoff, Debt, PayoffStrategy};
+/// 
+/// let debts = vec![
+///     Debt { name: "Card".into(), balance: 3000.0, apr: 24.0, minimum_payment: 90.0 },
+///     Debt { name: "Car".into(), balance: 12000.0, apr: 6.0, minimum_payment: 300.0 },
+/// ];
+/// let plan = simulate_debt_payoff(&debts, 800.0, &PayoffStrategy::Avalanche).unwrap();
+/// assert!(plan.months < 30);
+/// ```
+pub fn simulate_debt_payoff(
+    debts: &[Debt],
+    monthly_budget: f64,
+    strategy: &PayoffStrategy
+) -> FinanceResult<PayoffPlan> {
+    if debts.is_empty() {
+        return Err(FinanceError::InvalidInput("Debts cannot be empty".into()));
+    }
+    
+    for debt in debts {
+        validate_non_negative(debt.balance, &format!("Balance of {}", debt.name))?;
+        validate_non_negative(debt.apr, &format!("APR of {}", debt.name))?;
+        if debt.balance > 0.0 {
+            validate_positive(debt.minimum_payment, &format!("Minimum payment of {}", debt.name))?;
+        } else {
+            validate_non_negative(debt.minimum_payment, &format!("Minimum payment of {}", debt.name))?;
+        }
+    }
+    
+    let total_minimums: f64 = debts.iter().filter(|d| d.balance > 0.0).map(|d| d.minimum_payment).sum();
+    let budget = match strategy {
+        PayoffStrategy::MinimumPayments => total_minimums,
+        _ => monthly_budget,
+    };
+    
+    if budget < total_minimums {
+        return Err(FinanceError::InvalidInput(format!(
+            "Monthly budget {:.2} is below the total minimum payments {:.2}", budget, total_minimums
+        )));
+    }
+    
+    let custom_order = match strategy {
+        PayoffStrategy::Custom(names) => custom_priority(debts, names)?,
+        _ => Vec::new(),
+    };
+    
+    let mut balances: Vec<f64> = debts.iter().map(|d| d.balance).collect();
+    let mut interest_paid = vec![0.0; debts.len()];
+    let mut payoff_months: Vec<Option<u32>> = balances.iter().map(|&b| (b <= 0.0).then_some(0)).collect();
+    let mut month = 0;
+    
+    while payoff_months.iter().any(Option::is_none) {
+        month += 1;
+        if month > MAX_PAYOFF_MONTHS {
+            return Err(FinanceError::InvalidInput(format!(
+                "Debts are not paid off within {} years under the {} strategy",
+                MAX_PAYOFF_MONTHS / 12, strategy.name()
+            )));
+        }
+        
+        // Accrue interest on outstanding balances
+        for (i, debt) in debts.iter().enumerate() {
+            if payoff_months[i].is_none() {
+                let interest = balances[i] * debt.apr / 100.0 / 12.0;
+                balances[i] += interest;
+                interest_paid[i] += interest;
+            }
+        }
+        
+        // Minimum payments on every outstanding debt
+        let mut available = budget;
+        for (i, debt) in debts.iter().enumerate() {
+            if payoff_months[i].is_none() {
+                let payment = debt.minimum_payment.min(balances[i]);
+                balances[i] -= payment;
+                available -= payment;
+            }
+        }
+        
+        // Extra payments in priority order
+        if *strategy != PayoffStrategy::MinimumPayments {
+            let order = match strategy {
+                PayoffStrategy::Custom(_) => custom_order.clone(),
+                _ => priority_order(debts, &balances, strategy),
+            };
+            
+            for i in order {
+                if available <= 0.0 {
+                    break;
+                }
+                if payoff_months[i].is_none() {
+                    let payment = available.min(balances[i]);
+                    balances[i] -= payment;
+                    available -= payment;
+                }
+            }
+        }
+        
+        for (i, balance) in balances.iter().enumerate() {
+            if payoff_months[i].is_none() && *balance <= 1e-9 {
+                payoff_months[i] = Some(month);
+            }
+        }
+    }
+    
+    let payoffs: Vec<DebtPayoff> = debts
+        .iter()
+        .zip(payoff_months.iter().zip(interest_paid.iter()))
+        .map(|(debt, (payoff_month, interest))| DebtPayoff {
+            name: debt.name.clone(),
+            payoff_month: payoff_month.filter(|&month| month > 0),
+            interest_paid: *interest,
+        })
+        .collect();
+    
+    Ok(PayoffPlan {
+        strategy: strategy.clone(),
+        total_interest: interest_paid.iter().sum(),
+        months: month,
+        debts: payoffs,
+    })
+}
+
+/// Debt indices ordered by snowball or avalanche priority
+fn priority_order(debts: &[Debt], balances: &[f64], strategy: &PayoffStrategy) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..debts.len()).collect();
+    
+    match strategy {
+        PayoffStrategy::Snowball => order.sort_by(|&a, &b| {
+            balances[a].total_cmp(&balances[b]).then(debts[b].apr.total_cmp(&debts[a].apr))
+        }),
+        PayoffStrategy::Avalanche => order.sort_by(|&a, &b| {
+            debts[b].apr.total_cmp(&debts[a].apr).then(balances[a].total_cmp(&balances[b]))
+        }),
+        _ => {}
+    }
+    
+    order
+}
+
+/// Debt indices in a user-specified order of names
+fn custom_priority(debts: &[Debt], names: &[String]) -> FinanceResult<Vec<usize>> {
+    let mut order = Vec::with_capacity(debts.len());
+    
+    for name in names {
+        let index = debts
+            .iter()
+            .position(|d| d.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| FinanceError::InvalidInput(format!("Unknown debt in custom order: {}", name)))?;
+        if !order.contains(&index) {
+            order.push(index);
+        }
+    }
+    
+    for index in 0..debts.len() {
+        if !order.contains(&index) {
+            order.push(index);
+        }
+    }
+    
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_debts() -> Vec<Debt> {
+        vec![
+            Debt { name: "Card".into(), balance: 4000.0, apr: 24.0, minimum_payment: 120.0 },
+            Debt { name: "Store".into(), balance: 800.0, apr: 18.0, minimum_payment: 40.0 },
+            Debt { name: "Car".into(), balance: 9000.0, apr: 5.0, minimum_payment: 250.0 },
+        ]
+    }
+
+    #[test]
+    fn test_read_debts_csv() {
+        let csv = "name, balance, apr, minimum_payment\nCard, 4000, 24, 120\nCar,9000,5,250\n";
+        let debts = read_debts_csv(csv.as_bytes()).unwrap();
+        assert_eq!(debts.len(), 2);
+        assert_eq!(debts[1], Debt { name: "Car".into(), balance: 9000.0, apr: 5.0, minimum_payment: 250.0 });
+        
+        assert!(read_debts_csv("name,balance\nCard,abc\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_snowball_pays_smallest_first() {
+        let plan = simulate_debt_payoff(&sample_debts(), 1000.0, &PayoffStrategy::Snowball).unwrap();
+        let store = plan.debts[1].payoff_month;
+        assert!(store < plan.debts[0].payoff_month);
+        assert!(store < plan.debts[2].payoff_month);
+    }
+
+    #[test]
+    fn test_avalanche_minimizes_interest() {
+        let snowball = simulate_debt_payoff(&sample_debts(), 1000.0, &PayoffStrategy::Snowball).unwrap();
+        let avalanche = simulate_debt_payoff(&sample_debts(), 1000.0, &PayoffStrategy::Avalanche).unwrap();
+        let minimums = simulate_debt_payoff(&sample_debts(), 1000.0, &PayoffStrategy::MinimumPayments).unwrap();
+        
+        assert!(avalanche.total_interest <= snowball.total_interest);
+        assert!(avalanche.months < minimums.months);
+        assert!(avalanche.total_interest < minimums.total_interest);
+        
+        // Card has the highest APR, so it is paid off first
+        assert!(avalanche.debts[0].payoff_month < avalanche.debts[2].payoff_month);
+        
+        let per_debt: f64 = avalanche.debts.iter().map(|d| d.interest_paid).sum();
+        assert!((per_debt - avalanche.total_interest).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_custom_order() {
+        let strategy = PayoffStrategy::Custom(vec!["car".into()]);
+        let plan = simulate_debt_payoff(&sample_debts(), 1500.0, &strategy).unwrap();
+        assert!(plan.debts[2].payoff_month < plan.debts[0].payoff_month);
+        
+        let unknown = PayoffStrategy::Custom(vec!["Boat".into()]);
+        assert!(simulate_debt_payoff(&sample_debts(), 1500.0, &unknown).is_err());
+    }
+
+    #[test]
+    fn test_budget_validation() {
+        // Budget below the sum of minimums
+        assert!(simulate_debt_payoff(&sample_debts(), 300.0, &PayoffStrategy::Avalanche).is_err());
+        
+        // Minimum payment that never covers the interest
+        let debts = vec![Debt { name: "Card".into(), balance: 10000.0, apr: 30.0, minimum_payment: 100.0 }];
+        assert!(simulate_debt_payoff(&debts, 100.0, &PayoffStrategy::Avalanche).is_err());
+        
+        assert!(simulate_debt_payoff(&[], 100.0, &PayoffStrategy::Avalanche).is_err());
+    }
+    
+    #[test]
+    fn test_zero_balance_debts_are_already_paid() {
+        let mut debts = sample_debts();
+        debts.push(Debt { name: "Closed Card".into(), balance: 0.0, apr: 20.0, minimum_payment: 0.0 });
+        
+        let plan = simulate_debt_payoff(&debts, 410.0, &PayoffStrategy::Avalanche).unwrap();
+        assert_eq!(plan.debts[3].payoff_month, None);
+        assert_eq!(plan.debts[3].interest_paid, 0.0);
+        assert!(plan.debts[..3].iter().all(|debt| debt.payoff_month.is_some()));
+        
+        // A balance still needs a minimum payment
+        debts[0].minimum_payment = 0.0;
+        assert!(simulate_debt_payoff(&debts, 1000.0, &PayoffStrategy::Avalanche).is_err());
+    }
+}
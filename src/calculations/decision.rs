@@ -0,0 +1,456 @@
+//! Rent-vs-buy and lease-vs-buy decision models
+
+use crate::{FinanceError, FinanceResult, validate_positive, validate_non_negative, validate_fraction};
+use crate::calculations::{calculate_future_value, calculate_loan_payment, generate_amortization_schedule, AmortizationPayment};
+use chrono::NaiveDate;
+
+/// Assumptions for comparing buying a home with renting
+#[derive(Debug, Clone)]
+pub struct RentVsBuyInputs {
+    pub home_price: f64,
+    /// Down payment as a fraction of the price (0.20 = 20%)
+    pub down_payment_rate: f64,
+    /// Closing costs as a fraction of the price
+    pub closing_cost_rate: f64,
+    /// The annual mortgage rate as a percentage
+    pub mortgage_rate: f64,
+    pub mortgage_term_years: i32,
+    /// Annual property tax as a fraction of the home value
+    pub property_tax_rate: f64,
+    /// Annual maintenance as a fraction of the home value
+    pub maintenance_rate: f64,
+    /// Annual home price appreciation (as a decimal)
+    pub appreciation_rate: f64,
+    /// Selling costs as a fraction of the sale price
+    pub selling_cost_rate: f64,
+    pub monthly_rent: f64,
+    /// Annual rent increase (as a decimal)
+    pub rent_inflation: f64,
+    /// Annual return on invested cash, the opportunity cost of buying (as a decimal)
+    pub investment_return: f64,
+    pub years: u32,
+}
+
+/// Assumptions for comparing leasing an asset with buying it on credit
+#[derive(Debug, Clone)]
+pub struct LeaseVsBuyInputs {
+    pub price: f64,
+    /// Cash paid at signing of each lease (capitalized cost reduction)
+    pub lease_down_payment: f64,
+    pub lease_term_months: u32,
+    /// Residual value as a fraction of the price at lease end
+    pub residual_rate: f64,
+    /// The lease money factor (APR / 2400)
+    pub money_factor: f64,
+    /// Down payment when buying
+    pub purchase_down_payment: f64,
+    /// The annual loan rate as a percentage
+    pub loan_rate: f64,
+    pub loan_term_years: i32,
+    /// Annual decline in the asset's market value (as a decimal)
+    pub depreciation_rate: f64,
+    /// Annual return on invested cash, the opportunity cost of either choice (as a decimal)
+    pub investment_return: f64,
+    pub years: u32,
+}
+
+/// Net worth of the buy and alternative choices at the end of a year
+#[derive(Debug, Clone)]
+pub struct NetWorthComparison {
+    pub year: u32,
+    pub buy_net_worth: f64,
+    /// Net worth when renting or leasing instead
+    pub alternative_net_worth: f64,
+    /// Total cash spent on the purchase during the year
+    pub buy_outflow: f64,
+    /// Total cash spent on renting or leasing during the year
+    pub alternative_outflow: f64,
+}
+
+impl NetWorthComparison {
+    /// Buy net worth minus alternative net worth
+    pub fn advantage(&self) -> f64 {
+        self.buy_net_worth - self.alternative_net_worth
+    }
+}
+
+/// Year-by-year result of a buy-versus-alternative decision
+#[derive(Debug, Clone)]
+pub struct DecisionAnalysis {
+    pub years: Vec<NetWorthComparison>,
+    /// First year from which buying stays ahead, if it ever does
+    pub crossover_year: Option<u32>,
+}
+
+/// Calculates a monthly lease payment from the money factor and residual
+/// 
+/// Formula: Payment = (Cap Cost - Residual) / Term + (Cap Cost + Residual) × Money Factor
+/// 
+/// # Arguments
+/// * `capitalized_cost` - The price less any capitalized cost reduction
+/// * `residual_value` - The asset's value at lease end
+/// * `term_months` - The lease term in months
+/// * `money_factor` - The lease money factor (APR / 2400)
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_lease_payment;
+/// 
+/// let payment = calculate_lease_payment(30000.0, 18000.0, 36, 0.0025).unwrap();
+/// assert!((payment - 453.33).abs() < 0.01);
+/// ```
+pub fn calculate_lease_payment(
+    capitalized_cost: f64,
+    residual_value: f64,
+    term_months: u32,
+    money_factor: f64
+) -> FinanceResult<f64> {
+    validate_positive(capitalized_cost, "Capitalized cost")?;
+    validate_non_negative(residual_value, "Residual value")?;
+    validate_non_negative(money_factor, "Money factor")?;
+    
+    if term_months == 0 {
+        return Err(FinanceError::InvalidInput("Lease term must be positive".into()));
+    }
+    
+    let depreciation_fee = (capitalized_cost - residual_value) / term_months as f64;
+    let finance_fee = (capitalized_cost + residual_value) * money_factor;
+    
+    Ok(depreciation_fee + finance_fee)
+}
+
+/// Compares the net worth of buying a home against renting, year by year
+/// 
+/// Both households start with the buyer's upfront cash (down payment plus
+/// closing costs); the renter invests it. Each year, whichever choice costs
+/// less invests the difference at the investment return. The buyer's net
+/// worth is the home value after selling costs, less the mortgage balance,
+/// plus their investments.
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{analyze_rent_vs_buy, RentVsBuyInputs};
+/// 
+/// let inputs = RentVsBuyInputs {
+///     home_price: 400000.0, down_payment_rate: 0.20, closing_cost_rate: 0.03,
+///     mortgage_rate: 6.5, mortgage_term_years: 30, property_tax_rate: 0.011,
+///     maintenance_rate: 0.01, appreciation_rate: 0.04, selling_cost_rate: 0.06,
+///     monthly_rent: 2200.0, rent_inflation: 0.03, investment_return: 0.06, years: 15,
+/// };
+/// let analysis = analyze_rent_vs_buy(&inputs).unwrap();
+/// assert_eq!(analysis.years.len(), 15);
+/// ```
+pub fn analyze_rent_vs_buy(inputs: &RentVsBuyInputs) -> FinanceResult<DecisionAnalysis> {
+    validate_positive(inputs.home_price, "Home price")?;
+    validate_positive(inputs.monthly_rent, "Monthly rent")?;
+    for (value, name) in [
+        (inputs.closing_cost_rate, "Closing cost rate"),
+        (inputs.property_tax_rate, "Property tax rate"),
+        (inputs.maintenance_rate, "Maintenance rate"),
+        (inputs.appreciation_rate, "Appreciation rate"),
+        (inputs.selling_cost_rate, "Selling cost rate"),
+        (inputs.rent_inflation, "Rent inflation"),
+        (inputs.investment_return, "Investment return"),
+    ] {
+        validate_non_negative(value, name)?;
+    }
+    
+    validate_fraction(inputs.down_payment_rate, "Down payment rate")?;
+    
+    if inputs.years == 0 {
+        return Err(FinanceError::InvalidInput("Years must be positive".into()));
+    }
+    
+    let down_payment = inputs.home_price * inputs.down_payment_rate;
+    let loan_amount = inputs.home_price - down_payment;
+    let upfront_cash = down_payment + inputs.home_price * inputs.closing_cost_rate;
+    
+    let (monthly_payment, schedule) = if loan_amount > 0.0 {
+        (
+            calculate_loan_payment(loan_amount, inputs.mortgage_rate, inputs.mortgage_term_years as f64)?,
+            generate_amortization_schedule(loan_amount, inputs.mortgage_rate, inputs.mortgage_term_years, NaiveDate::default())?,
+        )
+    } else {
+        (0.0, Vec::new())
+    };
+    
+    let mut buyer_investments = 0.0;
+    let mut renter_investments = upfront_cash;
+    let mut years = Vec::with_capacity(inputs.years as usize);
+    
+    for year in 1..=inputs.years {
+        // Values at the start of the year drive the year's carrying costs
+        let start_value = calculate_future_value(inputs.home_price, inputs.appreciation_rate, (year - 1) as f64)?;
+        let end_value = calculate_future_value(inputs.home_price, inputs.appreciation_rate, year as f64)?;
+        
+        let mortgage_payments = monthly_payment * payments_in_year(&schedule, year) as f64;
+        let buy_outflow = mortgage_payments + start_value * (inputs.property_tax_rate + inputs.maintenance_rate);
+        let rent_outflow = calculate_future_value(inputs.monthly_rent, inputs.rent_inflation, (year - 1) as f64)? * 12.0;
+        
+        buyer_investments *= 1.0 + inputs.investment_return;
+        renter_investments *= 1.0 + inputs.investment_return;
+        if buy_outflow > rent_outflow {
+            renter_investments += buy_outflow - rent_outflow;
+        } else {
+            buyer_investments += rent_outflow - buy_outflow;
+        }
+        
+        let home_equity = end_value * (1.0 - inputs.selling_cost_rate) - balance_after_year(&schedule, year);
+        
+        years.push(NetWorthComparison {
+            year,
+            buy_net_worth: home_equity + buyer_investments,
+            alternative_net_worth: renter_investments,
+            buy_outflow,
+            alternative_outflow: rent_outflow,
+        });
+    }
+    
+    Ok(DecisionAnalysis {
+        crossover_year: crossover_year(&years),
+        years,
+    })
+}
+
+/// Compares the net worth of buying an asset on credit against leasing it, year by year
+/// 
+/// Leases are assumed to be renewed on the same terms, including the down
+/// payment, whenever the horizon outlasts the lease term. Both choices start
+/// with the same cash; the cheaper choice each year invests the difference.
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{analyze_lease_vs_buy, LeaseVsBuyInputs};
+/// 
+/// let inputs = LeaseVsBuyInputs {
+///     price: 35000.0, lease_down_payment: 2000.0, lease_term_months: 36,
+///     residual_rate: 0.55, money_factor: 0.0025, purchase_down_payment: 5000.0,
+///     loan_rate: 6.0, loan_term_years: 5, depreciation_rate: 0.15,
+///     investment_return: 0.05, years: 6,
+/// };
+/// let analysis = analyze_lease_vs_buy(&inputs).unwrap();
+/// assert_eq!(analysis.years.len(), 6);
+/// ```
+pub fn analyze_lease_vs_buy(inputs: &LeaseVsBuyInputs) -> FinanceResult<DecisionAnalysis> {
+    validate_positive(inputs.price, "Price")?;
+    validate_non_negative(inputs.lease_down_payment, "Lease down payment")?;
+    validate_non_negative(inputs.purchase_down_payment, "Purchase down payment")?;
+    validate_non_negative(inputs.depreciation_rate, "Depreciation rate")?;
+    validate_non_negative(inputs.investment_return, "Investment return")?;
+    
+    validate_fraction(inputs.residual_rate, "Residual rate")?;
+    
+    if inputs.depreciation_rate >= 1.0 {
+        return Err(FinanceError::InvalidInput("Depreciation rate must be less than 1".into()));
+    }
+    
+    if inputs.purchase_down_payment > inputs.price {
+        return Err(FinanceError::InvalidInput("Purchase down payment cannot exceed the price".into()));
+    }
+    
+    if inputs.years == 0 {
+        return Err(FinanceError::InvalidInput("Years must be positive".into()));
+    }
+    
+    let residual_value = inputs.price * inputs.residual_rate;
+    let lease_payment = calculate_lease_payment(
+        inputs.price - inputs.lease_down_payment,
+        residual_value,
+        inputs.lease_term_months,
+        inputs.money_factor
+    )?;
+    
+    let loan_amount = inputs.price - inputs.purchase_down_payment;
+    let (loan_payment, schedule) = if loan_amount > 0.0 {
+        (
+            calculate_loan_payment(loan_amount, inputs.loan_rate, inputs.loan_term_years as f64)?,
+            generate_amortization_schedule(loan_amount, inputs.loan_rate, inputs.loan_term_years, NaiveDate::default())?,
+        )
+    } else {
+        (0.0, Vec::new())
+    };
+    
+    // The buyer's down payment is spent at signing; the lessee starts with the difference invested
+    let mut buyer_investments = 0.0;
+    let mut lessee_investments = inputs.purchase_down_payment - inputs.lease_down_payment;
+    let mut years = Vec::with_capacity(inputs.years as usize);
+    
+    for year in 1..=inputs.years {
+        let buy_outflow = loan_payment * payments_in_year(&schedule, year) as f64;
+        
+        // Lease payments this year, plus down payments for any renewals signed in it
+        let mut lease_outflow = 0.0;
+        for month in (year - 1) * 12..year * 12 {
+            if month > 0 && month.is_multiple_of(inputs.lease_term_months) {
+                lease_outflow += inputs.lease_down_payment;
+            }
+            lease_outflow += lease_payment;
+        }
+        
+        buyer_investments *= 1.0 + inputs.investment_return;
+        lessee_investments *= 1.0 + inputs.investment_return;
+        if buy_outflow > lease_outflow {
+            lessee_investments += buy_outflow - lease_outflow;
+        } else {
+            buyer_investments += lease_outflow - buy_outflow;
+        }
+        
+        let asset_value = inputs.price * (1.0 - inputs.depreciation_rate).powi(year as i32);
+        
+        years.push(NetWorthComparison {
+            year,
+            buy_net_worth: asset_value - balance_after_year(&schedule, year) + buyer_investments,
+            alternative_net_worth: lessee_investments,
+            buy_outflow,
+            alternative_outflow: lease_outflow,
+        });
+    }
+    
+    Ok(DecisionAnalysis {
+        crossover_year: crossover_year(&years),
+        years,
+    })
+}
+
+/// Number of scheduled payments falling in the given year of the loan
+fn payments_in_year(schedule: &[AmortizationPayment], year: u32) -> usize {
+    let start = ((year - 1) * 12) as usize;
+    schedule.len().saturating_sub(start).min(12)
+}
+
+/// Loan balance outstanding at the end of the given year
+fn balance_after_year(schedule: &[AmortizationPayment], year: u32) -> f64 {
+    let index = (year * 12) as usize;
+    if index == 0 || index > schedule.len() {
+        0.0
+    } else {
+        schedule[index - 1].remaining_balance
+    }
+}
+
+/// First year from which buying is ahead in every remaining year
+fn crossover_year(years: &[NetWorthComparison]) -> Option<u32> {
+    let last_behind = years.iter().rposition(|y| y.advantage() < 0.0);
+    
+    match last_behind {
+        None => years.first().map(|y| y.year),
+        Some(index) => years.get(index + 1).map(|y| y.year),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rent_inputs() -> RentVsBuyInputs {
+        RentVsBuyInputs {
+            home_price: 400000.0,
+            down_payment_rate: 0.20,
+            closing_cost_rate: 0.03,
+            mortgage_rate: 6.5,
+            mortgage_term_years: 30,
+            property_tax_rate: 0.011,
+            maintenance_rate: 0.01,
+            appreciation_rate: 0.04,
+            selling_cost_rate: 0.06,
+            monthly_rent: 2200.0,
+            rent_inflation: 0.03,
+            investment_return: 0.06,
+            years: 30,
+        }
+    }
+
+    fn lease_inputs() -> LeaseVsBuyInputs {
+        LeaseVsBuyInputs {
+            price: 35000.0,
+            lease_down_payment: 2000.0,
+            lease_term_months: 36,
+            residual_rate: 0.55,
+            money_factor: 0.0025,
+            purchase_down_payment: 5000.0,
+            loan_rate: 6.0,
+            loan_term_years: 5,
+            depreciation_rate: 0.15,
+            investment_return: 0.05,
+            years: 10,
+        }
+    }
+
+    #[test]
+    fn test_lease_payment() {
+        // (30000 - 18000) / 36 + (30000 + 18000) * 0.0025
+        let payment = calculate_lease_payment(30000.0, 18000.0, 36, 0.0025).unwrap();
+        assert!((payment - (333.333333 + 120.0)).abs() < 1e-4);
+        
+        assert!(calculate_lease_payment(30000.0, 18000.0, 0, 0.0025).is_err());
+        assert!(calculate_lease_payment(30000.0, 18000.0, 36, -0.001).is_err());
+    }
+
+    #[test]
+    fn test_rent_vs_buy_first_year() {
+        let inputs = rent_inputs();
+        let analysis = analyze_rent_vs_buy(&inputs).unwrap();
+        let first = &analysis.years[0];
+        
+        let payment = calculate_loan_payment(320000.0, 6.5, 30.0).unwrap();
+        let expected_outflow = payment * 12.0 + 400000.0 * 0.021;
+        assert!((first.buy_outflow - expected_outflow).abs() < 1e-6);
+        assert!((first.alternative_outflow - 26400.0).abs() < 1e-6);
+        
+        // Buying costs more up front, so the renter starts ahead
+        assert!(first.advantage() < 0.0);
+    }
+
+    #[test]
+    fn test_rent_vs_buy_crossover() {
+        let analysis = analyze_rent_vs_buy(&rent_inputs()).unwrap();
+        let crossover = analysis.crossover_year.unwrap();
+        
+        assert!(crossover > 1 && crossover <= 30);
+        assert!(analysis.years[crossover as usize - 2].advantage() < 0.0);
+        assert!(analysis.years[crossover as usize - 1..].iter().all(|y| y.advantage() >= 0.0));
+        
+        // Cheap rent with no appreciation never favours buying
+        let inputs = RentVsBuyInputs { monthly_rent: 800.0, appreciation_rate: 0.0, ..rent_inputs() };
+        assert_eq!(analyze_rent_vs_buy(&inputs).unwrap().crossover_year, None);
+    }
+
+    #[test]
+    fn test_rent_vs_buy_cash_purchase() {
+        let inputs = RentVsBuyInputs { down_payment_rate: 1.0, ..rent_inputs() };
+        let analysis = analyze_rent_vs_buy(&inputs).unwrap();
+        assert!((analysis.years[0].buy_outflow - 400000.0 * 0.021).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rent_vs_buy_invalid_inputs() {
+        assert!(analyze_rent_vs_buy(&RentVsBuyInputs { down_payment_rate: 1.5, ..rent_inputs() }).is_err());
+        assert!(analyze_rent_vs_buy(&RentVsBuyInputs { years: 0, ..rent_inputs() }).is_err());
+        assert!(analyze_rent_vs_buy(&RentVsBuyInputs { monthly_rent: 0.0, ..rent_inputs() }).is_err());
+    }
+
+    #[test]
+    fn test_lease_vs_buy() {
+        let inputs = lease_inputs();
+        let analysis = analyze_lease_vs_buy(&inputs).unwrap();
+        assert_eq!(analysis.years.len(), 10);
+        
+        // A renewal down payment lands in year 4 (month 36)
+        let lease_payment = calculate_lease_payment(33000.0, 19250.0, 36, 0.0025).unwrap();
+        assert!((analysis.years[0].alternative_outflow - lease_payment * 12.0).abs() < 1e-6);
+        assert!((analysis.years[3].alternative_outflow - (lease_payment * 12.0 + 2000.0)).abs() < 1e-6);
+        
+        // The loan is paid off after five years
+        assert_eq!(analysis.years[5].buy_outflow, 0.0);
+        
+        // Owning outright eventually wins over perpetual leasing
+        assert!(analysis.crossover_year.is_some());
+    }
+
+    #[test]
+    fn test_lease_vs_buy_invalid_inputs() {
+        assert!(analyze_lease_vs_buy(&LeaseVsBuyInputs { residual_rate: 1.2, ..lease_inputs() }).is_err());
+        assert!(analyze_lease_vs_buy(&LeaseVsBuyInputs { depreciation_rate: 1.0, ..lease_inputs() }).is_err());
+        assert!(analyze_lease_vs_buy(&LeaseVsBuyInputs { purchase_down_payment: 50000.0, ..lease_inputs() }).is_err());
+    }
+}
@@ -0,0 +1,288 @@
+//! Dividend discount models: Gordon growth, multi-stage and H-model
+
+use crate::{FinanceError, FinanceResult, validate_finite, validate_non_negative, validate_positive};
+use crate::calculations::goal_seek;
+
+/// A dividend paid in one year of the explicit growth stage
+#[derive(Debug, Clone)]
+pub struct ProjectedDividend {
+    pub year: u32,
+    pub growth: f64,
+    pub dividend: f64,
+    pub present_value: f64,
+}
+
+/// Value of a share under a multi-stage dividend discount model
+#[derive(Debug, Clone)]
+pub struct MultiStageValuation {
+    pub dividends: Vec<ProjectedDividend>,
+    /// Value at the end of the explicit stage of dividends growing at the terminal rate
+    pub terminal_value: f64,
+    pub present_value_of_terminal: f64,
+    pub value: f64,
+}
+
+/// A dividend discount model and its growth assumptions
+#[derive(Debug, Clone, PartialEq)]
+pub enum DividendModel {
+    /// Dividends grow at a constant rate forever
+    Gordon { growth: f64 },
+    /// Explicit growth rates for the first years, then constant terminal growth
+    MultiStage { high_growth: Vec<f64>, terminal_growth: f64 },
+    /// Growth declines linearly from `short_term_growth` to `long_term_growth` over `2 × half_life` years
+    HModel { short_term_growth: f64, long_term_growth: f64, half_life: f64 },
+}
+
+impl DividendModel {
+    /// Value of a share given the dividend just paid and the required return
+    pub fn value(&self, current_dividend: f64, required_return: f64) -> FinanceResult<f64> {
+        match self {
+            DividendModel::Gordon { growth } => {
+                calculate_gordon_growth_value(current_dividend, required_return, *growth)
+            }
+            DividendModel::MultiStage { high_growth, terminal_growth } => {
+                calculate_multi_stage_ddm(current_dividend, high_growth, *terminal_growth, required_return)
+                    .map(|valuation| valuation.value)
+            }
+            DividendModel::HModel { short_term_growth, long_term_growth, half_life } => {
+                calculate_h_model_value(current_dividend, *short_term_growth, *long_term_growth, *half_life, required_return)
+            }
+        }
+    }
+
+    /// Growth rate implied by a market price
+    /// 
+    /// Solves for the Gordon growth rate, the rate applied to every explicit
+    /// year of a multi-stage model, or the H-model's short-term growth, with
+    /// the model's other assumptions held fixed.
+    /// 
+    /// # Examples
+    /// ```
+    /// use rusty_finance::calculations::DividendModel;
+    /// 
+    /// // A $2 dividend priced at $40 with a 9% required return implies 3.85% growth
+    /// let model = DividendModel::Gordon { growth: 0.05 };
+    /// let growth = model.implied_growth(2.0, 0.09, 40.0).unwrap();
+    /// assert!((growth - (40.0 * 0.09 - 2.0) / 42.0).abs() < 1e-9);
+    /// ```
+    pub fn implied_growth(&self, current_dividend: f64, required_return: f64, price: f64) -> FinanceResult<f64> {
+        validate_positive(price, "Price")?;
+        
+        goal_seek(
+            |growth| self.with_solved_growth(growth).value(current_dividend, required_return),
+            price,
+            self.solved_growth()
+        )
+    }
+    
+    /// Current value of the growth rate `implied_growth` solves for
+    fn solved_growth(&self) -> f64 {
+        match self {
+            DividendModel::Gordon { growth } => *growth,
+            DividendModel::MultiStage { high_growth, .. } => {
+                high_growth.iter().sum::<f64>() / high_growth.len().max(1) as f64
+            }
+            DividendModel::HModel { short_term_growth, .. } => *short_term_growth,
+        }
+    }
+    
+    /// Copy of the model with the solved-for growth rate replaced
+    fn with_solved_growth(&self, growth: f64) -> DividendModel {
+        match self {
+            DividendModel::Gordon { .. } => DividendModel::Gordon { growth },
+            DividendModel::MultiStage { high_growth, terminal_growth } => DividendModel::MultiStage {
+                high_growth: vec![growth; high_growth.len()],
+                terminal_growth: *terminal_growth,
+            },
+            DividendModel::HModel { long_term_growth, half_life, .. } => DividendModel::HModel {
+                short_term_growth: growth,
+                long_term_growth: *long_term_growth,
+                half_life: *half_life,
+            },
+        }
+    }
+}
+
+/// Values a share whose dividends grow at a constant rate forever
+/// 
+/// Formula: Value = D0 × (1 + g) / (r - g)
+/// 
+/// # Arguments
+/// * `current_dividend` - The annual dividend just paid (D0)
+/// * `required_return` - The required return on equity (as a decimal)
+/// * `growth` - The constant dividend growth rate (as a decimal)
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_gordon_growth_value;
+/// 
+/// let value = calculate_gordon_growth_value(2.0, 0.09, 0.04).unwrap();
+/// assert!((value - 41.6).abs() < 1e-9);
+/// ```
+pub fn calculate_gordon_growth_value(current_dividend: f64, required_return: f64, growth: f64) -> FinanceResult<f64> {
+    validate_dividend_inputs(current_dividend, required_return)?;
+    validate_growth(growth, required_return)?;
+    
+    Ok(current_dividend * (1.0 + growth) / (required_return - growth))
+}
+
+/// Values a share with explicit dividend growth rates followed by constant terminal growth
+/// 
+/// A two-stage model is the special case of one growth rate repeated for the
+/// high-growth years.
+/// 
+/// # Arguments
+/// * `current_dividend` - The annual dividend just paid (D0)
+/// * `high_growth` - Growth rate for each explicit year
+/// * `terminal_growth` - Growth rate after the explicit years
+/// * `required_return` - The required return on equity (as a decimal)
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{calculate_multi_stage_ddm, calculate_gordon_growth_value};
+/// 
+/// // With the same rate in both stages the model reduces to Gordon growth
+/// let staged = calculate_multi_stage_ddm(2.0, &[0.04; 5], 0.04, 0.09).unwrap();
+/// let gordon = calculate_gordon_growth_value(2.0, 0.09, 0.04).unwrap();
+/// assert!((staged.value - gordon).abs() < 1e-9);
+/// ```
+pub fn calculate_multi_stage_ddm(
+    current_dividend: f64,
+    high_growth: &[f64],
+    terminal_growth: f64,
+    required_return: f64
+) -> FinanceResult<MultiStageValuation> {
+    validate_dividend_inputs(current_dividend, required_return)?;
+    validate_growth(terminal_growth, required_return)?;
+    
+    let mut dividend = current_dividend;
+    let mut dividends = Vec::with_capacity(high_growth.len());
+    
+    for (index, &growth) in high_growth.iter().enumerate() {
+        validate_finite(growth, "High growth rate")?;
+        if growth <= -1.0 {
+            return Err(FinanceError::InvalidInput(format!("Growth rate must be greater than -100%: {}", growth)));
+        }
+        
+        dividend *= 1.0 + growth;
+        let year = index as u32 + 1;
+        dividends.push(ProjectedDividend {
+            year,
+            growth,
+            dividend,
+            present_value: dividend / (1.0 + required_return).powi(year as i32),
+        });
+    }
+    
+    let terminal_value = dividend * (1.0 + terminal_growth) / (required_return - terminal_growth);
+    let present_value_of_terminal = terminal_value / (1.0 + required_return).powi(high_growth.len() as i32);
+    let value = dividends.iter().map(|d| d.present_value).sum::<f64>() + present_value_of_terminal;
+    
+    Ok(MultiStageValuation { dividends, terminal_value, present_value_of_terminal, value })
+}
+
+/// Values a share with the H-model, where growth fades linearly to a long-term rate
+/// 
+/// Formula: Value = D0 × (1 + gL) / (r - gL) + D0 × H × (gS - gL) / (r - gL)
+/// 
+/// # Arguments
+/// * `current_dividend` - The annual dividend just paid (D0)
+/// * `short_term_growth` - Growth rate today (gS)
+/// * `long_term_growth` - Growth rate once the fade is complete (gL)
+/// * `half_life` - Half the length of the fade period in years (H)
+/// * `required_return` - The required return on equity (as a decimal)
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_h_model_value;
+/// 
+/// // Growth fading from 12% to 4% over 10 years
+/// let value = calculate_h_model_value(2.0, 0.12, 0.04, 5.0, 0.09).unwrap();
+/// assert!((value - 57.6).abs() < 1e-9);
+/// ```
+pub fn calculate_h_model_value(
+    current_dividend: f64,
+    short_term_growth: f64,
+    long_term_growth: f64,
+    half_life: f64,
+    required_return: f64
+) -> FinanceResult<f64> {
+    validate_dividend_inputs(current_dividend, required_return)?;
+    validate_growth(long_term_growth, required_return)?;
+    validate_finite(short_term_growth, "Short-term growth")?;
+    validate_non_negative(half_life, "Half-life")?;
+    
+    let spread = required_return - long_term_growth;
+    Ok(current_dividend * (1.0 + long_term_growth) / spread
+        + current_dividend * half_life * (short_term_growth - long_term_growth) / spread)
+}
+
+fn validate_dividend_inputs(current_dividend: f64, required_return: f64) -> FinanceResult<()> {
+    validate_non_negative(current_dividend, "Current dividend")?;
+    validate_finite(required_return, "Required return")
+}
+
+/// Perpetual growth must be above -100% and below the required return
+fn validate_growth(growth: f64, required_return: f64) -> FinanceResult<()> {
+    validate_finite(growth, "Growth rate")?;
+    
+    if growth <= -1.0 {
+        return Err(FinanceError::InvalidInput(format!("Growth rate must be greater than -100%: {}", growth)));
+    }
+    
+    if growth >= required_return {
+        return Err(FinanceError::InvalidInput(format!(
+            "Required return ({}) must exceed the perpetual growth rate ({})", required_return, growth
+        )));
+    }
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gordon_growth() {
+        assert!((calculate_gordon_growth_value(1.0, 0.10, 0.0).unwrap() - 10.0).abs() < 1e-12);
+        assert!(calculate_gordon_growth_value(1.0, 0.05, 0.05).is_err());
+        assert!(calculate_gordon_growth_value(-1.0, 0.10, 0.02).is_err());
+    }
+
+    #[test]
+    fn test_two_stage_ddm() {
+        // $1 growing 20% for two years then 5%, at a 10% required return
+        let valuation = calculate_multi_stage_ddm(1.0, &[0.20, 0.20], 0.05, 0.10).unwrap();
+        
+        assert!((valuation.dividends[1].dividend - 1.44).abs() < 1e-12);
+        assert!((valuation.terminal_value - 1.44 * 1.05 / 0.05).abs() < 1e-9);
+        let expected = 1.2 / 1.1 + 1.44 / 1.21 + valuation.terminal_value / 1.21;
+        assert!((valuation.value - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_h_model_without_fade_is_gordon() {
+        let h_model = calculate_h_model_value(2.0, 0.04, 0.04, 5.0, 0.09).unwrap();
+        let gordon = calculate_gordon_growth_value(2.0, 0.09, 0.04).unwrap();
+        assert!((h_model - gordon).abs() < 1e-9);
+        
+        // Faster early growth is worth more than steady growth
+        assert!(calculate_h_model_value(2.0, 0.10, 0.04, 5.0, 0.09).unwrap() > gordon);
+    }
+
+    #[test]
+    fn test_implied_growth_reprices_the_share() {
+        let models = vec![
+            DividendModel::Gordon { growth: 0.03 },
+            DividendModel::MultiStage { high_growth: vec![0.10; 5], terminal_growth: 0.03 },
+            DividendModel::HModel { short_term_growth: 0.10, long_term_growth: 0.03, half_life: 4.0 },
+        ];
+        
+        for model in models {
+            let growth = model.implied_growth(2.0, 0.09, 60.0).unwrap();
+            let repriced = model.with_solved_growth(growth).value(2.0, 0.09).unwrap();
+            assert!((repriced - 60.0).abs() < 1e-6);
+        }
+    }
+}
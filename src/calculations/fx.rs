@@ -0,0 +1,512 @@
+//! Foreign exchange: spot and cross rates, forwards by covered interest parity and cash-flow conversion
+
+use crate::{FinanceError, FinanceResult, to_decimal, validate_finite, validate_non_negative, validate_positive};
+use crate::money::{Currency, Money};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io::Read;
+
+/// Days per year used to turn dates into tenors
+const DAYS_PER_YEAR: f64 = 365.0;
+
+/// A spot rate: one unit of `base` buys `rate` units of `quote`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct SpotRate {
+    pub base: Currency,
+    pub quote: Currency,
+    pub rate: f64,
+}
+
+/// A point on a currency's zero curve
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct CurvePoint {
+    pub tenor_years: f64,
+    /// Annually compounded zero rate (as a decimal)
+    pub rate: f64,
+}
+
+/// A spot rate between two currencies and the currency it was crossed through, if any
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpotQuote {
+    pub rate: f64,
+    pub via: Option<Currency>,
+}
+
+/// How dated cash flows are converted to the base currency
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FxConversion {
+    /// Today's spot rate for every flow
+    Spot,
+    /// The covered-interest-parity forward rate for each flow's date
+    #[default]
+    Forward,
+}
+
+/// Spot rates and zero curves loaded from a local file
+#[derive(Debug, Clone, PartialEq)]
+pub struct FxMarket {
+    spots: Vec<SpotRate>,
+    curves: BTreeMap<Currency, Vec<CurvePoint>>,
+}
+
+#[derive(Deserialize)]
+struct FxMarketFile {
+    #[serde(default)]
+    spot: Vec<SpotRate>,
+    #[serde(default)]
+    curves: BTreeMap<Currency, Vec<CurvePoint>>,
+}
+
+#[derive(Deserialize)]
+struct FxMarketRow {
+    kind: String,
+    currency: Currency,
+    quote: Option<Currency>,
+    tenor_years: Option<f64>,
+    rate: f64,
+}
+
+impl FxMarket {
+    /// Builds a market from spot rates and zero curves, checking every rate
+    pub fn new(spots: Vec<SpotRate>, curves: BTreeMap<Currency, Vec<CurvePoint>>) -> FinanceResult<Self> {
+        for spot in &spots {
+            validate_positive(spot.rate, &format!("{}/{} spot rate", spot.base, spot.quote))?;
+            if spot.base == spot.quote {
+                return Err(FinanceError::InvalidInput(format!("Spot rate quotes {} against itself", spot.base)));
+            }
+        }
+        
+        let mut sorted = BTreeMap::new();
+        for (currency, mut points) in curves {
+            if points.is_empty() {
+                return Err(FinanceError::InvalidInput(format!("The {} curve has no points", currency)));
+            }
+            for point in &points {
+                validate_non_negative(point.tenor_years, &format!("{} curve tenor", currency))?;
+                validate_finite(point.rate, &format!("{} curve rate", currency))?;
+                if point.rate <= -1.0 {
+                    return Err(FinanceError::InvalidInput(format!("{} curve rate must be greater than -100%: {}", currency, point.rate)));
+                }
+            }
+            points.sort_by(|a, b| a.tenor_years.total_cmp(&b.tenor_years));
+            sorted.insert(currency, points);
+        }
+        
+        Ok(FxMarket { spots, curves: sorted })
+    }
+
+    /// Reads a market from JSON
+    /// 
+    /// # Examples
+    /// ```
+    /// use rusty_finance::calculations::FxMarket;
+    /// use rusty_finance::money::Currency;
+    /// 
+    /// let json = r#"{
+    ///     "spot": [{ "base": "EUR", "quote": "USD", "rate": 1.08 }],
+    ///     "curves": { "USD": [{ "tenor_years": 1, "rate": 0.045 }] }
+    /// }"#;
+    /// let market = FxMarket::from_json_str(json).unwrap();
+    /// assert!((market.spot(Currency::USD, Currency::EUR).unwrap().rate - 1.0 / 1.08).abs() < 1e-12);
+    /// ```
+    pub fn from_json_str(contents: &str) -> FinanceResult<Self> {
+        let file: FxMarketFile = serde_json::from_str(contents)
+            .map_err(|e| FinanceError::InvalidInput(format!("Invalid FX rates: {}", e)))?;
+        FxMarket::new(file.spot, file.curves)
+    }
+
+    /// Reads a market from CSV with the header `kind,currency,quote,tenor_years,rate`
+    /// 
+    /// `spot` rows give one `currency` in units of `quote`; `curve` rows give
+    /// the zero rate of `currency` at `tenor_years`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use rusty_finance::calculations::FxMarket;
+    /// use rusty_finance::money::Currency;
+    /// 
+    /// let csv = "kind,currency,quote,tenor_years,rate\n\
+    ///            spot,EUR,USD,,1.08\n\
+    ///            curve,USD,,1,0.045\n";
+    /// let market = FxMarket::from_csv_reader(csv.as_bytes()).unwrap();
+    /// assert_eq!(market.zero_rate(Currency::USD, 1.0).unwrap(), 0.045);
+    /// ```
+    pub fn from_csv_reader<R: Read>(reader: R) -> FinanceResult<Self> {
+        let mut csv_reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .flexible(true)
+            .from_reader(reader);
+        
+        let mut spots = Vec::new();
+        let mut curves: BTreeMap<Currency, Vec<CurvePoint>> = BTreeMap::new();
+        for (index, record) in csv_reader.deserialize().enumerate() {
+            let row: FxMarketRow = record
+                .map_err(|e| FinanceError::InvalidInput(format!("Invalid FX rate on row {}: {}", index + 1, e)))?;
+            match (row.kind.to_ascii_lowercase().as_str(), row.quote, row.tenor_years) {
+                ("spot", Some(quote), _) => spots.push(SpotRate { base: row.currency, quote, rate: row.rate }),
+                ("curve", _, Some(tenor_years)) => {
+                    curves.entry(row.currency).or_default().push(CurvePoint { tenor_years, rate: row.rate });
+                }
+                ("spot", None, _) => return Err(FinanceError::InvalidInput(format!("Spot rate on row {} needs a quote currency", index + 1))),
+                ("curve", _, None) => return Err(FinanceError::InvalidInput(format!("Curve rate on row {} needs a tenor", index + 1))),
+                (other, _, _) => return Err(FinanceError::InvalidInput(format!(
+                    "Unknown rate kind '{}' on row {}; expected spot or curve", other, index + 1
+                ))),
+            }
+        }
+        
+        FxMarket::new(spots, curves)
+    }
+
+    /// Spot rate of a quoted pair, in either direction
+    fn direct(&self, from: Currency, to: Currency) -> Option<f64> {
+        self.spots.iter().find_map(|spot| {
+            if spot.base == from && spot.quote == to {
+                Some(spot.rate)
+            } else if spot.base == to && spot.quote == from {
+                Some(1.0 / spot.rate)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Units of `to` one unit of `from` buys today
+    /// 
+    /// Uses the quoted pair when there is one, in either direction; otherwise
+    /// triangulates through a currency quoted against both.
+    /// 
+    /// # Examples
+    /// ```
+    /// use rusty_finance::calculations::{FxMarket, SpotRate};
+    /// use rusty_finance::money::Currency;
+    /// use std::collections::BTreeMap;
+    /// 
+    /// let market = FxMarket::new(vec![
+    ///     SpotRate { base: Currency::EUR, quote: Currency::USD, rate: 1.10 },
+    ///     SpotRate { base: Currency::USD, quote: Currency::JPY, rate: 150.0 },
+    /// ], BTreeMap::new()).unwrap();
+    /// 
+    /// let cross = market.spot(Currency::EUR, Currency::JPY).unwrap();
+    /// assert!((cross.rate - 165.0).abs() < 1e-9);
+    /// assert_eq!(cross.via, Some(Currency::USD));
+    /// ```
+    pub fn spot(&self, from: Currency, to: Currency) -> FinanceResult<SpotQuote> {
+        if from == to {
+            return Ok(SpotQuote { rate: 1.0, via: None });
+        }
+        if let Some(rate) = self.direct(from, to) {
+            return Ok(SpotQuote { rate, via: None });
+        }
+        
+        Currency::ALL
+            .into_iter()
+            .filter(|&via| via != from && via != to)
+            .find_map(|via| {
+                let first = self.direct(from, via)?;
+                let second = self.direct(via, to)?;
+                Some(SpotQuote { rate: first * second, via: Some(via) })
+            })
+            .ok_or_else(|| FinanceError::InvalidInput(format!("No spot or cross rate for {}/{}", from, to)))
+    }
+
+    /// Zero rate of a currency at a tenor, interpolated linearly and held flat beyond the curve
+    pub fn zero_rate(&self, currency: Currency, tenor_years: f64) -> FinanceResult<f64> {
+        validate_non_negative(tenor_years, "Tenor")?;
+        let points = self.curves.get(&currency)
+            .ok_or_else(|| FinanceError::InvalidInput(format!("No interest rate curve for {}", currency)))?;
+        
+        let first = points[0];
+        let last = points[points.len() - 1];
+        if tenor_years <= first.tenor_years {
+            return Ok(first.rate);
+        }
+        if tenor_years >= last.tenor_years {
+            return Ok(last.rate);
+        }
+        
+        let upper = points.iter().position(|point| point.tenor_years >= tenor_years).expect("tenor is inside the curve");
+        let (left, right) = (points[upper - 1], points[upper]);
+        let weight = (tenor_years - left.tenor_years) / (right.tenor_years - left.tenor_years);
+        Ok(left.rate + weight * (right.rate - left.rate))
+    }
+
+    /// Forward rate for exchanging `from` into `to` after `tenor_years`, by covered interest parity
+    /// 
+    /// Formula: F = S × (1 + r_to)^T / (1 + r_from)^T
+    /// 
+    /// # Examples
+    /// ```
+    /// use rusty_finance::calculations::FxMarket;
+    /// use rusty_finance::money::Currency;
+    /// 
+    /// let json = r#"{
+    ///     "spot": [{ "base": "EUR", "quote": "USD", "rate": 1.10 }],
+    ///     "curves": {
+    ///         "EUR": [{ "tenor_years": 1, "rate": 0.02 }],
+    ///         "USD": [{ "tenor_years": 1, "rate": 0.05 }]
+    ///     }
+    /// }"#;
+    /// let market = FxMarket::from_json_str(json).unwrap();
+    /// let forward = market.forward(Currency::EUR, Currency::USD, 1.0).unwrap();
+    /// assert!((forward - 1.10 * 1.05 / 1.02).abs() < 1e-12);
+    /// ```
+    pub fn forward(&self, from: Currency, to: Currency, tenor_years: f64) -> FinanceResult<f64> {
+        validate_non_negative(tenor_years, "Tenor")?;
+        let spot = self.spot(from, to)?.rate;
+        if from == to || tenor_years == 0.0 {
+            return Ok(spot);
+        }
+        
+        let from_rate = self.zero_rate(from, tenor_years)?;
+        let to_rate = self.zero_rate(to, tenor_years)?;
+        Ok(spot * ((1.0 + to_rate) / (1.0 + from_rate)).powf(tenor_years))
+    }
+
+    /// Converts an amount into another currency at today's spot rate
+    /// 
+    /// # Examples
+    /// ```
+    /// use rusty_finance::calculations::{FxMarket, SpotRate};
+    /// use rusty_finance::money::{Currency, Money};
+    /// use std::collections::BTreeMap;
+    /// 
+    /// let market = FxMarket::new(vec![SpotRate { base: Currency::USD, quote: Currency::JPY, rate: 150.0 }], BTreeMap::new()).unwrap();
+    /// let yen = market.convert(&Money::from_f64(10.0, Currency::USD).unwrap(), Currency::JPY).unwrap();
+    /// assert_eq!(yen.to_string(), "¥1,500");
+    /// ```
+    pub fn convert(&self, amount: &Money, to: Currency) -> FinanceResult<Money> {
+        let rate = self.spot(amount.currency(), to)?.rate;
+        exchange(amount, to, rate)
+    }
+}
+
+/// Multiplies an amount by an exchange rate into the target currency
+fn exchange(amount: &Money, to: Currency, rate: f64) -> FinanceResult<Money> {
+    let converted = amount.checked_mul(to_decimal(rate, "exchange rate")?)?;
+    Ok(Money::new(converted.amount(), to))
+}
+
+/// A cash flow in its own currency on a given date
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct DatedCashFlow {
+    pub date: NaiveDate,
+    pub currency: Currency,
+    pub amount: f64,
+}
+
+/// A cash flow after conversion to the base currency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConvertedCashFlow {
+    pub date: NaiveDate,
+    pub original: Money,
+    /// Years from the valuation date
+    pub tenor_years: f64,
+    /// Units of the base currency per unit of the original currency
+    pub rate: f64,
+    pub converted: Money,
+    /// Year the flow falls in for annual discounting, starting at 1
+    pub year: usize,
+}
+
+/// Reads dated cash flows from CSV with the header `date,currency,amount`
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::read_dated_cash_flows_csv;
+/// use rusty_finance::money::Currency;
+/// 
+/// let csv = "date,currency,amount\n2026-06-30,EUR,250000\n";
+/// let flows = read_dated_cash_flows_csv(csv.as_bytes()).unwrap();
+/// assert_eq!(flows[0].currency, Currency::EUR);
+/// ```
+pub fn read_dated_cash_flows_csv<R: Read>(reader: R) -> FinanceResult<Vec<DatedCashFlow>> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    
+    csv_reader
+        .deserialize()
+        .enumerate()
+        .map(|(index, record)| {
+            record.map_err(|e| FinanceError::InvalidInput(format!("Invalid cash flow on row {}: {}", index + 1, e)))
+        })
+        .collect()
+}
+
+/// Converts dated cash flows in several currencies into one base currency
+/// 
+/// Each flow's tenor is its distance from the valuation date in 365-day
+/// years, and it is assigned to the year containing it (flows within the
+/// first year fall in year 1). Use [`annual_cash_flows`] to total the result
+/// for [`calculate_npv`](crate::calculations::calculate_npv) or
+/// [`calculate_dcf`](crate::calculations::calculate_dcf).
+/// 
+/// # Arguments
+/// * `market` - Spot rates and zero curves
+/// * `flows` - Cash flows in their own currencies
+/// * `base` - Currency to convert into
+/// * `valuation_date` - Date of today's spot rates; no flow may precede it
+/// * `conversion` - Whether to convert at spot or at each flow's forward rate
+pub fn convert_cash_flows(
+    market: &FxMarket,
+    flows: &[DatedCashFlow],
+    base: Currency,
+    valuation_date: NaiveDate,
+    conversion: FxConversion
+) -> FinanceResult<Vec<ConvertedCashFlow>> {
+    flows.iter().map(|flow| {
+        let days = (flow.date - valuation_date).num_days();
+        if days < 0 {
+            return Err(FinanceError::InvalidInput(format!(
+                "Cash flow on {} is before the valuation date {}", flow.date, valuation_date
+            )));
+        }
+        
+        let tenor_years = days as f64 / DAYS_PER_YEAR;
+        let rate = match conversion {
+            FxConversion::Spot => market.spot(flow.currency, base)?.rate,
+            FxConversion::Forward => market.forward(flow.currency, base, tenor_years)?,
+        };
+        let original = Money::from_f64(flow.amount, flow.currency)?;
+        
+        Ok(ConvertedCashFlow {
+            date: flow.date,
+            original,
+            tenor_years,
+            rate,
+            converted: exchange(&original, base, rate)?,
+            year: (tenor_years.ceil() as usize).max(1),
+        })
+    }).collect()
+}
+
+/// Totals converted cash flows by year, ready for annual discounting
+/// 
+/// # Examples
+/// ```
+/// use chrono::NaiveDate;
+/// use rusty_finance::calculations::{annual_cash_flows, calculate_dcf, convert_cash_flows, DatedCashFlow, FxConversion, FxMarket, SpotRate};
+/// use rusty_finance::money::Currency;
+/// use std::collections::BTreeMap;
+/// 
+/// let market = FxMarket::new(vec![SpotRate { base: Currency::EUR, quote: Currency::USD, rate: 1.10 }], BTreeMap::new()).unwrap();
+/// let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+/// let flows = [
+///     DatedCashFlow { date: NaiveDate::from_ymd_opt(2025, 12, 31).unwrap(), currency: Currency::EUR, amount: 1000.0 },
+///     DatedCashFlow { date: NaiveDate::from_ymd_opt(2026, 6, 30).unwrap(), currency: Currency::USD, amount: 500.0 },
+/// ];
+/// 
+/// let converted = convert_cash_flows(&market, &flows, Currency::USD, today, FxConversion::Spot).unwrap();
+/// let annual = annual_cash_flows(&converted);
+/// assert_eq!(annual, vec![1100.0, 500.0]);
+/// assert!(calculate_dcf(&annual, 0.08).unwrap() > 0.0);
+/// ```
+pub fn annual_cash_flows(flows: &[ConvertedCashFlow]) -> Vec<f64> {
+    let years = flows.iter().map(|flow| flow.year).max().unwrap_or(0);
+    let mut annual = vec![0.0; years];
+    for flow in flows {
+        annual[flow.year - 1] += flow.converted.to_f64();
+    }
+    annual
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculations::calculate_npv;
+
+    fn market() -> FxMarket {
+        let json = r#"{
+            "spot": [
+                { "base": "EUR", "quote": "USD", "rate": 1.10 },
+                { "base": "GBP", "quote": "USD", "rate": 1.25 },
+                { "base": "USD", "quote": "JPY", "rate": 150.0 }
+            ],
+            "curves": {
+                "USD": [{ "tenor_years": 1, "rate": 0.05 }, { "tenor_years": 3, "rate": 0.04 }],
+                "EUR": [{ "tenor_years": 1, "rate": 0.02 }],
+                "JPY": [{ "tenor_years": 1, "rate": 0.001 }]
+            }
+        }"#;
+        FxMarket::from_json_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_cross_rates_triangulate_through_a_common_currency() {
+        let market = market();
+        
+        let eur_gbp = market.spot(Currency::EUR, Currency::GBP).unwrap();
+        assert!((eur_gbp.rate - 1.10 / 1.25).abs() < 1e-12);
+        assert_eq!(eur_gbp.via, Some(Currency::USD));
+        
+        let round_trip = market.spot(Currency::JPY, Currency::EUR).unwrap().rate * market.spot(Currency::EUR, Currency::JPY).unwrap().rate;
+        assert!((round_trip - 1.0).abs() < 1e-12);
+        assert!(market.spot(Currency::CHF, Currency::USD).is_err());
+    }
+
+    #[test]
+    fn test_forwards_follow_covered_interest_parity() {
+        let market = market();
+        
+        assert!((market.zero_rate(Currency::USD, 2.0).unwrap() - 0.045).abs() < 1e-12);
+        assert_eq!(market.zero_rate(Currency::USD, 0.25).unwrap(), 0.05);
+        assert_eq!(market.zero_rate(Currency::USD, 10.0).unwrap(), 0.04);
+        
+        // Borrowing in the lower-rate currency makes it trade at a forward premium
+        let forward = market.forward(Currency::EUR, Currency::USD, 2.0).unwrap();
+        assert!((forward - 1.10 * (1.045_f64 / 1.02).powi(2)).abs() < 1e-12);
+        assert!(forward > 1.10);
+        assert_eq!(market.forward(Currency::EUR, Currency::USD, 0.0).unwrap(), 1.10);
+        assert!(market.forward(Currency::GBP, Currency::USD, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_csv_and_json_markets_agree() {
+        let csv = "kind,currency,quote,tenor_years,rate\n\
+                   spot,EUR,USD,,1.10\n\
+                   spot,GBP,USD,,1.25\n\
+                   spot,USD,JPY,,150\n\
+                   curve,USD,,3,0.04\n\
+                   curve,USD,,1,0.05\n\
+                   curve,EUR,,1,0.02\n\
+                   curve,JPY,,1,0.001\n";
+        assert_eq!(FxMarket::from_csv_reader(csv.as_bytes()).unwrap(), market());
+        
+        let lowercase = "kind,currency,quote,tenor_years,rate\nspot,eur,usd,,1.10\ncurve,usd,,1,0.05\n";
+        let json = r#"{ "spot": [{ "base": "eur", "quote": "Usd", "rate": 1.10 }], "curves": { "usd": [{ "tenor_years": 1, "rate": 0.05 }] } }"#;
+        assert_eq!(FxMarket::from_csv_reader(lowercase.as_bytes()).unwrap(), FxMarket::from_json_str(json).unwrap());
+        assert_eq!(FxMarket::from_json_str(json).unwrap().spot(Currency::EUR, Currency::USD).unwrap().rate, 1.10);
+        
+        assert!(FxMarket::from_csv_reader("kind,currency,quote,tenor_years,rate\nswap,EUR,USD,,1.1\n".as_bytes()).is_err());
+        assert!(FxMarket::from_json_str(r#"{ "spot": [{ "base": "XYZ", "quote": "USD", "rate": 1 }] }"#).is_err());
+        assert!(FxMarket::from_json_str(r#"{ "spot": [{ "base": "EUR", "quote": "USD", "rate": -1 }] }"#).is_err());
+    }
+
+    #[test]
+    fn test_dated_flows_convert_at_their_forward_rate() {
+        let market = market();
+        let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let flows = [
+            DatedCashFlow { date: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(), currency: Currency::EUR, amount: 1000.0 },
+            DatedCashFlow { date: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(), currency: Currency::USD, amount: 200.0 },
+            DatedCashFlow { date: NaiveDate::from_ymd_opt(2027, 1, 1).unwrap(), currency: Currency::JPY, amount: 150000.0 },
+        ];
+        
+        let converted = convert_cash_flows(&market, &flows, Currency::USD, today, FxConversion::Forward).unwrap();
+        let eur_forward = 1.10 * 1.05 / 1.02;
+        assert!((converted[0].converted.to_f64() - 1000.0 * eur_forward).abs() < 1e-6);
+        assert_eq!(converted[1].rate, 1.0);
+        assert_eq!(converted[2].year, 2);
+        assert_eq!(converted[2].converted.currency(), Currency::USD);
+        
+        let annual = annual_cash_flows(&converted);
+        assert_eq!(annual.len(), 2);
+        assert!((annual[0] - (1000.0 * eur_forward + 200.0)).abs() < 1e-6);
+        assert!(calculate_npv(1500.0, &annual, 0.05).is_ok());
+        
+        let late = [DatedCashFlow { date: NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(), currency: Currency::USD, amount: 1.0 }];
+        assert!(convert_cash_flows(&market, &late, Currency::USD, today, FxConversion::Spot).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+//! Goal seek: find the input value that makes a calculation produce a target result
+
+use crate::{FinanceError, FinanceResult, validate_finite};
+
+/// Largest number of times the search interval is doubled while bracketing
+const MAX_BRACKET_EXPANSIONS: usize = 60;
+
+/// Largest number of refinement steps once the target is bracketed
+const MAX_ITERATIONS: usize = 200;
+
+/// Relative tolerance on both the result and the input
+const TOLERANCE: f64 = 1e-10;
+
+/// Finds the input between `lower` and `upper` at which `calculate` returns `target`
+/// 
+/// The calculation must move from one side of the target to the other between
+/// the two bounds. The bracket is narrowed with the Illinois variant of the
+/// false-position method, which keeps the root bracketed at every step.
+/// 
+/// # Arguments
+/// * `calculate` - Computes the result for an input value
+/// * `target` - The result to aim for
+/// * `lower` - One end of the bracket
+/// * `upper` - The other end of the bracket
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{solve_in_bracket, calculate_npv};
+/// 
+/// // The rate at which NPV is zero is the internal rate of return
+/// let irr = solve_in_bracket(|rate| calculate_npv(100.0, &[110.0], rate), 0.0, 0.0, 1.0).unwrap();
+/// assert!((irr - 0.10).abs() < 1e-9);
+/// ```
+pub fn solve_in_bracket<F>(calculate: F, target: f64, lower: f64, upper: f64) -> FinanceResult<f64>
+where
+    F: Fn(f64) -> FinanceResult<f64>,
+{
+    validate_finite(target, "Target")?;
+    validate_finite(lower, "Lower bound")?;
+    validate_finite(upper, "Upper bound")?;
+    
+    let gap = |input: f64| -> FinanceResult<f64> {
+        let result = calculate(input)?;
+        if result.is_finite() { Ok(result - target) } else { Err(FinanceError::Overflow) }
+    };
+    let result_tolerance = TOLERANCE * target.abs().max(1.0);
+    
+    let (mut a, mut b) = (lower, upper);
+    let (mut gap_a, mut gap_b) = (gap(a)?, gap(b)?);
+    
+    if gap_a.abs() <= result_tolerance {
+        return Ok(a);
+    }
+    if gap_b.abs() <= result_tolerance {
+        return Ok(b);
+    }
+    if gap_a.signum() == gap_b.signum() {
+        return Err(FinanceError::ConvergenceFailed);
+    }
+    
+    // Which end moved last: halving the stale end's gap stops false position stalling
+    let mut last_moved = 0;
+    
+    for _ in 0..MAX_ITERATIONS {
+        let c = (a * gap_b - b * gap_a) / (gap_b - gap_a);
+        let gap_c = gap(c)?;
+        
+        if gap_c.abs() <= result_tolerance || (b - a).abs() <= TOLERANCE * c.abs().max(1.0) {
+            return Ok(c);
+        }
+        
+        if gap_c.signum() == gap_b.signum() {
+            b = c;
+            gap_b = gap_c;
+            if last_moved == -1 {
+                gap_a /= 2.0;
+            }
+            last_moved = -1;
+        } else {
+            a = c;
+            gap_a = gap_c;
+            if last_moved == 1 {
+                gap_b /= 2.0;
+            }
+            last_moved = 1;
+        }
+    }
+    
+    Err(FinanceError::ConvergenceFailed)
+}
+
+/// Finds the input value at which `calculate` returns `target`, starting from a guess
+/// 
+/// The search steps outward from `initial_guess` in both directions, doubling
+/// the step each time, until the result crosses the target; inputs the
+/// calculation rejects are skipped. The crossing is then refined with
+/// [`solve_in_bracket`]. If no crossing is found the search fails with
+/// [`FinanceError::ConvergenceFailed`].
+/// 
+/// # Arguments
+/// * `calculate` - Computes the result for an input value
+/// * `target` - The result to aim for
+/// * `initial_guess` - Where to start looking, usually the input's current value
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{goal_seek, calculate_break_even_units};
+/// 
+/// // Price per unit that makes break-even 300 units
+/// let price = goal_seek(|price| calculate_break_even_units(6000.0, 10.0, price), 300.0, 20.0).unwrap();
+/// assert!((price - 30.0).abs() < 1e-6);
+/// ```
+pub fn goal_seek<F>(calculate: F, target: f64, initial_guess: f64) -> FinanceResult<f64>
+where
+    F: Fn(f64) -> FinanceResult<f64>,
+{
+    validate_finite(target, "Target")?;
+    validate_finite(initial_guess, "Initial guess")?;
+    
+    let gap = |input: f64| calculate(input).ok().map(|result| result - target).filter(|g| g.is_finite());
+    let start = gap(initial_guess);
+    if start == Some(0.0) {
+        return Ok(initial_guess);
+    }
+    
+    let mut step = if initial_guess == 0.0 { 0.1 } else { initial_guess.abs() * 0.1 };
+    let mut below = start.map(|g| (initial_guess, g));
+    let mut above = start.map(|g| (initial_guess, g));
+    
+    for _ in 0..MAX_BRACKET_EXPANSIONS {
+        for (side, direction) in [(&mut above, 1.0), (&mut below, -1.0)] {
+            let input = initial_guess + direction * step;
+            let Some(gap_here) = gap(input) else {
+                continue;
+            };
+            
+            if gap_here == 0.0 {
+                return Ok(input);
+            }
+            
+            if let Some((previous, gap_previous)) = *side {
+                if gap_previous.signum() != gap_here.signum() {
+                    return solve_in_bracket(&calculate, target, previous, input);
+                }
+            }
+            *side = Some((input, gap_here));
+        }
+        step *= 2.0;
+    }
+    
+    Err(FinanceError::ConvergenceFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculations::{calculate_break_even_units, calculate_capm, calculate_loan_payment, calculate_npv};
+
+    #[test]
+    fn test_rate_making_npv_zero() {
+        let rate = goal_seek(|rate| calculate_npv(3000.0, &[1000.0; 5], rate), 0.0, 0.10).unwrap();
+        
+        assert!((rate - 0.198577).abs() < 1e-6);
+        assert!(calculate_npv(3000.0, &[1000.0; 5], rate).unwrap().abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_principal_for_target_payment() {
+        let principal = goal_seek(|principal| calculate_loan_payment(principal, 6.0, 30.0), 1500.0, 100000.0).unwrap();
+        
+        assert!((calculate_loan_payment(principal, 6.0, 30.0).unwrap() - 1500.0).abs() < 1e-6);
+        assert!((principal - 250187.42).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_search_skips_rejected_inputs() {
+        // Prices at or below the variable cost are rejected by the calculation
+        let price = goal_seek(|price| calculate_break_even_units(6000.0, 10.0, price), 300.0, 5.0).unwrap();
+        assert!((price - 30.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_unreachable_target_fails_to_converge() {
+        // Expected return does not depend on beta when the market return equals the risk-free rate
+        let result = goal_seek(|beta| calculate_capm(0.05, beta, 0.05), 0.10, 1.0);
+        assert!(matches!(result, Err(FinanceError::ConvergenceFailed)));
+        
+        let no_bracket = solve_in_bracket(|rate| calculate_npv(100.0, &[110.0], rate), 0.0, 0.2, 0.5);
+        assert!(matches!(no_bracket, Err(FinanceError::ConvergenceFailed)));
+    }
+}
@@ -0,0 +1,249 @@
+//! Inflation adjustment: real versus nominal rates and amounts
+
+use crate::{FinanceError, FinanceResult, validate_finite, validate_positive};
+use crate::calculations::AmortizationPayment;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::io::Read;
+
+/// A single consumer price index observation
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CpiObservation {
+    pub date: NaiveDate,
+    pub cpi: f64,
+}
+
+/// A constant annual inflation rate used to express amounts in today's money
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Inflation {
+    /// The annual inflation rate as a decimal
+    pub annual_rate: f64,
+}
+
+impl Inflation {
+    /// Creates an inflation assumption from an annual rate (as a decimal)
+    pub fn new(annual_rate: f64) -> FinanceResult<Self> {
+        validate_inflation_rate(annual_rate)?;
+        Ok(Inflation { annual_rate })
+    }
+
+    /// Estimates inflation as the annualized change between two CPI observations
+    /// 
+    /// Amounts are then deflated at that constant rate, so only a start and an
+    /// end observation are accepted; a longer series is rejected rather than
+    /// having its intermediate points silently ignored.
+    /// 
+    /// # Examples
+    /// ```
+    /// use rusty_finance::calculations::{CpiObservation, Inflation};
+    /// use chrono::NaiveDate;
+    /// 
+    /// let series = vec![
+    ///     CpiObservation { date: NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(), cpi: 100.0 },
+    ///     CpiObservation { date: NaiveDate::from_ymd_opt(2022, 1, 1).unwrap(), cpi: 110.25 },
+    /// ];
+    /// let inflation = Inflation::from_cpi(&series).unwrap();
+    /// assert!((inflation.annual_rate - 0.05).abs() < 1e-3);
+    /// ```
+    pub fn from_cpi(series: &[CpiObservation]) -> FinanceResult<Self> {
+        if series.len() != 2 {
+            return Err(FinanceError::InvalidInput(format!(
+                "CPI series needs exactly two observations, a start and an end, found {}", series.len()
+            )));
+        }
+        
+        let mut sorted = series.to_vec();
+        sorted.sort_by_key(|observation| observation.date);
+        
+        let (first, last) = (&sorted[0], &sorted[1]);
+        if last.date == first.date {
+            return Err(FinanceError::InvalidInput("CPI observations must be on different dates".into()));
+        }
+        
+        for observation in &sorted {
+            validate_positive(observation.cpi, "CPI")?;
+        }
+        
+        let years = (last.date - first.date).num_days() as f64 / 365.25;
+        Inflation::new((last.cpi / first.cpi).powf(1.0 / years) - 1.0)
+    }
+
+    /// Price level after `years` relative to today (1.0 today)
+    pub fn price_level(&self, years: f64) -> f64 {
+        (1.0 + self.annual_rate).powf(years)
+    }
+
+    /// Expresses a nominal amount received after `years` in today's money
+    pub fn to_real(&self, nominal_amount: f64, years: f64) -> f64 {
+        nominal_amount / self.price_level(years)
+    }
+
+    /// Converts a nominal annual rate to a real rate with the Fisher equation
+    pub fn real_rate(&self, nominal_rate: f64) -> f64 {
+        (1.0 + nominal_rate) / (1.0 + self.annual_rate) - 1.0
+    }
+}
+
+/// Reads CPI observations from CSV with the header `date,cpi`
+/// 
+/// # Arguments
+/// * `reader` - The CSV source, with dates as YYYY-MM-DD
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::read_cpi_csv;
+/// 
+/// let csv = "date,cpi\n2023-01-01,299.2\n2024-01-01,308.4\n";
+/// let series = read_cpi_csv(csv.as_bytes()).unwrap();
+/// assert_eq!(series.len(), 2);
+/// ```
+pub fn read_cpi_csv<R: Read>(reader: R) -> FinanceResult<Vec<CpiObservation>> {
+    let mut csv_reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(reader);
+    
+    csv_reader
+        .deserialize()
+        .enumerate()
+        .map(|(index, record)| {
+            record.map_err(|e| FinanceError::InvalidInput(format!("Invalid CPI observation on row {}: {}", index + 1, e)))
+        })
+        .collect()
+}
+
+/// Converts a nominal rate to a real rate using the Fisher equation
+/// 
+/// # Arguments
+/// * `nominal_rate` - The nominal rate (as a decimal)
+/// * `inflation_rate` - The inflation rate over the same period (as a decimal)
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_real_rate;
+/// 
+/// let real = calculate_real_rate(0.08, 0.03).unwrap();
+/// assert!((real - 0.048543689).abs() < 1e-8);
+/// ```
+pub fn calculate_real_rate(nominal_rate: f64, inflation_rate: f64) -> FinanceResult<f64> {
+    validate_finite(nominal_rate, "Nominal rate")?;
+    validate_inflation_rate(inflation_rate)?;
+    
+    Ok((1.0 + nominal_rate) / (1.0 + inflation_rate) - 1.0)
+}
+
+/// Converts a real rate to a nominal rate using the Fisher equation
+/// 
+/// # Arguments
+/// * `real_rate` - The real rate (as a decimal)
+/// * `inflation_rate` - The inflation rate over the same period (as a decimal)
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_nominal_rate;
+/// 
+/// let nominal = calculate_nominal_rate(0.02, 0.03).unwrap();
+/// assert!((nominal - 0.0506).abs() < 1e-12);
+/// ```
+pub fn calculate_nominal_rate(real_rate: f64, inflation_rate: f64) -> FinanceResult<f64> {
+    validate_finite(real_rate, "Real rate")?;
+    validate_inflation_rate(inflation_rate)?;
+    
+    Ok((1.0 + real_rate) * (1.0 + inflation_rate) - 1.0)
+}
+
+/// Restates an amortization schedule in today's money
+/// 
+/// Each payment is deflated by the price level at its payment month and the
+/// cumulative columns are re-accumulated from the deflated amounts.
+/// 
+/// # Arguments
+/// * `schedule` - The nominal schedule
+/// * `inflation` - The inflation assumption
+pub fn real_amortization_schedule(schedule: &[AmortizationPayment], inflation: &Inflation) -> Vec<AmortizationPayment> {
+    let mut cumulative_principal = 0.0;
+    let mut cumulative_interest = 0.0;
+    
+    schedule
+        .iter()
+        .map(|payment| {
+            let years = payment.month as f64 / 12.0;
+            let principal_payment = inflation.to_real(payment.principal_payment, years);
+            let interest_payment = inflation.to_real(payment.interest_payment, years);
+            cumulative_principal += principal_payment;
+            cumulative_interest += interest_payment;
+            
+            AmortizationPayment {
+                principal_payment,
+                interest_payment,
+                remaining_balance: inflation.to_real(payment.remaining_balance, years),
+                cumulative_principal,
+                cumulative_interest,
+                ..payment.clone()
+            }
+        })
+        .collect()
+}
+
+fn validate_inflation_rate(rate: f64) -> FinanceResult<()> {
+    validate_finite(rate, "Inflation rate")?;
+    
+    if rate <= -1.0 {
+        return Err(FinanceError::InvalidInput(format!("Inflation rate must be greater than -100%: {}", rate)));
+    }
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculations::generate_amortization_schedule;
+
+    #[test]
+    fn test_fisher_round_trip() {
+        let real = calculate_real_rate(0.07, 0.025).unwrap();
+        assert!((calculate_nominal_rate(real, 0.025).unwrap() - 0.07).abs() < 1e-12);
+        assert!(calculate_real_rate(0.02, 0.05).unwrap() < 0.0);
+        assert!(calculate_real_rate(0.05, -1.0).is_err());
+    }
+
+    #[test]
+    fn test_real_amounts() {
+        let inflation = Inflation::new(0.03).unwrap();
+        assert!((inflation.to_real(1.03_f64.powi(10), 10.0) - 1.0).abs() < 1e-12);
+        assert_eq!(inflation.to_real(500.0, 0.0), 500.0);
+        
+        // Growing at the nominal rate is growing at the real rate in today's money
+        let nominal = 1000.0 * 1.08_f64.powi(5);
+        let real = 1000.0 * (1.0 + inflation.real_rate(0.08)).powi(5);
+        assert!((inflation.to_real(nominal, 5.0) - real).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_inflation_from_cpi_csv() {
+        let csv = "date,cpi\n2022-01-01,110.25\n2020-01-01,100\n";
+        let series = read_cpi_csv(csv.as_bytes()).unwrap();
+        let inflation = Inflation::from_cpi(&series).unwrap();
+        assert!((inflation.annual_rate - 0.05).abs() < 1e-3);
+        
+        assert!(Inflation::from_cpi(&series[..1]).is_err());
+        
+        // Intermediate observations would not be used, so they are refused
+        let monthly = read_cpi_csv("date,cpi\n2020-01-01,100\n2021-01-01,104\n2022-01-01,110.25\n".as_bytes()).unwrap();
+        assert!(Inflation::from_cpi(&monthly).is_err());
+        assert!(read_cpi_csv("date,cpi\nlast year,100\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_real_amortization_schedule() {
+        let first_payment = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let schedule = generate_amortization_schedule(100000.0, 6.0, 10, first_payment).unwrap();
+        
+        let unchanged = real_amortization_schedule(&schedule, &Inflation::new(0.0).unwrap());
+        assert!((unchanged[119].cumulative_interest - schedule[119].cumulative_interest).abs() < 1e-9);
+        
+        let real = real_amortization_schedule(&schedule, &Inflation::new(0.03).unwrap());
+        assert_eq!(real[11].payment_date, schedule[11].payment_date);
+        assert!((real[11].interest_payment - schedule[11].interest_payment / 1.03).abs() < 1e-9);
+        assert!(real[119].cumulative_interest < schedule[119].cumulative_interest);
+        assert!(real[119].cumulative_principal < 100000.0);
+    }
+}
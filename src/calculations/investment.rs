@@ -1,6 +1,8 @@
 //! Investment analysis functions
 
-use crate::{FinanceError, FinanceResult, validate_positive, validate_non_negative};
+use crate::{FinanceError, FinanceResult, validate_finite, validate_positive, validate_non_negative};
+use crate::calculations::goal_seek;
+use std::str::FromStr;
 
 /// Calculates Net Present Value (NPV)
 /// 
@@ -188,6 +190,432 @@ pub fn calculate_capm(
     Ok(risk_free_rate + beta * (market_return - risk_free_rate))
 }
 
+/// Calculates the modified internal rate of return (MIRR)
+/// 
+/// Outflows are discounted to today at the finance rate and inflows are
+/// compounded to the final year at the reinvestment rate.
+/// 
+/// Formula: MIRR = (FV of inflows / PV of outflows)^(1/n) - 1
+/// 
+/// # Arguments
+/// * `initial_investment` - The initial cost of the investment
+/// * `cash_flows` - Vector of future cash flows, which may include outflows
+/// * `finance_rate` - The rate paid to fund outflows (as a decimal)
+/// * `reinvestment_rate` - The rate earned on reinvested inflows (as a decimal)
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_mirr;
+/// 
+/// let mirr = calculate_mirr(1000.0, &[500.0, 500.0, 500.0], 0.10, 0.10).unwrap();
+/// assert!((mirr - 0.1829).abs() < 0.0001);
+/// ```
+pub fn calculate_mirr(
+    initial_investment: f64,
+    cash_flows: &[f64],
+    finance_rate: f64,
+    reinvestment_rate: f64
+) -> FinanceResult<f64> {
+    validate_positive(initial_investment, "Initial investment")?;
+    validate_non_negative(finance_rate, "Finance rate")?;
+    validate_non_negative(reinvestment_rate, "Reinvestment rate")?;
+    validate_cash_flows(cash_flows)?;
+    
+    let years = cash_flows.len() as i32;
+    let mut outflows = initial_investment;
+    let mut inflows = 0.0;
+    
+    for (index, &cash_flow) in cash_flows.iter().enumerate() {
+        let year = index as i32 + 1;
+        if cash_flow < 0.0 {
+            outflows += -cash_flow / (1.0 + finance_rate).powi(year);
+        } else {
+            inflows += cash_flow * (1.0 + reinvestment_rate).powi(years - year);
+        }
+    }
+    
+    if inflows == 0.0 {
+        return Err(FinanceError::InvalidInput("MIRR needs at least one positive cash flow".into()));
+    }
+    
+    Ok((inflows / outflows).powf(1.0 / years as f64) - 1.0)
+}
+
+/// Calculates the Internal Rate of Return (IRR), the discount rate at which NPV is zero
+/// 
+/// # Arguments
+/// * `initial_investment` - The initial cost of the investment
+/// * `cash_flows` - Vector of future cash flows, which may include outflows
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_irr;
+/// 
+/// let irr = calculate_irr(1000.0, &[0.0, 0.0, 1331.0]).unwrap();
+/// assert!((irr - 0.10).abs() < 1e-9);
+/// ```
+pub fn calculate_irr(initial_investment: f64, cash_flows: &[f64]) -> FinanceResult<f64> {
+    validate_positive(initial_investment, "Initial investment")?;
+    validate_cash_flows(cash_flows)?;
+    
+    if !cash_flows.iter().any(|&cash_flow| cash_flow > 0.0) {
+        return Err(FinanceError::InvalidInput("IRR needs at least one positive cash flow".into()));
+    }
+    
+    let npv = |rate: f64| {
+        if rate <= -1.0 {
+            return Err(FinanceError::InvalidInput("Discount rate must be greater than -1".into()));
+        }
+        Ok(cash_flows
+            .iter()
+            .enumerate()
+            .fold(-initial_investment, |npv, (year, &cash_flow)| npv + cash_flow / (1.0 + rate).powi(year as i32 + 1)))
+    };
+    
+    goal_seek(npv, 0.0, 0.10)
+}
+
+/// Calculates the profitability index (present value of future cash flows per dollar invested)
+/// 
+/// # Arguments
+/// * `initial_investment` - The initial cost of the investment
+/// * `cash_flows` - Vector of future cash flows
+/// * `discount_rate` - The discount rate (as a decimal)
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_profitability_index;
+/// 
+/// let index = calculate_profitability_index(1000.0, &[1100.0], 0.10).unwrap();
+/// assert!((index - 1.0).abs() < 1e-12);
+/// ```
+pub fn calculate_profitability_index(
+    initial_investment: f64,
+    cash_flows: &[f64],
+    discount_rate: f64
+) -> FinanceResult<f64> {
+    let npv = calculate_npv(initial_investment, cash_flows, discount_rate)?;
+    Ok(1.0 + npv / initial_investment)
+}
+
+/// Calculates the payback period using discounted cash flows
+/// 
+/// # Arguments
+/// * `initial_cost` - The initial cost of the investment
+/// * `cash_flows` - Vector of future cash flows
+/// * `discount_rate` - The discount rate (as a decimal)
+/// 
+/// # Returns
+/// * The payback period in years, or None if the discounted flows never repay the cost
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{calculate_discounted_payback_period, calculate_payback_period};
+/// 
+/// let cash_flows = vec![400.0, 400.0, 400.0, 400.0];
+/// let simple = calculate_payback_period(1000.0, &cash_flows).unwrap().unwrap();
+/// let discounted = calculate_discounted_payback_period(1000.0, &cash_flows, 0.10).unwrap().unwrap();
+/// assert!(discounted > simple);
+/// ```
+pub fn calculate_discounted_payback_period(
+    initial_cost: f64,
+    cash_flows: &[f64],
+    discount_rate: f64
+) -> FinanceResult<Option<f64>> {
+    validate_non_negative(discount_rate, "Discount rate")?;
+    
+    let discounted: Vec<f64> = cash_flows
+        .iter()
+        .enumerate()
+        .map(|(year, &cash_flow)| cash_flow / (1.0 + discount_rate).powi(year as i32 + 1))
+        .collect();
+    
+    calculate_payback_period(initial_cost, &discounted)
+}
+
+/// Calculates the equivalent annual annuity (EAA): the level annual payment with the same NPV
+/// 
+/// Formula: EAA = NPV × r / (1 - (1 + r)^-n)
+/// 
+/// # Arguments
+/// * `initial_investment` - The initial cost of the investment
+/// * `cash_flows` - Vector of future cash flows
+/// * `discount_rate` - The discount rate (as a decimal)
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_equivalent_annual_annuity;
+/// 
+/// // A project that is itself a level annuity has that annuity, less the cost, as its EAA
+/// let eaa = calculate_equivalent_annual_annuity(0.01, &[100.0, 100.0, 100.0], 0.08).unwrap();
+/// assert!((eaa - 100.0).abs() < 0.01);
+/// ```
+pub fn calculate_equivalent_annual_annuity(
+    initial_investment: f64,
+    cash_flows: &[f64],
+    discount_rate: f64
+) -> FinanceResult<f64> {
+    let npv = calculate_npv(initial_investment, cash_flows, discount_rate)?;
+    let years = cash_flows.len() as f64;
+    
+    if discount_rate == 0.0 {
+        return Ok(npv / years);
+    }
+    
+    Ok(npv * discount_rate / (1.0 - (1.0 + discount_rate).powf(-years)))
+}
+
+/// Calculates the NPV of repeating a project back to back until a common horizon
+/// 
+/// Used to compare projects with unequal lives: each is replicated until the
+/// horizon, usually the least common multiple of their lives.
+/// 
+/// # Arguments
+/// * `initial_investment` - The initial cost of one cycle of the project
+/// * `cash_flows` - Vector of future cash flows for one cycle
+/// * `discount_rate` - The discount rate (as a decimal)
+/// * `horizon_years` - The comparison horizon, a multiple of the project's life and at most
+///   [`MAX_REPLACEMENT_CHAIN_YEARS`]
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{calculate_replacement_chain_npv, calculate_npv};
+/// 
+/// let single = calculate_npv(1000.0, &[600.0, 600.0], 0.10).unwrap();
+/// let chain = calculate_replacement_chain_npv(1000.0, &[600.0, 600.0], 0.10, 4).unwrap();
+/// assert!((chain - (single + single / 1.1_f64.powi(2))).abs() < 1e-9);
+/// ```
+pub fn calculate_replacement_chain_npv(
+    initial_investment: f64,
+    cash_flows: &[f64],
+    discount_rate: f64,
+    horizon_years: usize
+) -> FinanceResult<f64> {
+    let npv = calculate_npv(initial_investment, cash_flows, discount_rate)?;
+    let life = cash_flows.len();
+    
+    if horizon_years == 0 || !horizon_years.is_multiple_of(life) {
+        return Err(FinanceError::InvalidInput(format!(
+            "Horizon of {} years is not a multiple of the project life of {} years", horizon_years, life
+        )));
+    }
+    if horizon_years > MAX_REPLACEMENT_CHAIN_YEARS {
+        return Err(FinanceError::InvalidInput(format!(
+            "Horizon of {} years exceeds the replacement chain limit of {} years", horizon_years, MAX_REPLACEMENT_CHAIN_YEARS
+        )));
+    }
+    
+    Ok((0..horizon_years / life)
+        .map(|cycle| npv / (1.0 + discount_rate).powf((cycle * life) as f64))
+        .sum())
+}
+
+/// Longest horizon a replacement chain is built for; beyond it, compare by EAA
+pub const MAX_REPLACEMENT_CHAIN_YEARS: usize = 1000;
+
+/// Shortest horizon that is a whole number of cycles for every project life
+/// 
+/// Returns `None` when that horizon exceeds [`MAX_REPLACEMENT_CHAIN_YEARS`].
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::replacement_chain_horizon;
+/// 
+/// assert_eq!(replacement_chain_horizon(&[2, 3, 4]), Some(12));
+/// assert_eq!(replacement_chain_horizon(&[29, 31, 37]), None);
+/// ```
+pub fn replacement_chain_horizon(lives: &[usize]) -> Option<usize> {
+    fn gcd(a: usize, b: usize) -> usize {
+        if b == 0 { a } else { gcd(b, a % b) }
+    }
+    
+    lives.iter()
+        .filter(|&&life| life > 0)
+        .try_fold(1usize, |horizon, &life| (horizon / gcd(horizon, life)).checked_mul(life))
+        .filter(|&horizon| horizon <= MAX_REPLACEMENT_CHAIN_YEARS)
+}
+
+/// A named project: an initial outlay followed by yearly cash flows
+#[derive(Debug, Clone, PartialEq)]
+pub struct Project {
+    pub name: String,
+    pub initial_investment: f64,
+    pub cash_flows: Vec<f64>,
+}
+
+impl FromStr for Project {
+    type Err = FinanceError;
+
+    /// Parses `name=-1000,300,400,500`, where the first (negative) flow is the outlay today
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, flows) = s.split_once('=')
+            .ok_or_else(|| FinanceError::InvalidInput(format!("Project must be name=outlay,flow,...: {}", s)))?;
+        
+        let flows: Vec<f64> = flows
+            .split(',')
+            .map(|flow| flow.trim().parse::<f64>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| FinanceError::InvalidInput(format!("Invalid cash flows for project {}: {}", name, flows)))?;
+        
+        match flows.split_first() {
+            Some((&outlay, cash_flows)) if outlay < 0.0 && !cash_flows.is_empty() => Ok(Project {
+                name: name.trim().to_string(),
+                initial_investment: -outlay,
+                cash_flows: cash_flows.to_vec(),
+            }),
+            _ => Err(FinanceError::InvalidInput(format!(
+                "Project {} needs a negative outlay followed by at least one cash flow", name
+            ))),
+        }
+    }
+}
+
+/// Capital budgeting measures for one project
+#[derive(Debug, Clone)]
+pub struct ProjectMetrics {
+    pub name: String,
+    pub life: usize,
+    pub npv: f64,
+    pub mirr: f64,
+    pub profitability_index: f64,
+    /// `None` when the discounted flows never repay the outlay
+    pub discounted_payback: Option<f64>,
+    pub equivalent_annual_annuity: f64,
+    /// NPV of repeating the project to the common horizon of all projects compared;
+    /// `None` when that horizon exceeds [`MAX_REPLACEMENT_CHAIN_YEARS`]
+    pub replacement_chain_npv: Option<f64>,
+}
+
+/// A measure projects can be ranked by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectMetric {
+    Npv,
+    Mirr,
+    ProfitabilityIndex,
+    DiscountedPayback,
+    EquivalentAnnualAnnuity,
+    ReplacementChainNpv,
+}
+
+impl ProjectMetric {
+    /// Every metric, in display order
+    pub const ALL: [ProjectMetric; 6] = [
+        ProjectMetric::Npv,
+        ProjectMetric::Mirr,
+        ProjectMetric::ProfitabilityIndex,
+        ProjectMetric::DiscountedPayback,
+        ProjectMetric::EquivalentAnnualAnnuity,
+        ProjectMetric::ReplacementChainNpv,
+    ];
+
+    /// Human-readable name of the metric
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProjectMetric::Npv => "NPV",
+            ProjectMetric::Mirr => "MIRR",
+            ProjectMetric::ProfitabilityIndex => "Profitability Index",
+            ProjectMetric::DiscountedPayback => "Discounted Payback",
+            ProjectMetric::EquivalentAnnualAnnuity => "Equivalent Annual Annuity",
+            ProjectMetric::ReplacementChainNpv => "Replacement Chain NPV",
+        }
+    }
+
+    fn value(&self, metrics: &ProjectMetrics) -> Option<f64> {
+        match self {
+            ProjectMetric::Npv => Some(metrics.npv),
+            ProjectMetric::Mirr => Some(metrics.mirr),
+            ProjectMetric::ProfitabilityIndex => Some(metrics.profitability_index),
+            // A shorter payback is better, so rank on its negative
+            ProjectMetric::DiscountedPayback => metrics.discounted_payback.map(|years| -years),
+            ProjectMetric::EquivalentAnnualAnnuity => Some(metrics.equivalent_annual_annuity),
+            ProjectMetric::ReplacementChainNpv => metrics.replacement_chain_npv,
+        }
+    }
+}
+
+/// Calculates every capital budgeting measure for a set of projects
+/// 
+/// # Arguments
+/// * `projects` - The projects to compare
+/// * `discount_rate` - The rate for NPV, profitability index, payback and EAA (as a decimal)
+/// * `finance_rate` - The MIRR finance rate (as a decimal)
+/// * `reinvestment_rate` - The MIRR reinvestment rate (as a decimal)
+pub fn compare_projects(
+    projects: &[Project],
+    discount_rate: f64,
+    finance_rate: f64,
+    reinvestment_rate: f64
+) -> FinanceResult<Vec<ProjectMetrics>> {
+    if projects.is_empty() {
+        return Err(FinanceError::InvalidInput("At least one project is required".into()));
+    }
+    
+    let lives: Vec<usize> = projects.iter().map(|project| project.cash_flows.len()).collect();
+    let horizon = replacement_chain_horizon(&lives);
+    
+    projects
+        .iter()
+        .map(|project| {
+            let (initial, flows) = (project.initial_investment, project.cash_flows.as_slice());
+            Ok(ProjectMetrics {
+                name: project.name.clone(),
+                life: flows.len(),
+                npv: calculate_npv(initial, flows, discount_rate)?,
+                mirr: calculate_mirr(initial, flows, finance_rate, reinvestment_rate)?,
+                profitability_index: calculate_profitability_index(initial, flows, discount_rate)?,
+                discounted_payback: calculate_discounted_payback_period(initial, flows, discount_rate)?,
+                equivalent_annual_annuity: calculate_equivalent_annual_annuity(initial, flows, discount_rate)?,
+                replacement_chain_npv: horizon
+                    .map(|horizon| calculate_replacement_chain_npv(initial, flows, discount_rate, horizon))
+                    .transpose()?,
+            })
+        })
+        .collect()
+}
+
+/// Orders projects from best to worst on one metric
+/// 
+/// Projects whose discounted payback never arrives, or without a replacement
+/// chain NPV, rank last.
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{compare_projects, rank_projects, Project, ProjectMetric};
+/// 
+/// let projects: Vec<Project> = vec![
+///     "short=-1000,700,700".parse().unwrap(),
+///     "long=-1000,400,400,400,400".parse().unwrap(),
+/// ];
+/// let metrics = compare_projects(&projects, 0.10, 0.10, 0.10).unwrap();
+/// let by_npv = rank_projects(&metrics, ProjectMetric::Npv);
+/// assert_eq!(by_npv[0].name, "long");
+/// let by_eaa = rank_projects(&metrics, ProjectMetric::EquivalentAnnualAnnuity);
+/// assert_eq!(by_eaa[0].name, "short");
+/// ```
+pub fn rank_projects(metrics: &[ProjectMetrics], metric: ProjectMetric) -> Vec<&ProjectMetrics> {
+    let mut ranked: Vec<&ProjectMetrics> = metrics.iter().collect();
+    ranked.sort_by(|a, b| match (metric.value(a), metric.value(b)) {
+        (Some(a), Some(b)) => b.total_cmp(&a),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    ranked
+}
+
+/// Validates that every cash flow is a finite number
+fn validate_cash_flows(cash_flows: &[f64]) -> FinanceResult<()> {
+    if cash_flows.is_empty() {
+        return Err(FinanceError::InvalidInput("Cash flows cannot be empty".into()));
+    }
+    
+    for (year, &cash_flow) in cash_flows.iter().enumerate() {
+        validate_finite(cash_flow, &format!("Cash flow at year {}", year + 1))?;
+    }
+    
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -263,4 +691,79 @@ mod tests {
         let expected_return = calculate_capm(0.05, 0.0, 0.10).unwrap();
         assert_eq!(expected_return, 0.05);
     }
+
+    #[test]
+    fn test_mirr_separates_finance_and_reinvestment_rates() {
+        // 500 outflow in year 2 is financed at 6%; inflows are reinvested at 12%
+        let mirr = calculate_mirr(1000.0, &[800.0, -500.0, 900.0], 0.06, 0.12).unwrap();
+        let outflows = 1000.0 + 500.0 / 1.06_f64.powi(2);
+        let inflows = 800.0 * 1.12_f64.powi(2) + 900.0;
+        assert!((mirr - ((inflows / outflows).powf(1.0 / 3.0) - 1.0)).abs() < 1e-12);
+        
+        assert!(calculate_mirr(1000.0, &[-100.0, -100.0], 0.1, 0.1).is_err());
+    }
+
+    #[test]
+    fn test_irr_zeroes_npv() {
+        let irr = calculate_irr(1000.0, &[300.0, 400.0, 500.0]).unwrap();
+        assert!(calculate_npv(1000.0, &[300.0, 400.0, 500.0], irr).unwrap().abs() < 1e-6);
+        
+        // Getting back less than was invested gives a negative rate
+        let loss = calculate_irr(1000.0, &[0.0, 810.0]).unwrap();
+        assert!((loss + 0.10).abs() < 1e-9);
+        
+        assert!(calculate_irr(1000.0, &[0.0, -100.0]).is_err());
+    }
+
+    #[test]
+    fn test_discounted_payback() {
+        let payback = calculate_discounted_payback_period(1000.0, &[500.0, 500.0, 500.0], 0.10).unwrap();
+        // Discounted flows 454.55, 413.22 and 375.66 repay 1000 in year 3, counted as in payback-period
+        let expected = 3.0 + (1000.0 - 500.0 / 1.1 - 500.0 / 1.21) / (500.0 / 1.331);
+        assert!((payback.unwrap() - expected).abs() < 1e-9);
+        
+        assert_eq!(calculate_discounted_payback_period(1000.0, &[500.0, 500.0], 0.10).unwrap(), None);
+    }
+
+    #[test]
+    fn test_replacement_chain_matches_eaa_ranking() {
+        let projects: Vec<Project> = vec![
+            "a=-1000,700,700".parse().unwrap(),
+            "b=-1500,600,600,600".parse().unwrap(),
+        ];
+        assert_eq!(replacement_chain_horizon(&[2, 3]), Some(6));
+        
+        let metrics = compare_projects(&projects, 0.10, 0.10, 0.10).unwrap();
+        let by_chain = rank_projects(&metrics, ProjectMetric::ReplacementChainNpv);
+        let by_eaa = rank_projects(&metrics, ProjectMetric::EquivalentAnnualAnnuity);
+        assert_eq!(by_chain[0].name, by_eaa[0].name);
+        
+        assert!(calculate_replacement_chain_npv(1000.0, &[700.0, 700.0], 0.10, 3).is_err());
+        assert!(calculate_replacement_chain_npv(1000.0, &[700.0, 700.0], 0.10, 1002).is_err());
+    }
+    
+    #[test]
+    fn test_replacement_chain_unavailable_beyond_horizon_limit() {
+        let lives = [29, 31, 37, 41, 43, 47];
+        assert_eq!(replacement_chain_horizon(&lives), None);
+        assert_eq!(replacement_chain_horizon(&[usize::MAX, usize::MAX - 1]), None);
+        
+        let projects: Vec<Project> = lives.iter()
+            .map(|&life| Project { name: life.to_string(), initial_investment: 1000.0, cash_flows: vec![100.0; life] })
+            .collect();
+        let metrics = compare_projects(&projects, 0.08, 0.08, 0.08).unwrap();
+        assert!(metrics.iter().all(|project| project.replacement_chain_npv.is_none()));
+    }
+
+    #[test]
+    fn test_parse_project() {
+        let project: Project = "plant=-5000, 1500, 2000, 2500".parse().unwrap();
+        assert_eq!(project.name, "plant");
+        assert_eq!(project.initial_investment, 5000.0);
+        assert_eq!(project.cash_flows, vec![1500.0, 2000.0, 2500.0]);
+        
+        assert!("plant=5000,1500".parse::<Project>().is_err());
+        assert!("plant=-5000".parse::<Project>().is_err());
+        assert!("plant".parse::<Project>().is_err());
+    }
 }
\ No newline at end of file
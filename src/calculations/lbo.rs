@@ -0,0 +1,564 @@
+//! Leveraged buyout: debt paydown by tranche and sponsor equity returns
+
+use crate::{FinanceError, FinanceResult, validate_finite, validate_fraction, validate_non_negative, validate_positive};
+use crate::calculations::{calculate_irr, calculate_loan_payment};
+use serde::Deserialize;
+
+/// Where a tranche sits in the debt stack and how it is repaid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TrancheKind {
+    /// Amortizes with level payments over `amortization_years` and takes the cash sweep
+    Senior,
+    /// Pays cash interest and is repaid in full at exit
+    Subordinated,
+    /// Accrues interest onto the balance and is repaid in full at exit
+    Pik,
+}
+
+impl TrancheKind {
+    /// Human-readable name of the tranche type
+    pub fn name(&self) -> &'static str {
+        match self {
+            TrancheKind::Senior => "Senior amortizing",
+            TrancheKind::Subordinated => "Subordinated bullet",
+            TrancheKind::Pik => "PIK",
+        }
+    }
+}
+
+/// One layer of acquisition debt
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DebtTranche {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: TrancheKind,
+    /// Amount raised as a multiple of entry EBITDA
+    pub multiple: f64,
+    /// Annual interest rate (as a decimal)
+    pub rate: f64,
+    /// Term of the level-payment amortization schedule; senior tranches only
+    #[serde(default)]
+    pub amortization_years: Option<f64>,
+}
+
+impl DebtTranche {
+    fn validate(&self) -> FinanceResult<()> {
+        if self.name.trim().is_empty() {
+            return Err(FinanceError::InvalidInput("Tranche name cannot be empty".into()));
+        }
+        validate_non_negative(self.multiple, &format!("{} multiple", self.name))?;
+        validate_non_negative(self.rate, &format!("{} rate", self.name))?;
+        
+        match (self.kind, self.amortization_years) {
+            (TrancheKind::Senior, Some(years)) => validate_positive(years, &format!("{} amortization years", self.name)),
+            (TrancheKind::Senior, None) => Err(FinanceError::InvalidInput(format!(
+                "Senior tranche {} needs amortization_years", self.name
+            ))),
+            (_, Some(_)) => Err(FinanceError::InvalidInput(format!(
+                "Only senior tranches amortize; remove amortization_years from {}", self.name
+            ))),
+            (_, None) => Ok(()),
+        }
+    }
+
+    /// Balance the level-payment schedule leaves after `years`, ignoring prepayments
+    fn scheduled_balance(&self, principal: f64, years: u32) -> FinanceResult<f64> {
+        let term = self.amortization_years.unwrap_or(0.0);
+        let months = (years as f64 * 12.0).min(term * 12.0);
+        let payment = calculate_loan_payment(principal, self.rate * 100.0, term)?;
+        let monthly_rate = self.rate / 12.0;
+        
+        let balance = if monthly_rate == 0.0 {
+            principal - payment * months
+        } else {
+            let growth = (1.0 + monthly_rate).powf(months);
+            principal * growth - payment * (growth - 1.0) / monthly_rate
+        };
+        
+        Ok(balance.max(0.0))
+    }
+}
+
+/// Everything needed to run the buyout
+/// 
+/// Assumptions are written in TOML:
+/// 
+/// ```toml
+/// entry_ebitda = 100
+/// entry_multiple = 8.0
+/// exit_multiple = 8.0
+/// ebitda = [110, 120, 130, 140, 150]
+/// capex_ratio = 0.15
+/// tax_rate = 0.25
+/// transaction_fees = 0.02
+/// minimum_cash = 10
+/// cash_sweep = 1.0
+/// 
+/// [[tranches]]
+/// name = "Term Loan B"
+/// type = "senior"
+/// multiple = 3.0
+/// rate = 0.07
+/// amortization_years = 7
+/// 
+/// [[tranches]]
+/// name = "Senior Notes"
+/// type = "subordinated"
+/// multiple = 1.5
+/// rate = 0.09
+/// 
+/// [[tranches]]
+/// name = "HoldCo PIK"
+/// type = "pik"
+/// multiple = 0.5
+/// rate = 0.12
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LboAssumptions {
+    /// EBITDA over the twelve months before closing
+    pub entry_ebitda: f64,
+    /// Purchase enterprise value as a multiple of entry EBITDA
+    pub entry_multiple: f64,
+    /// Sale enterprise value as a multiple of final-year EBITDA
+    pub exit_multiple: f64,
+    /// Projected EBITDA for each year of the hold; exit is at the end of the last year
+    pub ebitda: Vec<f64>,
+    /// Capital expenditure as a share of EBITDA, also taken as the tax depreciation
+    #[serde(default)]
+    pub capex_ratio: f64,
+    #[serde(default)]
+    pub tax_rate: f64,
+    /// Transaction fees as a share of entry enterprise value, funded by the sponsor
+    #[serde(default)]
+    pub transaction_fees: f64,
+    /// Cash funded at closing and kept on the balance sheet
+    #[serde(default)]
+    pub minimum_cash: f64,
+    /// Share of cash above the minimum swept into prepaying senior debt each year
+    #[serde(default = "full_sweep")]
+    pub cash_sweep: f64,
+    /// Debt stack, senior tranches first
+    pub tranches: Vec<DebtTranche>,
+}
+
+fn full_sweep() -> f64 {
+    1.0
+}
+
+impl LboAssumptions {
+    /// Parses and validates assumptions from TOML
+    pub fn from_toml_str(contents: &str) -> FinanceResult<Self> {
+        let assumptions: LboAssumptions = toml::from_str(contents)
+            .map_err(|e| FinanceError::InvalidInput(format!("Invalid LBO assumptions: {}", e)))?;
+        assumptions.validate()?;
+        Ok(assumptions)
+    }
+
+    /// Checks that every assumption is usable
+    pub fn validate(&self) -> FinanceResult<()> {
+        validate_positive(self.entry_ebitda, "Entry EBITDA")?;
+        validate_positive(self.entry_multiple, "Entry multiple")?;
+        validate_positive(self.exit_multiple, "Exit multiple")?;
+        validate_non_negative(self.capex_ratio, "Capex ratio")?;
+        validate_non_negative(self.transaction_fees, "Transaction fees")?;
+        validate_non_negative(self.minimum_cash, "Minimum cash")?;
+        
+        if self.ebitda.is_empty() {
+            return Err(FinanceError::InvalidInput("At least one year of projected EBITDA is required".into()));
+        }
+        for (year, &ebitda) in self.ebitda.iter().enumerate() {
+            validate_finite(ebitda, &format!("EBITDA in year {}", year + 1))?;
+        }
+        if !(0.0..1.0).contains(&self.tax_rate) {
+            return Err(FinanceError::InvalidInput(format!("Tax rate must be between 0 and 1: {}", self.tax_rate)));
+        }
+        validate_fraction(self.cash_sweep, "Cash sweep")?;
+        if self.tranches.is_empty() {
+            return Err(FinanceError::InvalidInput("At least one debt tranche is required".into()));
+        }
+        for tranche in &self.tranches {
+            tranche.validate()?;
+        }
+        
+        Ok(())
+    }
+}
+
+/// A tranche's movements over one year
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrancheYear {
+    pub name: String,
+    pub kind: TrancheKind,
+    pub opening: f64,
+    /// Cash interest paid, or interest accrued onto the balance for PIK
+    pub interest: f64,
+    /// Scheduled amortization
+    pub mandatory: f64,
+    /// Prepayment from the cash sweep
+    pub sweep: f64,
+    pub closing: f64,
+}
+
+/// One year of the hold
+#[derive(Debug, Clone, PartialEq)]
+pub struct LboYear {
+    pub year: u32,
+    pub ebitda: f64,
+    pub capex: f64,
+    pub cash_interest: f64,
+    pub pik_interest: f64,
+    pub taxes: f64,
+    /// Cash left for repaying debt after capex, cash interest and taxes
+    pub free_cash_flow: f64,
+    pub tranches: Vec<TrancheYear>,
+    /// Closing cash balance
+    pub cash: f64,
+}
+
+impl LboYear {
+    /// Debt outstanding at the end of the year
+    pub fn total_debt(&self) -> f64 {
+        self.tranches.iter().map(|tranche| tranche.closing).sum()
+    }
+}
+
+/// Result of running the buyout
+#[derive(Debug, Clone, PartialEq)]
+pub struct LboAnalysis {
+    pub entry_enterprise_value: f64,
+    pub fees: f64,
+    /// Debt raised at closing across all tranches
+    pub entry_debt: f64,
+    /// Equity the sponsor contributes at closing
+    pub sponsor_equity: f64,
+    pub years: Vec<LboYear>,
+    pub exit_enterprise_value: f64,
+    /// Debt outstanding at exit less cash
+    pub exit_net_debt: f64,
+    /// Proceeds to the sponsor at exit
+    pub exit_equity: f64,
+    /// Multiple on invested capital
+    pub moic: f64,
+    /// Sponsor equity IRR; -100% when the equity is wiped out
+    pub irr: f64,
+}
+
+/// Runs a leveraged buyout and computes sponsor returns
+/// 
+/// Each tranche is sized as a multiple of entry EBITDA and the sponsor funds
+/// the rest of the purchase price, fees and minimum cash. Every year, interest
+/// is charged on opening balances: senior and subordinated tranches pay cash,
+/// PIK accrues. Free cash flow after capex, cash interest and taxes first
+/// meets senior scheduled amortization, then the sweep share of cash above
+/// the minimum prepays senior tranches in order. At exit, enterprise value
+/// less net debt goes to the sponsor.
+/// 
+/// # Arguments
+/// * `assumptions` - Entry and exit terms, projected EBITDA and the debt stack
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{run_lbo, DebtTranche, LboAssumptions, TrancheKind};
+/// 
+/// let assumptions = LboAssumptions {
+///     entry_ebitda: 100.0,
+///     entry_multiple: 8.0,
+///     exit_multiple: 8.0,
+///     ebitda: vec![110.0, 120.0, 130.0, 140.0, 150.0],
+///     capex_ratio: 0.15,
+///     tax_rate: 0.25,
+///     transaction_fees: 0.0,
+///     minimum_cash: 0.0,
+///     cash_sweep: 1.0,
+///     tranches: vec![DebtTranche {
+///         name: "Term Loan".into(),
+///         kind: TrancheKind::Senior,
+///         multiple: 4.0,
+///         rate: 0.07,
+///         amortization_years: Some(7.0),
+///     }],
+/// };
+/// 
+/// let lbo = run_lbo(&assumptions).unwrap();
+/// assert_eq!(lbo.sponsor_equity, 400.0);
+/// assert!(lbo.moic > 2.0);
+/// assert!(lbo.irr > 0.15);
+/// ```
+pub fn run_lbo(assumptions: &LboAssumptions) -> FinanceResult<LboAnalysis> {
+    simulate_lbo(assumptions)?.map_err(FinanceError::InvalidInput)
+}
+
+/// Runs the buyout, separating deals that cannot be financed from invalid input
+/// 
+/// The inner `Err` explains why the capital structure does not work: the debt
+/// leaves no sponsor equity, or free cash flow misses mandatory amortization.
+fn simulate_lbo(assumptions: &LboAssumptions) -> FinanceResult<Result<LboAnalysis, String>> {
+    assumptions.validate()?;
+    
+    let entry_enterprise_value = assumptions.entry_multiple * assumptions.entry_ebitda;
+    let fees = entry_enterprise_value * assumptions.transaction_fees;
+    let principals: Vec<f64> = assumptions.tranches.iter()
+        .map(|tranche| tranche.multiple * assumptions.entry_ebitda)
+        .collect();
+    let entry_debt: f64 = principals.iter().sum();
+    let sponsor_equity = entry_enterprise_value + fees + assumptions.minimum_cash - entry_debt;
+    
+    if sponsor_equity <= 0.0 {
+        return Ok(Err(format!(
+            "Debt of {:.2} leaves no sponsor equity in a purchase of {:.2}", entry_debt, entry_enterprise_value + fees
+        )));
+    }
+    
+    let mut balances = principals.clone();
+    let mut cash = assumptions.minimum_cash;
+    let mut years = Vec::with_capacity(assumptions.ebitda.len());
+    
+    for (index, &ebitda) in assumptions.ebitda.iter().enumerate() {
+        let year = index as u32 + 1;
+        let capex = ebitda * assumptions.capex_ratio;
+        
+        let mut tranches: Vec<TrancheYear> = Vec::with_capacity(balances.len());
+        for ((tranche, &principal), &opening) in assumptions.tranches.iter().zip(&principals).zip(&balances) {
+            let mandatory = match tranche.kind {
+                TrancheKind::Senior if principal > 0.0 => {
+                    let scheduled = tranche.scheduled_balance(principal, year - 1)? - tranche.scheduled_balance(principal, year)?;
+                    scheduled.min(opening)
+                }
+                _ => 0.0,
+            };
+            tranches.push(TrancheYear {
+                name: tranche.name.clone(),
+                kind: tranche.kind,
+                opening,
+                interest: opening * tranche.rate,
+                mandatory,
+                sweep: 0.0,
+                closing: 0.0,
+            });
+        }
+        
+        let (pik_interest, cash_interest) = tranches.iter()
+            .fold((0.0, 0.0), |(pik, paid), tranche| match tranche.kind {
+                TrancheKind::Pik => (pik + tranche.interest, paid),
+                _ => (pik, paid + tranche.interest),
+            });
+        let taxable_income = ebitda - capex - cash_interest - pik_interest;
+        let taxes = taxable_income.max(0.0) * assumptions.tax_rate;
+        let free_cash_flow = ebitda - capex - cash_interest - taxes;
+        
+        let mandatory: f64 = tranches.iter().map(|tranche| tranche.mandatory).sum();
+        cash += free_cash_flow - mandatory;
+        if cash < 0.0 {
+            return Ok(Err(format!(
+                "Year {}: free cash flow of {:.2} cannot cover mandatory amortization of {:.2}", year, free_cash_flow, mandatory
+            )));
+        }
+        
+        let mut sweep = (cash - assumptions.minimum_cash).max(0.0) * assumptions.cash_sweep;
+        for tranche in tranches.iter_mut() {
+            if tranche.kind == TrancheKind::Senior {
+                tranche.sweep = sweep.min(tranche.opening - tranche.mandatory);
+                sweep -= tranche.sweep;
+                cash -= tranche.sweep;
+            }
+            tranche.closing = match tranche.kind {
+                TrancheKind::Pik => tranche.opening + tranche.interest,
+                _ => tranche.opening - tranche.mandatory - tranche.sweep,
+            };
+        }
+        
+        balances = tranches.iter().map(|tranche| tranche.closing).collect();
+        years.push(LboYear {
+            year,
+            ebitda,
+            capex,
+            cash_interest,
+            pik_interest,
+            taxes,
+            free_cash_flow,
+            tranches,
+            cash,
+        });
+    }
+    
+    let final_ebitda = *assumptions.ebitda.last().expect("ebitda is validated as non-empty");
+    let exit_enterprise_value = assumptions.exit_multiple * final_ebitda;
+    let exit_net_debt = balances.iter().sum::<f64>() - cash;
+    let exit_equity = (exit_enterprise_value - exit_net_debt).max(0.0);
+    
+    let irr = if exit_equity > 0.0 {
+        let mut flows = vec![0.0; years.len()];
+        flows[years.len() - 1] = exit_equity;
+        calculate_irr(sponsor_equity, &flows)?
+    } else {
+        -1.0
+    };
+    
+    Ok(Ok(LboAnalysis {
+        entry_enterprise_value,
+        fees,
+        entry_debt,
+        sponsor_equity,
+        years,
+        exit_enterprise_value,
+        exit_net_debt,
+        exit_equity,
+        moic: exit_equity / sponsor_equity,
+        irr,
+    }))
+}
+
+/// Sponsor IRR for each pair of entry and exit multiples
+/// 
+/// Rows follow `entry_multiples` and columns follow `exit_multiples`. Debt is
+/// sized on EBITDA, so a higher entry multiple is funded entirely with more
+/// sponsor equity. A cell is `None` when that pair cannot be financed, for
+/// example when the debt leaves no sponsor equity; any other error, such as
+/// a non-positive multiple, fails the whole grid.
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{lbo_irr_grid, DebtTranche, LboAssumptions, TrancheKind};
+/// 
+/// let assumptions = LboAssumptions {
+///     entry_ebitda: 100.0,
+///     entry_multiple: 8.0,
+///     exit_multiple: 8.0,
+///     ebitda: vec![110.0, 120.0, 130.0],
+///     capex_ratio: 0.1,
+///     tax_rate: 0.25,
+///     transaction_fees: 0.0,
+///     minimum_cash: 0.0,
+///     cash_sweep: 1.0,
+///     tranches: vec![DebtTranche {
+///         name: "Notes".into(),
+///         kind: TrancheKind::Subordinated,
+///         multiple: 4.0,
+///         rate: 0.08,
+///         amortization_years: None,
+///     }],
+/// };
+/// 
+/// let grid = lbo_irr_grid(&assumptions, &[3.0, 7.0, 9.0], &[7.0, 9.0]).unwrap();
+/// assert_eq!(grid[0][0], None); // 4x of debt on a 3x purchase
+/// assert!(grid[1][1] > grid[1][0]); // selling higher helps
+/// assert!(grid[2][0] < grid[1][0]); // paying more hurts
+/// ```
+pub fn lbo_irr_grid(
+    assumptions: &LboAssumptions,
+    entry_multiples: &[f64],
+    exit_multiples: &[f64]
+) -> FinanceResult<Vec<Vec<Option<f64>>>> {
+    if entry_multiples.is_empty() || exit_multiples.is_empty() {
+        return Err(FinanceError::InvalidInput("The grid needs at least one entry and one exit multiple".into()));
+    }
+    
+    entry_multiples.iter()
+        .map(|&entry_multiple| {
+            exit_multiples.iter()
+                .map(|&exit_multiple| {
+                    let scenario = LboAssumptions { entry_multiple, exit_multiple, ..assumptions.clone() };
+                    Ok(simulate_lbo(&scenario)?.ok().map(|analysis| analysis.irr))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tranche(name: &str, kind: TrancheKind, multiple: f64, rate: f64) -> DebtTranche {
+        let amortization_years = (kind == TrancheKind::Senior).then_some(5.0);
+        DebtTranche { name: name.into(), kind, multiple, rate, amortization_years }
+    }
+
+    fn assumptions(tranches: Vec<DebtTranche>, cash_sweep: f64) -> LboAssumptions {
+        LboAssumptions {
+            entry_ebitda: 100.0,
+            entry_multiple: 8.0,
+            exit_multiple: 8.0,
+            ebitda: vec![100.0; 5],
+            capex_ratio: 0.2,
+            tax_rate: 0.25,
+            transaction_fees: 0.02,
+            minimum_cash: 10.0,
+            cash_sweep,
+            tranches,
+        }
+    }
+
+    #[test]
+    fn test_tranches_follow_their_repayment_terms() {
+        let stack = vec![
+            tranche("Term Loan", TrancheKind::Senior, 2.0, 0.06),
+            tranche("Notes", TrancheKind::Subordinated, 1.5, 0.09),
+            tranche("PIK", TrancheKind::Pik, 0.5, 0.12),
+        ];
+        let lbo = run_lbo(&assumptions(stack, 0.0)).unwrap();
+        
+        assert!((lbo.sponsor_equity - (800.0 + 16.0 + 10.0 - 400.0)).abs() < 1e-9);
+        
+        // Without a sweep the senior loan follows its level-payment schedule and is gone after five years
+        let senior: Vec<&TrancheYear> = lbo.years.iter().map(|year| &year.tranches[0]).collect();
+        assert!(senior.windows(2).all(|pair| pair[1].mandatory > pair[0].mandatory));
+        assert!(senior[4].closing.abs() < 1e-6);
+        assert!(senior.iter().all(|year| year.sweep == 0.0));
+        
+        let last = lbo.years.last().unwrap();
+        assert_eq!(last.tranches[1].closing, 150.0);
+        assert!((last.tranches[2].closing - 50.0 * 1.12_f64.powi(5)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sweep_prepays_senior_debt_and_keeps_minimum_cash() {
+        let stack = vec![tranche("Term Loan", TrancheKind::Senior, 2.5, 0.07)];
+        let swept = run_lbo(&assumptions(stack.clone(), 1.0)).unwrap();
+        let held = run_lbo(&assumptions(stack, 0.0)).unwrap();
+        
+        assert!(swept.years[0].tranches[0].sweep > 0.0);
+        assert!((swept.years[0].cash - 10.0).abs() < 1e-9);
+        assert!(swept.years[1].tranches[0].closing < held.years[1].tranches[0].closing);
+        
+        // Sweeping saves interest, so the sponsor ends up with more
+        assert!(swept.exit_equity > held.exit_equity);
+    }
+
+    #[test]
+    fn test_returns_match_irr_and_moic_definitions() {
+        let lbo = run_lbo(&assumptions(vec![tranche("Notes", TrancheKind::Subordinated, 4.0, 0.08)], 1.0)).unwrap();
+        
+        assert!((lbo.exit_equity - (800.0 - lbo.exit_net_debt)).abs() < 1e-9);
+        assert!((lbo.moic - lbo.exit_equity / lbo.sponsor_equity).abs() < 1e-12);
+        assert!((lbo.moic.powf(1.0 / 5.0) - 1.0 - lbo.irr).abs() < 1e-9);
+        
+        let grid = lbo_irr_grid(&assumptions(vec![tranche("Notes", TrancheKind::Subordinated, 4.0, 0.08)], 1.0), &[7.0, 8.0], &[8.0]).unwrap();
+        assert!((grid[1][0].unwrap() - lbo.irr).abs() < 1e-12);
+        assert!(grid[0][0] > grid[1][0]);
+    }
+
+    #[test]
+    fn test_rejects_unfinanceable_deals() {
+        // Debt above the purchase price leaves no equity
+        assert!(run_lbo(&assumptions(vec![tranche("Notes", TrancheKind::Subordinated, 9.0, 0.08)], 1.0)).is_err());
+        
+        // ...which only blanks that cell of the grid
+        let grid = lbo_irr_grid(&assumptions(vec![tranche("Notes", TrancheKind::Subordinated, 5.0, 0.08)], 1.0), &[4.0, 6.0], &[6.0]).unwrap();
+        assert!(grid[0][0].is_none());
+        assert!(grid[1][0].is_some());
+        
+        // ...while invalid multiples are still errors
+        assert!(lbo_irr_grid(&assumptions(vec![tranche("Notes", TrancheKind::Subordinated, 5.0, 0.08)], 1.0), &[6.0], &[0.0]).is_err());
+        
+        // Scheduled amortization larger than the business can pay
+        let mut heavy = assumptions(vec![tranche("Term Loan", TrancheKind::Senior, 7.0, 0.10)], 1.0);
+        heavy.tranches[0].amortization_years = Some(1.0);
+        assert!(run_lbo(&heavy).is_err());
+        
+        let mut pik_amortizing = tranche("PIK", TrancheKind::Pik, 1.0, 0.12);
+        pik_amortizing.amortization_years = Some(5.0);
+        assert!(run_lbo(&assumptions(vec![pik_amortizing], 1.0)).is_err());
+    }
+}
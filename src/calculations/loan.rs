@@ -1,15 +1,28 @@
 //! Loan and mortgage calculation functions
 
 use crate::{FinanceError, FinanceResult, validate_positive, validate_non_negative};
-use chrono::{Local, Months, NaiveDate};
+use chrono::{Datelike, Months, NaiveDate};
 
 /// Represents a single payment in an amortization schedule
 #[derive(Debug, Clone)]
 pub struct AmortizationPayment {
     pub month: u32,
+    pub payment_date: NaiveDate,
     pub principal_payment: f64,
     pub interest_payment: f64,
     pub remaining_balance: f64,
+    pub cumulative_principal: f64,
+    pub cumulative_interest: f64,
+}
+
+/// Principal and interest paid during one calendar year of a schedule
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnualAmortizationSummary {
+    pub year: i32,
+    pub payments: u32,
+    pub principal_paid: f64,
+    pub interest_paid: f64,
+    pub ending_balance: f64,
 }
 
 /// Calculates monthly loan payment using the standard loan payment formula
@@ -61,21 +74,26 @@ pub fn calculate_loan_payment(
 /// * `loan_amount` - The mortgage amount
 /// * `annual_interest_rate` - The annual interest rate as a percentage
 /// * `term_years` - The mortgage term in years
+/// * `first_payment_date` - The date the first payment is due
 /// 
 /// # Returns
 /// * A tuple containing (monthly_payment, total_interest, payoff_date)
 /// 
 /// # Examples
 /// ```
+/// use chrono::NaiveDate;
 /// use rusty_finance::calculations::calculate_mortgage_details;
 /// 
-/// let (payment, total_interest, _) = calculate_mortgage_details(200000.0, 4.5, 30).unwrap();
+/// let first_payment = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+/// let (payment, total_interest, payoff_date) = calculate_mortgage_details(200000.0, 4.5, 30, first_payment).unwrap();
 /// assert!((payment - 1013.37).abs() < 0.01);
+/// assert_eq!(payoff_date, NaiveDate::from_ymd_opt(2054, 12, 1).unwrap());
 /// ```
 pub fn calculate_mortgage_details(
     loan_amount: f64,
     annual_interest_rate: f64,
-    term_years: i32
+    term_years: i32,
+    first_payment_date: NaiveDate
 ) -> FinanceResult<(f64, f64, NaiveDate)> {
     validate_positive(loan_amount, "Loan amount")?;
     validate_positive(annual_interest_rate, "Annual interest rate")?;
@@ -89,67 +107,302 @@ pub fn calculate_mortgage_details(
     let total_amount_paid = monthly_payment * total_payments as f64;
     let total_interest = total_amount_paid - loan_amount;
     
-    // Calculate payoff date
-    let current_date = Local::now().naive_local().date();
-    let payoff_date = current_date + Months::new(total_payments as u32);
+    // The payoff date is the due date of the final payment
+    let payoff_date = calculate_payment_date(first_payment_date, total_payments as u32 - 1)?;
     
     Ok((monthly_payment, total_interest, payoff_date))
 }
 
+/// Calculates the due date of a payment in a monthly schedule
+/// 
+/// Payments keep the day of month of the first payment, clamped to shorter
+/// months. When the first payment falls on the last day of its month, every
+/// payment falls on the last day of its month.
+/// 
+/// # Arguments
+/// * `first_payment_date` - The date the first payment is due
+/// * `payments_after_first` - Number of monthly payments after the first (0 for the first payment)
+/// 
+/// # Examples
+/// ```
+/// use chrono::NaiveDate;
+/// use rusty_finance::calculations::calculate_payment_date;
+/// 
+/// let first = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+/// assert_eq!(calculate_payment_date(first, 1).unwrap(), NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+/// assert_eq!(calculate_payment_date(first, 2).unwrap(), NaiveDate::from_ymd_opt(2024, 3, 31).unwrap());
+/// ```
+pub fn calculate_payment_date(first_payment_date: NaiveDate, payments_after_first: u32) -> FinanceResult<NaiveDate> {
+    let date = first_payment_date
+        .checked_add_months(Months::new(payments_after_first))
+        .ok_or(FinanceError::Overflow)?;
+    
+    if is_last_day_of_month(first_payment_date) {
+        last_day_of_month(date)
+    } else {
+        Ok(date)
+    }
+}
+
+/// Returns true when the date is the final day of its month
+fn is_last_day_of_month(date: NaiveDate) -> bool {
+    date.succ_opt().is_some_and(|next| next.month() != date.month())
+}
+
+/// Returns the final day of the date's month
+fn last_day_of_month(date: NaiveDate) -> FinanceResult<NaiveDate> {
+    NaiveDate::from_ymd_opt(date.year(), date.month(), 1)
+        .and_then(|first| first.checked_add_months(Months::new(1)))
+        .and_then(|next_month| next_month.pred_opt())
+        .ok_or(FinanceError::Overflow)
+}
+
+/// Repayment structure used when building an amortization schedule
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoanStructure {
+    /// Level payments that fully amortize the loan over its term
+    FullyAmortizing,
+    /// Interest-only payments for the given number of months, then level
+    /// payments that amortize the balance over the rest of the term
+    InterestOnly { months: u32 },
+    /// Level payments sized to amortize over `amortization_years`, with the
+    /// outstanding balance due in full at the end of the loan term
+    Balloon { amortization_years: u32 },
+    /// Equal principal repayments each month, with interest on the declining balance
+    ConstantPrincipal,
+    /// Monthly payments taken from a user-supplied vector; any balance left
+    /// after the last payment remains outstanding
+    CustomPayments(Vec<f64>),
+}
+
 /// Generates a complete amortization schedule
 /// 
 /// # Arguments
 /// * `loan_amount` - The initial loan amount
 /// * `annual_interest_rate` - The annual interest rate as a percentage
 /// * `term_years` - The loan term in years
+/// * `first_payment_date` - The date the first payment is due
 /// 
 /// # Examples
 /// ```
+/// use chrono::NaiveDate;
 /// use rusty_finance::calculations::generate_amortization_schedule;
 /// 
-/// let schedule = generate_amortization_schedule(100000.0, 5.0, 30).unwrap();
+/// let first_payment = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+/// let schedule = generate_amortization_schedule(100000.0, 5.0, 30, first_payment).unwrap();
 /// assert_eq!(schedule.len(), 360); // 30 years * 12 months
+/// assert_eq!(schedule[359].payment_date, NaiveDate::from_ymd_opt(2054, 12, 1).unwrap());
 /// ```
 pub fn generate_amortization_schedule(
     loan_amount: f64,
     annual_interest_rate: f64,
-    term_years: i32
+    term_years: i32,
+    first_payment_date: NaiveDate
 ) -> FinanceResult<Vec<AmortizationPayment>> {
-    validate_positive(loan_amount, "Loan amount")?;
     validate_positive(annual_interest_rate, "Annual interest rate")?;
     
+    generate_structured_schedule(
+        loan_amount,
+        annual_interest_rate,
+        term_years,
+        &LoanStructure::FullyAmortizing,
+        first_payment_date
+    )
+}
+
+/// Generates an amortization schedule for the given repayment structure
+/// 
+/// For balloon loans `term_years` is the balloon term; the final row's
+/// principal payment includes the balloon. For custom payments the schedule
+/// length follows the payment vector and `term_years` is ignored.
+/// 
+/// # Arguments
+/// * `loan_amount` - The initial loan amount
+/// * `annual_interest_rate` - The annual interest rate as a percentage
+/// * `term_years` - The loan term in years
+/// * `structure` - The repayment structure
+/// * `first_payment_date` - The date the first payment is due
+/// 
+/// # Examples
+/// ```
+/// use chrono::NaiveDate;
+/// use rusty_finance::calculations::{generate_structured_schedule, LoanStructure};
+/// 
+/// // 30-year amortization with a 7-year balloon
+/// let structure = LoanStructure::Balloon { amortization_years: 30 };
+/// let first_payment = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+/// let schedule = generate_structured_schedule(1000000.0, 6.0, 7, &structure, first_payment).unwrap();
+/// assert_eq!(schedule.len(), 84);
+/// assert_eq!(schedule[83].remaining_balance, 0.0);
+/// ```
+pub fn generate_structured_schedule(
+    loan_amount: f64,
+    annual_interest_rate: f64,
+    term_years: i32,
+    structure: &LoanStructure,
+    first_payment_date: NaiveDate
+) -> FinanceResult<Vec<AmortizationPayment>> {
+    validate_positive(loan_amount, "Loan amount")?;
+    validate_non_negative(annual_interest_rate, "Annual interest rate")?;
+    
+    let monthly_rate = annual_interest_rate / 100.0 / 12.0;
+    
+    if let LoanStructure::CustomPayments(payments) = structure {
+        return custom_payment_schedule(loan_amount, monthly_rate, payments, first_payment_date);
+    }
+    
     if term_years <= 0 {
         return Err(FinanceError::InvalidInput("Term must be positive".into()));
     }
     
-    let monthly_payment = calculate_loan_payment(loan_amount, annual_interest_rate, term_years as f64)?;
-    let monthly_rate = annual_interest_rate / 100.0 / 12.0;
-    let total_payments = term_years * 12;
+    let total_payments = term_years as u32 * 12;
+    
+    // Months of interest-only payments before amortization starts
+    let interest_only_months = match structure {
+        LoanStructure::InterestOnly { months } => {
+            if *months >= total_payments {
+                return Err(FinanceError::InvalidInput(
+                    "Interest-only period must be shorter than the loan term".into()
+                ));
+            }
+            *months
+        }
+        _ => 0,
+    };
     
-    let mut schedule = Vec::with_capacity(total_payments as usize);
+    let level_payment = match structure {
+        LoanStructure::Balloon { amortization_years } => {
+            if *amortization_years < term_years as u32 {
+                return Err(FinanceError::InvalidInput(
+                    "Amortization period must be at least as long as the balloon term".into()
+                ));
+            }
+            calculate_loan_payment(loan_amount, annual_interest_rate, *amortization_years as f64)?
+        }
+        _ => {
+            let amortizing_years = (total_payments - interest_only_months) as f64 / 12.0;
+            calculate_loan_payment(loan_amount, annual_interest_rate, amortizing_years)?
+        }
+    };
+    
+    let constant_principal = loan_amount / total_payments as f64;
+    
+    let mut schedule: Vec<AmortizationPayment> = Vec::with_capacity(total_payments as usize);
     let mut remaining_balance = loan_amount;
     
     for month in 1..=total_payments {
         let interest_payment = remaining_balance * monthly_rate;
-        let principal_payment = monthly_payment - interest_payment;
-        remaining_balance -= principal_payment;
+        let mut principal_payment = match structure {
+            _ if month <= interest_only_months => 0.0,
+            LoanStructure::ConstantPrincipal => constant_principal,
+            _ => level_payment - interest_payment,
+        };
         
-        // Handle final payment rounding
+        // The final payment retires whatever is left, including any balloon
         if month == total_payments {
-            remaining_balance = 0.0;
+            principal_payment = remaining_balance;
         }
         
+        remaining_balance -= principal_payment;
+        
+        schedule.push(AmortizationPayment {
+            month,
+            payment_date: calculate_payment_date(first_payment_date, month - 1)?,
+            principal_payment,
+            interest_payment,
+            remaining_balance,
+            cumulative_principal: loan_amount - remaining_balance,
+            cumulative_interest: interest_payment + schedule.last().map_or(0.0, |p| p.cumulative_interest),
+        });
+    }
+    
+    Ok(schedule)
+}
+
+/// Builds a schedule from an explicit payment vector
+fn custom_payment_schedule(
+    loan_amount: f64,
+    monthly_rate: f64,
+    payments: &[f64],
+    first_payment_date: NaiveDate
+) -> FinanceResult<Vec<AmortizationPayment>> {
+    if payments.is_empty() {
+        return Err(FinanceError::InvalidInput("Payments cannot be empty".into()));
+    }
+    
+    let mut schedule: Vec<AmortizationPayment> = Vec::with_capacity(payments.len());
+    let mut remaining_balance = loan_amount;
+    
+    for (index, &payment) in payments.iter().enumerate() {
+        validate_non_negative(payment, &format!("Payment {}", index + 1))?;
+        
+        let interest_payment = remaining_balance * monthly_rate;
+        // Never collect more than is owed
+        let principal_payment = (payment - interest_payment).min(remaining_balance);
+        remaining_balance -= principal_payment;
+        
         schedule.push(AmortizationPayment {
-            month: month as u32,
+            month: index as u32 + 1,
+            payment_date: calculate_payment_date(first_payment_date, index as u32)?,
             principal_payment,
             interest_payment,
             remaining_balance,
+            cumulative_principal: loan_amount - remaining_balance,
+            cumulative_interest: interest_payment + schedule.last().map_or(0.0, |p| p.cumulative_interest),
         });
+        
+        if remaining_balance <= 0.0 {
+            break;
+        }
     }
     
     Ok(schedule)
 }
 
+/// Groups an amortization schedule by the calendar year of each payment date
+/// 
+/// Useful for tax reporting of the interest paid in each year.
+/// 
+/// # Arguments
+/// * `schedule` - The amortization schedule, ordered by payment date
+/// 
+/// # Examples
+/// ```
+/// use chrono::NaiveDate;
+/// use rusty_finance::calculations::{generate_amortization_schedule, summarize_schedule_by_year};
+/// 
+/// let first_payment = NaiveDate::from_ymd_opt(2025, 7, 1).unwrap();
+/// let schedule = generate_amortization_schedule(100000.0, 5.0, 30, first_payment).unwrap();
+/// let years = summarize_schedule_by_year(&schedule);
+/// assert_eq!(years[0].year, 2025);
+/// assert_eq!(years[0].payments, 6);
+/// ```
+pub fn summarize_schedule_by_year(schedule: &[AmortizationPayment]) -> Vec<AnnualAmortizationSummary> {
+    let mut summaries: Vec<AnnualAmortizationSummary> = Vec::new();
+    
+    for payment in schedule {
+        let year = payment.payment_date.year();
+        
+        match summaries.last_mut() {
+            Some(summary) if summary.year == year => {
+                summary.payments += 1;
+                summary.principal_paid += payment.principal_payment;
+                summary.interest_paid += payment.interest_payment;
+                summary.ending_balance = payment.remaining_balance;
+            }
+            _ => summaries.push(AnnualAmortizationSummary {
+                year,
+                payments: 1,
+                principal_paid: payment.principal_payment,
+                interest_paid: payment.interest_payment,
+                ending_balance: payment.remaining_balance,
+            }),
+        }
+    }
+    
+    summaries
+}
+
 /// Calculates break-even point in units
 /// 
 /// Formula: Break-even units = Fixed Costs / (Price per Unit - Variable Cost per Unit)
@@ -218,6 +471,10 @@ pub fn calculate_break_even_analysis(
 mod tests {
     use super::*;
 
+    fn first_payment() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
+    }
+
     #[test]
     fn test_loan_payment() {
         let payment = calculate_loan_payment(100000.0, 5.0, 30.0).unwrap();
@@ -239,14 +496,56 @@ mod tests {
 
     #[test]
     fn test_mortgage_details() {
-        let (payment, total_interest, _payoff_date) = calculate_mortgage_details(200000.0, 4.5, 30).unwrap();
+        let (payment, total_interest, payoff_date) = calculate_mortgage_details(200000.0, 4.5, 30, first_payment()).unwrap();
         assert!((payment - 1013.37).abs() < 0.01);
         assert!(total_interest > 0.0);
+        assert_eq!(payoff_date, NaiveDate::from_ymd_opt(2054, 12, 1).unwrap());
+    }
+
+    #[test]
+    fn test_payment_dates_end_of_month() {
+        // Month-end first payments stay on month-end
+        let first = NaiveDate::from_ymd_opt(2023, 2, 28).unwrap();
+        assert_eq!(calculate_payment_date(first, 1).unwrap(), NaiveDate::from_ymd_opt(2023, 3, 31).unwrap());
+        assert_eq!(calculate_payment_date(first, 2).unwrap(), NaiveDate::from_ymd_opt(2023, 4, 30).unwrap());
+        
+        // Other days are clamped to short months without drifting
+        let first = NaiveDate::from_ymd_opt(2023, 1, 30).unwrap();
+        assert_eq!(calculate_payment_date(first, 1).unwrap(), NaiveDate::from_ymd_opt(2023, 2, 28).unwrap());
+        assert_eq!(calculate_payment_date(first, 2).unwrap(), NaiveDate::from_ymd_opt(2023, 3, 30).unwrap());
+    }
+
+    #[test]
+    fn test_schedule_cumulative_totals() {
+        let schedule = generate_amortization_schedule(100000.0, 5.0, 30, first_payment()).unwrap();
+        let total_interest: f64 = schedule.iter().map(|p| p.interest_payment).sum();
+        let last = &schedule[359];
+        
+        assert!((last.cumulative_interest - total_interest).abs() < 1e-6);
+        assert!((last.cumulative_principal - 100000.0).abs() < 1e-6);
+        assert!((schedule[0].cumulative_interest - schedule[0].interest_payment).abs() < 1e-9);
+        assert_eq!(schedule[12].payment_date, NaiveDate::from_ymd_opt(2026, 1, 1).unwrap());
+    }
+
+    #[test]
+    fn test_summarize_schedule_by_year() {
+        let first = NaiveDate::from_ymd_opt(2025, 10, 15).unwrap();
+        let schedule = generate_amortization_schedule(100000.0, 5.0, 2, first).unwrap();
+        let years = summarize_schedule_by_year(&schedule);
+        
+        assert_eq!(years.len(), 3);
+        assert_eq!((years[0].year, years[0].payments), (2025, 3));
+        assert_eq!((years[1].year, years[1].payments), (2026, 12));
+        assert_eq!((years[2].year, years[2].payments), (2027, 9));
+        
+        let interest: f64 = years.iter().map(|y| y.interest_paid).sum();
+        assert!((interest - schedule[23].cumulative_interest).abs() < 1e-6);
+        assert_eq!(years[2].ending_balance, 0.0);
     }
 
     #[test]
     fn test_amortization_schedule() {
-        let schedule = generate_amortization_schedule(100000.0, 5.0, 30).unwrap();
+        let schedule = generate_amortization_schedule(100000.0, 5.0, 30, first_payment()).unwrap();
         assert_eq!(schedule.len(), 360);
         
         // First payment should have more interest than principal
@@ -258,6 +557,83 @@ mod tests {
         assert!((last_payment.remaining_balance).abs() < 0.01);
     }
 
+    #[test]
+    fn test_structured_schedule_fully_amortizing_matches_level() {
+        let level = generate_amortization_schedule(100000.0, 5.0, 30, first_payment()).unwrap();
+        let structured = generate_structured_schedule(100000.0, 5.0, 30, &LoanStructure::FullyAmortizing, first_payment()).unwrap();
+        assert_eq!(level.len(), structured.len());
+        assert!((level[0].principal_payment - structured[0].principal_payment).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_interest_only_schedule() {
+        let structure = LoanStructure::InterestOnly { months: 24 };
+        let schedule = generate_structured_schedule(120000.0, 6.0, 10, &structure, first_payment()).unwrap();
+        assert_eq!(schedule.len(), 120);
+        
+        // Interest-only months leave the balance untouched
+        assert_eq!(schedule[23].principal_payment, 0.0);
+        assert_eq!(schedule[23].remaining_balance, 120000.0);
+        assert!((schedule[0].interest_payment - 600.0).abs() < 1e-9);
+        
+        // Amortization starts afterwards and retires the loan
+        assert!(schedule[24].principal_payment > 0.0);
+        assert_eq!(schedule[119].remaining_balance, 0.0);
+        
+        let invalid = LoanStructure::InterestOnly { months: 120 };
+        assert!(generate_structured_schedule(120000.0, 6.0, 10, &invalid, first_payment()).is_err());
+    }
+
+    #[test]
+    fn test_balloon_schedule() {
+        let structure = LoanStructure::Balloon { amortization_years: 30 };
+        let schedule = generate_structured_schedule(1000000.0, 6.0, 7, &structure, first_payment()).unwrap();
+        assert_eq!(schedule.len(), 84);
+        
+        // Regular payments follow the 30-year amortization
+        let level_payment = calculate_loan_payment(1000000.0, 6.0, 30.0).unwrap();
+        let first = &schedule[0];
+        assert!((first.principal_payment + first.interest_payment - level_payment).abs() < 1e-6);
+        
+        // The final payment carries the balloon
+        let last = &schedule[83];
+        assert!(last.principal_payment > 800000.0);
+        assert_eq!(last.remaining_balance, 0.0);
+        
+        let invalid = LoanStructure::Balloon { amortization_years: 5 };
+        assert!(generate_structured_schedule(1000000.0, 6.0, 7, &invalid, first_payment()).is_err());
+    }
+
+    #[test]
+    fn test_constant_principal_schedule() {
+        let schedule = generate_structured_schedule(120000.0, 6.0, 10, &LoanStructure::ConstantPrincipal, first_payment()).unwrap();
+        assert_eq!(schedule.len(), 120);
+        assert!((schedule[0].principal_payment - 1000.0).abs() < 1e-9);
+        assert!((schedule[60].principal_payment - 1000.0).abs() < 1e-9);
+        assert!(schedule[0].interest_payment > schedule[60].interest_payment);
+        assert!(schedule[119].remaining_balance.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_custom_payment_schedule() {
+        let structure = LoanStructure::CustomPayments(vec![500.0, 500.0, 20000.0]);
+        let schedule = generate_structured_schedule(10000.0, 12.0, 1, &structure, first_payment()).unwrap();
+        
+        // Stops once the loan is repaid, capping the overpayment
+        assert_eq!(schedule.len(), 3);
+        assert!((schedule[0].interest_payment - 100.0).abs() < 1e-9);
+        assert!((schedule[0].principal_payment - 400.0).abs() < 1e-9);
+        assert_eq!(schedule[2].remaining_balance, 0.0);
+        
+        // Short payment vectors leave a balance outstanding
+        let partial = LoanStructure::CustomPayments(vec![500.0]);
+        let schedule = generate_structured_schedule(10000.0, 12.0, 1, &partial, first_payment()).unwrap();
+        assert!((schedule[0].remaining_balance - 9600.0).abs() < 1e-9);
+        
+        assert!(generate_structured_schedule(10000.0, 12.0, 1, &LoanStructure::CustomPayments(vec![]), first_payment()).is_err());
+        assert!(generate_structured_schedule(10000.0, 12.0, 1, &LoanStructure::CustomPayments(vec![-1.0]), first_payment()).is_err());
+    }
+
     #[test]
     fn test_break_even_units() {
         let units = calculate_break_even_units(1000.0, 10.0, 20.0).unwrap();
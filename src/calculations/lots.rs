@@ -0,0 +1,475 @@
+//! Capital gains lot tracking with FIFO, LIFO, HIFO, average cost and specific-ID matching
+
+use crate::{FinanceError, FinanceResult, validate_positive, validate_non_negative};
+use chrono::{Duration, Months, NaiveDate};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::Read;
+
+/// Days before and after a loss sale in which a purchase makes it a wash sale
+const WASH_SALE_WINDOW_DAYS: i64 = 30;
+
+/// Whether a trade buys or sells shares
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeAction {
+    Buy,
+    Sell,
+}
+
+/// A single buy or sell from a brokerage record
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Trade {
+    pub date: NaiveDate,
+    pub symbol: String,
+    pub action: TradeAction,
+    pub quantity: f64,
+    /// Price per share
+    pub price: f64,
+    /// Lot name for a buy, or the lot to sell from under specific-ID matching
+    #[serde(default)]
+    pub lot_id: Option<String>,
+}
+
+/// How sold shares are matched against open lots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LotMethod {
+    /// First in, first out
+    Fifo,
+    /// Last in, first out
+    Lifo,
+    /// Highest cost first
+    Hifo,
+    /// Average cost of all open shares, with holding periods taken first in, first out
+    AverageCost,
+    /// The lot named by the sell's `lot_id`
+    SpecificId,
+}
+
+/// Holding period classification of a gain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HoldingTerm {
+    /// Held one year or less
+    ShortTerm,
+    /// Held more than one year
+    LongTerm,
+}
+
+impl HoldingTerm {
+    /// Classifies a holding from its acquisition and disposal dates
+    pub fn between(acquired: NaiveDate, disposed: NaiveDate) -> Self {
+        match acquired.checked_add_months(Months::new(12)) {
+            Some(anniversary) if disposed > anniversary => HoldingTerm::LongTerm,
+            _ => HoldingTerm::ShortTerm,
+        }
+    }
+
+    /// Human-readable name of the holding term
+    pub fn name(&self) -> &'static str {
+        match self {
+            HoldingTerm::ShortTerm => "Short-Term",
+            HoldingTerm::LongTerm => "Long-Term",
+        }
+    }
+}
+
+/// Shares bought together and not yet sold
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lot {
+    pub id: String,
+    pub symbol: String,
+    pub acquired: NaiveDate,
+    pub quantity: f64,
+    pub cost_per_share: f64,
+}
+
+/// Gain or loss from selling part or all of one lot
+#[derive(Debug, Clone)]
+pub struct RealizedGain {
+    pub sale_date: NaiveDate,
+    pub symbol: String,
+    pub lot_id: String,
+    pub acquired: NaiveDate,
+    pub quantity: f64,
+    pub proceeds: f64,
+    pub cost_basis: f64,
+    pub gain: f64,
+    pub term: HoldingTerm,
+    /// A loss sale with a purchase of the same symbol within 30 days either side
+    pub wash_sale: bool,
+    /// Part of the loss disallowed by the wash sale rule (reported, not added to replacement basis)
+    pub disallowed_loss: f64,
+}
+
+/// Unrealized gain or loss on an open lot at a market price
+#[derive(Debug, Clone)]
+pub struct UnrealizedGain {
+    pub lot: Lot,
+    pub market_price: f64,
+    pub market_value: f64,
+    pub cost_basis: f64,
+    pub gain: f64,
+    pub term: HoldingTerm,
+}
+
+/// Realized gains and remaining open lots after matching every trade
+#[derive(Debug, Clone)]
+pub struct LotReport {
+    pub realized: Vec<RealizedGain>,
+    pub open_lots: Vec<Lot>,
+}
+
+impl LotReport {
+    /// Total realized gain for a holding term
+    pub fn total_gain(&self, term: HoldingTerm) -> f64 {
+        self.realized.iter().filter(|g| g.term == term).map(|g| g.gain).sum()
+    }
+}
+
+/// Reads trades from CSV with the header `date,symbol,action,quantity,price,lot_id`
+/// 
+/// The `lot_id` column is optional and may be left empty.
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{read_trades_csv, TradeAction};
+/// 
+/// let csv = "date,symbol,action,quantity,price,lot_id\n2024-01-05,AAPL,buy,10,185.50,\n";
+/// let trades = read_trades_csv(csv.as_bytes()).unwrap();
+/// assert_eq!(trades[0].action, TradeAction::Buy);
+/// ```
+pub fn read_trades_csv<R: Read>(reader: R) -> FinanceResult<Vec<Trade>> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(reader);
+    
+    csv_reader
+        .deserialize()
+        .enumerate()
+        .map(|(index, record)| {
+            record.map_err(|e| FinanceError::InvalidInput(format!("Invalid trade on row {}: {}", index + 1, e)))
+        })
+        .map(|trade: FinanceResult<Trade>| {
+            trade.map(|mut t| {
+                t.lot_id = t.lot_id.filter(|id| !id.is_empty());
+                t
+            })
+        })
+        .collect()
+}
+
+/// Matches sells against buys and reports realized gains and open lots
+/// 
+/// Trades are processed in date order (file order within a day). Buys
+/// without a `lot_id` are named after their position in the input, e.g. `L3`.
+/// 
+/// # Arguments
+/// * `trades` - Buys and sells, in any order
+/// * `method` - How sold shares are matched against open lots
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{match_lots, read_trades_csv, HoldingTerm, LotMethod};
+/// 
+/// let csv = "date,symbol,action,quantity,price,lot_id\n\
+///            2022-01-10,VTI,buy,10,200,\n\
+///            2024-03-01,VTI,buy,10,250,\n\
+///            2024-06-01,VTI,sell,10,260,\n";
+/// let trades = read_trades_csv(csv.as_bytes()).unwrap();
+/// 
+/// let fifo = match_lots(&trades, LotMethod::Fifo).unwrap();
+/// assert_eq!(fifo.total_gain(HoldingTerm::LongTerm), 600.0);
+/// 
+/// let hifo = match_lots(&trades, LotMethod::Hifo).unwrap();
+/// assert_eq!(hifo.total_gain(HoldingTerm::ShortTerm), 100.0);
+/// ```
+pub fn match_lots(trades: &[Trade], method: LotMethod) -> FinanceResult<LotReport> {
+    let mut ordered: Vec<(usize, &Trade)> = trades.iter().enumerate().collect();
+    ordered.sort_by_key(|(_, trade)| trade.date);
+    
+    let mut open: Vec<Lot> = Vec::new();
+    let mut realized = Vec::new();
+    
+    for (index, trade) in ordered {
+        validate_positive(trade.quantity, "Trade quantity")?;
+        validate_non_negative(trade.price, "Trade price")?;
+        
+        match trade.action {
+            TradeAction::Buy => {
+                let id = lot_id_for(index, trade);
+                if open.iter().any(|lot| lot.id == id) {
+                    return Err(FinanceError::InvalidInput(format!("Duplicate lot id: {}", id)));
+                }
+                open.push(Lot {
+                    id,
+                    symbol: trade.symbol.clone(),
+                    acquired: trade.date,
+                    quantity: trade.quantity,
+                    cost_per_share: trade.price,
+                });
+            }
+            TradeAction::Sell => {
+                realized.extend(sell(&mut open, trade, method)?);
+            }
+        }
+    }
+    
+    flag_wash_sales(&mut realized, trades);
+    
+    Ok(LotReport { realized, open_lots: open })
+}
+
+/// Values open lots at market prices keyed by symbol
+/// 
+/// Lots whose symbol has no price are skipped.
+/// 
+/// # Arguments
+/// * `open_lots` - Lots still held
+/// * `prices` - Current price per share by symbol
+/// * `as_of` - Date used to classify holding terms
+pub fn calculate_unrealized_gains(
+    open_lots: &[Lot],
+    prices: &HashMap<String, f64>,
+    as_of: NaiveDate
+) -> Vec<UnrealizedGain> {
+    open_lots
+        .iter()
+        .filter_map(|lot| {
+            let market_price = *prices.get(&lot.symbol)?;
+            let market_value = market_price * lot.quantity;
+            let cost_basis = lot.cost_per_share * lot.quantity;
+            
+            Some(UnrealizedGain {
+                lot: lot.clone(),
+                market_price,
+                market_value,
+                cost_basis,
+                gain: market_value - cost_basis,
+                term: HoldingTerm::between(lot.acquired, as_of),
+            })
+        })
+        .collect()
+}
+
+/// Name of the lot opened by the buy at `index` in the input
+fn lot_id_for(index: usize, buy: &Trade) -> String {
+    buy.lot_id.clone().unwrap_or_else(|| format!("L{}", index + 1))
+}
+
+/// Removes sold shares from open lots and returns one realized gain per lot touched
+fn sell(open: &mut Vec<Lot>, trade: &Trade, method: LotMethod) -> FinanceResult<Vec<RealizedGain>> {
+    let mut candidates: Vec<usize> = (0..open.len())
+        .filter(|&i| open[i].symbol == trade.symbol)
+        .collect();
+    
+    let held: f64 = candidates.iter().map(|&i| open[i].quantity).sum();
+    if trade.quantity > held + 1e-9 {
+        return Err(FinanceError::InvalidInput(format!(
+            "Sell of {} {} on {} exceeds the {} shares held", trade.quantity, trade.symbol, trade.date, held
+        )));
+    }
+    
+    match method {
+        LotMethod::Fifo | LotMethod::AverageCost => {}
+        LotMethod::Lifo => candidates.reverse(),
+        LotMethod::Hifo => candidates.sort_by(|&a, &b| open[b].cost_per_share.total_cmp(&open[a].cost_per_share)),
+        LotMethod::SpecificId => {
+            let id = trade.lot_id.as_ref().ok_or_else(|| {
+                FinanceError::InvalidInput(format!("Sell of {} on {} needs a lot_id for specific-ID matching", trade.symbol, trade.date))
+            })?;
+            candidates.retain(|&i| &open[i].id == id);
+            let available: f64 = candidates.iter().map(|&i| open[i].quantity).sum();
+            if candidates.is_empty() || trade.quantity > available + 1e-9 {
+                return Err(FinanceError::InvalidInput(format!(
+                    "Lot {} does not hold {} shares of {}", id, trade.quantity, trade.symbol
+                )));
+            }
+        }
+    }
+    
+    if method == LotMethod::AverageCost {
+        let cost: f64 = candidates.iter().map(|&i| open[i].cost_per_share * open[i].quantity).sum();
+        let average = cost / held;
+        for &i in &candidates {
+            open[i].cost_per_share = average;
+        }
+    }
+    
+    let mut remaining = trade.quantity;
+    let mut gains = Vec::new();
+    
+    for &i in &candidates {
+        if remaining <= 1e-9 {
+            break;
+        }
+        
+        let lot = &mut open[i];
+        let quantity = remaining.min(lot.quantity);
+        lot.quantity -= quantity;
+        remaining -= quantity;
+        
+        let proceeds = quantity * trade.price;
+        let cost_basis = quantity * lot.cost_per_share;
+        
+        gains.push(RealizedGain {
+            sale_date: trade.date,
+            symbol: trade.symbol.clone(),
+            lot_id: lot.id.clone(),
+            acquired: lot.acquired,
+            quantity,
+            proceeds,
+            cost_basis,
+            gain: proceeds - cost_basis,
+            term: HoldingTerm::between(lot.acquired, trade.date),
+            wash_sale: false,
+            disallowed_loss: 0.0,
+        });
+    }
+    
+    open.retain(|lot| lot.quantity > 1e-9);
+    Ok(gains)
+}
+
+/// Flags loss sales with replacement purchases within 30 days before or after the sale
+/// 
+/// Each replacement share can only wash one share of loss.
+fn flag_wash_sales(realized: &mut [RealizedGain], trades: &[Trade]) {
+    let window = Duration::days(WASH_SALE_WINDOW_DAYS);
+    let mut replacements: Vec<(usize, f64)> = trades
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.action == TradeAction::Buy)
+        .map(|(i, t)| (i, t.quantity))
+        .collect();
+    
+    for gain in realized.iter_mut().filter(|g| g.gain < 0.0) {
+        let mut washed = 0.0;
+        
+        for (index, available) in replacements.iter_mut() {
+            let buy = &trades[*index];
+            let in_window = buy.date >= gain.sale_date - window && buy.date <= gain.sale_date + window;
+            // The sold shares themselves are not a replacement purchase
+            let is_sold_lot = lot_id_for(*index, buy) == gain.lot_id;
+            
+            if buy.symbol != gain.symbol || !in_window || is_sold_lot || *available <= 0.0 {
+                continue;
+            }
+            
+            let used = (gain.quantity - washed).min(*available);
+            *available -= used;
+            washed += used;
+            if washed >= gain.quantity {
+                break;
+            }
+        }
+        
+        if washed > 0.0 {
+            gain.wash_sale = true;
+            gain.disallowed_loss = -gain.gain * washed / gain.quantity;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn trade(on: NaiveDate, action: TradeAction, quantity: f64, price: f64) -> Trade {
+        Trade { date: on, symbol: "ABC".into(), action, quantity, price, lot_id: None }
+    }
+
+    fn sample_trades() -> Vec<Trade> {
+        vec![
+            trade(date(2022, 1, 3), TradeAction::Buy, 10.0, 100.0),
+            trade(date(2023, 6, 1), TradeAction::Buy, 10.0, 150.0),
+            trade(date(2023, 9, 1), TradeAction::Buy, 10.0, 120.0),
+            trade(date(2024, 2, 1), TradeAction::Sell, 15.0, 140.0),
+        ]
+    }
+
+    #[test]
+    fn test_fifo_and_lifo() {
+        let fifo = match_lots(&sample_trades(), LotMethod::Fifo).unwrap();
+        assert_eq!(fifo.realized.len(), 2);
+        // All 10 shares bought at 100 were held over a year; the 5 bought at 150 were not
+        assert_eq!(fifo.realized[0].gain, 400.0);
+        assert_eq!(fifo.realized[0].term, HoldingTerm::LongTerm);
+        assert_eq!(fifo.realized[1].gain, -50.0);
+        assert_eq!(fifo.realized[1].term, HoldingTerm::ShortTerm);
+        assert_eq!(fifo.open_lots.len(), 2);
+        assert_eq!(fifo.open_lots[0].quantity, 5.0);
+        
+        let lifo = match_lots(&sample_trades(), LotMethod::Lifo).unwrap();
+        assert_eq!(lifo.realized[0].lot_id, "L3");
+        assert_eq!(lifo.total_gain(HoldingTerm::ShortTerm), 200.0 - 50.0);
+        assert_eq!(lifo.open_lots[0].id, "L1");
+    }
+
+    #[test]
+    fn test_hifo_and_average_cost() {
+        let hifo = match_lots(&sample_trades(), LotMethod::Hifo).unwrap();
+        assert_eq!(hifo.realized[0].lot_id, "L2");
+        assert_eq!(hifo.realized[1].lot_id, "L3");
+        assert_eq!(hifo.total_gain(HoldingTerm::ShortTerm), -100.0 + 100.0);
+        
+        let average = match_lots(&sample_trades(), LotMethod::AverageCost).unwrap();
+        let basis: f64 = average.realized.iter().map(|g| g.cost_basis).sum();
+        assert!((basis - 15.0 * (3700.0 / 30.0)).abs() < 1e-9);
+        assert!(average.open_lots.iter().all(|lot| (lot.cost_per_share - 3700.0 / 30.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_specific_id() {
+        let mut trades = sample_trades();
+        trades[2].lot_id = Some("cheap".into());
+        trades[3].lot_id = Some("cheap".into());
+        trades[3].quantity = 4.0;
+        
+        let report = match_lots(&trades, LotMethod::SpecificId).unwrap();
+        assert_eq!(report.realized[0].lot_id, "cheap");
+        assert_eq!(report.realized[0].gain, 80.0);
+        
+        trades[3].quantity = 15.0;
+        assert!(match_lots(&trades, LotMethod::SpecificId).is_err());
+        trades[3].lot_id = None;
+        assert!(match_lots(&trades, LotMethod::SpecificId).is_err());
+    }
+
+    #[test]
+    fn test_wash_sale() {
+        let trades = vec![
+            trade(date(2024, 1, 2), TradeAction::Buy, 10.0, 50.0),
+            trade(date(2024, 3, 1), TradeAction::Sell, 10.0, 40.0),
+            trade(date(2024, 3, 20), TradeAction::Buy, 4.0, 41.0),
+        ];
+        let report = match_lots(&trades, LotMethod::Fifo).unwrap();
+        let sale = &report.realized[0];
+        assert!(sale.wash_sale);
+        assert!((sale.disallowed_loss - 40.0).abs() < 1e-9);
+        
+        // A repurchase after 30 days does not trigger the rule
+        let mut later = trades.clone();
+        later[2].date = date(2024, 4, 5);
+        assert!(!match_lots(&later, LotMethod::Fifo).unwrap().realized[0].wash_sale);
+    }
+
+    #[test]
+    fn test_oversell_and_unrealized() {
+        let mut trades = sample_trades();
+        trades[3].quantity = 31.0;
+        assert!(match_lots(&trades, LotMethod::Fifo).is_err());
+        
+        let report = match_lots(&sample_trades(), LotMethod::Fifo).unwrap();
+        let prices = HashMap::from([("ABC".to_string(), 130.0)]);
+        let unrealized = calculate_unrealized_gains(&report.open_lots, &prices, date(2024, 8, 1));
+        assert_eq!(unrealized.len(), 2);
+        assert_eq!(unrealized[0].gain, -100.0);
+        assert_eq!(unrealized[0].term, HoldingTerm::LongTerm);
+        assert_eq!(unrealized[1].term, HoldingTerm::ShortTerm);
+    }
+}
@@ -1,14 +1,56 @@
 //! Financial calculation modules
 
+pub mod covenants;
+pub mod cvp;
+pub mod debt;
+pub mod decision;
+pub mod dividend;
+pub mod fx;
+pub mod goal_seek;
+pub mod inflation;
 pub mod interest;
 pub mod investment;
+pub mod lbo;
 pub mod loan;
+pub mod lots;
+pub mod model;
+pub mod property;
 pub mod statistics;
 pub mod ratios;
+pub mod real_option;
+pub mod refinance;
+pub mod retirement;
+pub mod scenario;
+pub mod sensitivity;
+pub mod tax;
+pub mod tax_accounts;
+pub mod valuation;
+pub mod working_capital;
 
 // Re-export commonly used functions
+pub use covenants::*;
+pub use cvp::*;
+pub use debt::*;
+pub use decision::*;
+pub use dividend::*;
+pub use fx::*;
+pub use goal_seek::*;
+pub use inflation::*;
 pub use interest::*;
 pub use investment::*;
+pub use lbo::*;
 pub use loan::*;
+pub use lots::*;
+pub use model::*;
+pub use property::*;
 pub use statistics::*;
-pub use ratios::*;
\ No newline at end of file
+pub use ratios::*;
+pub use real_option::*;
+pub use refinance::*;
+pub use retirement::*;
+pub use scenario::*;
+pub use sensitivity::*;
+pub use tax::*;
+pub use tax_accounts::*;
+pub use valuation::*;
+pub use working_capital::*;
\ No newline at end of file
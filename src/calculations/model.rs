@@ -0,0 +1,669 @@
+//! Three-statement model: projected income statement, balance sheet and cash flow statement
+
+use crate::{FinanceError, FinanceResult, validate_finite, validate_fraction, validate_non_negative, validate_positive};
+use crate::calculations::{
+    calculate_current_ratio, calculate_debt_to_equity, calculate_quick_ratio, calculate_roa, calculate_roe, Assumption,
+};
+use serde::Deserialize;
+
+/// Days per year used to turn working-capital days into balances
+const DAYS_PER_YEAR: f64 = 365.0;
+
+/// Largest number of passes when solving for circular interest
+const MAX_INTEREST_ITERATIONS: usize = 100;
+
+/// Interest is solved when successive passes differ by less than this
+const INTEREST_TOLERANCE: f64 = 1e-9;
+
+/// Largest gap between assets and liabilities plus equity accepted as balanced
+const BALANCE_TOLERANCE: f64 = 0.01;
+
+/// Base-year income statement and closing balance sheet
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BaseYear {
+    pub revenue: f64,
+    pub cost_of_goods_sold: f64,
+    pub operating_expenses: f64,
+    pub depreciation: f64,
+    pub interest_expense: f64,
+    pub cash: f64,
+    pub receivables: f64,
+    pub inventory: f64,
+    /// Net property, plant and equipment
+    pub ppe: f64,
+    pub payables: f64,
+    /// Term debt
+    pub debt: f64,
+    #[serde(default)]
+    pub revolver: f64,
+    pub equity: f64,
+}
+
+/// Operating drivers of the projection
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ModelDrivers {
+    pub revenue_growth: Assumption,
+    pub gross_margin: Assumption,
+    /// Operating expenses as a share of revenue
+    pub operating_expense_ratio: Assumption,
+    /// Depreciation as a share of opening PP&E
+    pub depreciation_rate: Assumption,
+    /// Capital expenditure as a share of revenue
+    pub capex_ratio: Assumption,
+    pub days_sales_outstanding: f64,
+    pub days_inventory_outstanding: f64,
+    pub days_payables_outstanding: f64,
+    pub tax_rate: f64,
+    /// Share of positive net income paid as dividends
+    #[serde(default)]
+    pub dividend_payout: f64,
+}
+
+/// How the model closes a cash shortfall or uses surplus cash
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FinancingMode {
+    /// Draw on the revolver to keep minimum cash and repay it from surplus cash
+    #[default]
+    Revolver,
+    /// As revolver, then sweep remaining surplus cash into prepaying term debt
+    CashSweep,
+}
+
+/// Debt terms and the cash the business must keep
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FinancingAssumptions {
+    #[serde(default)]
+    pub mode: FinancingMode,
+    #[serde(default)]
+    pub minimum_cash: f64,
+    /// Interest rate on term debt
+    pub debt_rate: f64,
+    /// Scheduled term-debt repayment each year
+    #[serde(default = "no_repayment")]
+    pub debt_repayment: Assumption,
+    pub revolver_rate: f64,
+    /// Most the revolver can be drawn; unlimited when absent
+    #[serde(default)]
+    pub revolver_limit: Option<f64>,
+    /// Interest earned on cash
+    #[serde(default)]
+    pub cash_rate: f64,
+}
+
+fn no_repayment() -> Assumption {
+    Assumption::Constant(0.0)
+}
+
+/// Everything needed to project the three statements
+/// 
+/// Assumptions are written in TOML:
+/// 
+/// ```toml
+/// years = 3
+/// 
+/// [base]
+/// revenue = 1000
+/// cost_of_goods_sold = 600
+/// operating_expenses = 200
+/// depreciation = 40
+/// interest_expense = 24
+/// cash = 100
+/// receivables = 150
+/// inventory = 120
+/// ppe = 800
+/// payables = 90
+/// debt = 400
+/// equity = 680
+/// 
+/// [drivers]
+/// revenue_growth = [0.10, 0.08, 0.06]
+/// gross_margin = 0.40
+/// operating_expense_ratio = 0.20
+/// depreciation_rate = 0.05
+/// capex_ratio = 0.06
+/// days_sales_outstanding = 55
+/// days_inventory_outstanding = 73
+/// days_payables_outstanding = 55
+/// tax_rate = 0.25
+/// dividend_payout = 0.30
+/// 
+/// [financing]
+/// mode = "cash-sweep"
+/// minimum_cash = 50
+/// debt_rate = 0.06
+/// debt_repayment = 40
+/// revolver_rate = 0.08
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ModelAssumptions {
+    pub years: u32,
+    pub base: BaseYear,
+    pub drivers: ModelDrivers,
+    pub financing: FinancingAssumptions,
+}
+
+/// One year's income statement
+#[derive(Debug, Clone, Default)]
+pub struct IncomeStatement {
+    pub revenue: f64,
+    pub cost_of_goods_sold: f64,
+    pub gross_profit: f64,
+    pub operating_expenses: f64,
+    pub ebitda: f64,
+    pub depreciation: f64,
+    pub ebit: f64,
+    /// Interest on term debt and the revolver less interest earned on cash
+    pub interest_expense: f64,
+    pub pre_tax_income: f64,
+    pub taxes: f64,
+    pub net_income: f64,
+}
+
+/// A closing balance sheet
+#[derive(Debug, Clone, Default)]
+pub struct BalanceSheet {
+    pub cash: f64,
+    pub receivables: f64,
+    pub inventory: f64,
+    pub ppe: f64,
+    pub payables: f64,
+    pub revolver: f64,
+    pub debt: f64,
+    pub equity: f64,
+}
+
+impl BalanceSheet {
+    pub fn current_assets(&self) -> f64 {
+        self.cash + self.receivables + self.inventory
+    }
+
+    pub fn total_assets(&self) -> f64 {
+        self.current_assets() + self.ppe
+    }
+
+    /// Payables plus the revolver
+    pub fn current_liabilities(&self) -> f64 {
+        self.payables + self.revolver
+    }
+
+    /// Term debt plus the revolver
+    pub fn total_debt(&self) -> f64 {
+        self.debt + self.revolver
+    }
+
+    pub fn total_liabilities_and_equity(&self) -> f64 {
+        self.current_liabilities() + self.debt + self.equity
+    }
+}
+
+/// One year's cash flow statement; outflows are negative
+#[derive(Debug, Clone, Default)]
+pub struct CashFlowStatement {
+    pub net_income: f64,
+    pub depreciation: f64,
+    pub change_in_working_capital: f64,
+    pub operating: f64,
+    pub capex: f64,
+    pub investing: f64,
+    pub debt_repayment: f64,
+    /// Revolver drawn (positive) or repaid (negative)
+    pub revolver: f64,
+    pub dividends: f64,
+    pub financing: f64,
+    pub net_change_in_cash: f64,
+}
+
+/// Ratios for one year from the existing ratio functions; `None` where a ratio is undefined
+#[derive(Debug, Clone, Default)]
+pub struct YearRatios {
+    pub current_ratio: Option<f64>,
+    pub quick_ratio: Option<f64>,
+    pub debt_to_equity: Option<f64>,
+    /// Return on equity (as a decimal)
+    pub return_on_equity: Option<f64>,
+    /// Return on assets (as a decimal)
+    pub return_on_assets: Option<f64>,
+    /// EBIT over net interest expense
+    pub interest_coverage: Option<f64>,
+}
+
+impl YearRatios {
+    /// Calculates the ratios from a year's statements
+    pub fn from_statements(income: &IncomeStatement, balance: &BalanceSheet) -> YearRatios {
+        YearRatios {
+            current_ratio: calculate_current_ratio(balance.current_assets(), balance.current_liabilities()).ok(),
+            quick_ratio: calculate_quick_ratio(balance.current_assets(), balance.inventory, balance.current_liabilities()).ok(),
+            debt_to_equity: calculate_debt_to_equity(balance.total_debt(), balance.equity).ok(),
+            return_on_equity: calculate_roe(income.net_income, balance.equity).ok().map(|roe| roe / 100.0),
+            return_on_assets: calculate_roa(income.net_income, balance.total_assets()).ok().map(|roa| roa / 100.0),
+            interest_coverage: (income.interest_expense > 0.0).then(|| income.ebit / income.interest_expense),
+        }
+    }
+}
+
+/// Statements for one projected year
+#[derive(Debug, Clone)]
+pub struct ModelYear {
+    pub year: u32,
+    pub income: IncomeStatement,
+    pub balance: BalanceSheet,
+    pub cash_flow: CashFlowStatement,
+    /// Passes needed to solve the circular interest calculation
+    pub interest_iterations: usize,
+}
+
+/// The base year and every projected year
+#[derive(Debug, Clone)]
+pub struct FinancialModel {
+    pub base_income: IncomeStatement,
+    pub base_balance: BalanceSheet,
+    pub years: Vec<ModelYear>,
+}
+
+impl ModelAssumptions {
+    /// Parses and validates model assumptions written in TOML
+    pub fn from_toml_str(contents: &str) -> FinanceResult<Self> {
+        let assumptions: ModelAssumptions = toml::from_str(contents)
+            .map_err(|e| FinanceError::InvalidInput(format!("Invalid model assumptions: {}", e)))?;
+        assumptions.validate()?;
+        Ok(assumptions)
+    }
+
+    /// Checks that every assumption is usable and the base balance sheet balances
+    pub fn validate(&self) -> FinanceResult<()> {
+        if self.years == 0 {
+            return Err(FinanceError::InvalidInput("At least one projected year is required".into()));
+        }
+        
+        let base = &self.base;
+        validate_positive(base.revenue, "Base revenue")?;
+        for (value, name) in [
+            (base.cost_of_goods_sold, "Base cost of goods sold"),
+            (base.operating_expenses, "Base operating expenses"),
+            (base.depreciation, "Base depreciation"),
+            (base.cash, "Base cash"),
+            (base.receivables, "Base receivables"),
+            (base.inventory, "Base inventory"),
+            (base.ppe, "Base PP&E"),
+            (base.payables, "Base payables"),
+            (base.debt, "Base debt"),
+            (base.revolver, "Base revolver"),
+        ] {
+            validate_non_negative(value, name)?;
+        }
+        validate_finite(base.interest_expense, "Base interest expense")?;
+        validate_finite(base.equity, "Base equity")?;
+        
+        let balance = self.base_balance();
+        let gap = balance.total_assets() - balance.total_liabilities_and_equity();
+        if gap.abs() > BALANCE_TOLERANCE {
+            return Err(FinanceError::InvalidInput(format!(
+                "Base balance sheet does not balance: assets {:.2} vs liabilities and equity {:.2}",
+                balance.total_assets(), balance.total_liabilities_and_equity()
+            )));
+        }
+        
+        let drivers = &self.drivers;
+        drivers.revenue_growth.validate(self.years, "Revenue growth")?;
+        drivers.gross_margin.validate(self.years, "Gross margin")?;
+        drivers.operating_expense_ratio.validate(self.years, "Operating expense ratio")?;
+        drivers.depreciation_rate.validate(self.years, "Depreciation rate")?;
+        drivers.capex_ratio.validate(self.years, "Capex ratio")?;
+        validate_non_negative(drivers.days_sales_outstanding, "Days sales outstanding")?;
+        validate_non_negative(drivers.days_inventory_outstanding, "Days inventory outstanding")?;
+        validate_non_negative(drivers.days_payables_outstanding, "Days payables outstanding")?;
+        validate_fraction(drivers.dividend_payout, "Dividend payout")?;
+        validate_fraction(drivers.tax_rate, "Tax rate")?;
+        if drivers.tax_rate >= 1.0 {
+            return Err(FinanceError::InvalidInput(format!("Tax rate must be below 100%: {}", drivers.tax_rate)));
+        }
+        
+        let financing = &self.financing;
+        financing.debt_repayment.validate(self.years, "Debt repayment")?;
+        validate_non_negative(financing.minimum_cash, "Minimum cash")?;
+        validate_non_negative(financing.debt_rate, "Debt rate")?;
+        validate_non_negative(financing.revolver_rate, "Revolver rate")?;
+        validate_non_negative(financing.cash_rate, "Cash rate")?;
+        if let Some(limit) = financing.revolver_limit {
+            validate_non_negative(limit, "Revolver limit")?;
+        }
+        
+        Ok(())
+    }
+
+    fn base_balance(&self) -> BalanceSheet {
+        let base = &self.base;
+        BalanceSheet {
+            cash: base.cash,
+            receivables: base.receivables,
+            inventory: base.inventory,
+            ppe: base.ppe,
+            payables: base.payables,
+            revolver: base.revolver,
+            debt: base.debt,
+            equity: base.equity,
+        }
+    }
+
+    fn base_income(&self) -> IncomeStatement {
+        let base = &self.base;
+        let gross_profit = base.revenue - base.cost_of_goods_sold;
+        let ebitda = gross_profit - base.operating_expenses;
+        let ebit = ebitda - base.depreciation;
+        let pre_tax_income = ebit - base.interest_expense;
+        let taxes = pre_tax_income.max(0.0) * self.drivers.tax_rate;
+        
+        IncomeStatement {
+            revenue: base.revenue,
+            cost_of_goods_sold: base.cost_of_goods_sold,
+            gross_profit,
+            operating_expenses: base.operating_expenses,
+            ebitda,
+            depreciation: base.depreciation,
+            ebit,
+            interest_expense: base.interest_expense,
+            pre_tax_income,
+            taxes,
+            net_income: pre_tax_income - taxes,
+        }
+    }
+}
+
+/// Projects the income statement, balance sheet and cash flow statement
+/// 
+/// Working-capital balances follow from days outstanding, PP&E rolls forward
+/// with capex and depreciation, and equity with retained earnings. Cash below
+/// the minimum is covered by the revolver, which surplus cash repays first;
+/// in cash-sweep mode any surplus left then prepays term debt. Interest is
+/// charged on average balances, which depend on interest itself, so each year
+/// is recalculated until interest stops changing.
+/// 
+/// # Arguments
+/// * `assumptions` - Base year, drivers and financing terms
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{project_financial_model, ModelAssumptions};
+/// 
+/// let assumptions = ModelAssumptions::from_toml_str(r#"
+///     years = 2
+///     [base]
+///     revenue = 1000
+///     cost_of_goods_sold = 600
+///     operating_expenses = 200
+///     depreciation = 40
+///     interest_expense = 24
+///     cash = 100
+///     receivables = 150
+///     inventory = 120
+///     ppe = 800
+///     payables = 90
+///     debt = 400
+///     equity = 680
+///     [drivers]
+///     revenue_growth = 0.05
+///     gross_margin = 0.40
+///     operating_expense_ratio = 0.20
+///     depreciation_rate = 0.05
+///     capex_ratio = 0.06
+///     days_sales_outstanding = 55
+///     days_inventory_outstanding = 73
+///     days_payables_outstanding = 55
+///     tax_rate = 0.25
+///     [financing]
+///     debt_rate = 0.06
+///     revolver_rate = 0.08
+/// "#).unwrap();
+/// 
+/// let model = project_financial_model(&assumptions).unwrap();
+/// for year in &model.years {
+///     let balance = &year.balance;
+///     assert!((balance.total_assets() - balance.total_liabilities_and_equity()).abs() < 1e-6);
+/// }
+/// ```
+pub fn project_financial_model(assumptions: &ModelAssumptions) -> FinanceResult<FinancialModel> {
+    assumptions.validate()?;
+    
+    let base_income = assumptions.base_income();
+    let base_balance = assumptions.base_balance();
+    let mut years = Vec::with_capacity(assumptions.years as usize);
+    let (mut revenue, mut opening) = (base_income.revenue, base_balance.clone());
+    
+    for index in 0..assumptions.years as usize {
+        let year = project_year(assumptions, index, revenue, &opening)?;
+        revenue = year.income.revenue;
+        opening = year.balance.clone();
+        years.push(year);
+    }
+    
+    Ok(FinancialModel { base_income, base_balance, years })
+}
+
+/// Projects one year from the previous year's revenue and closing balance sheet
+fn project_year(
+    assumptions: &ModelAssumptions,
+    index: usize,
+    previous_revenue: f64,
+    opening: &BalanceSheet
+) -> FinanceResult<ModelYear> {
+    let drivers = &assumptions.drivers;
+    let financing = &assumptions.financing;
+    let year = index as u32 + 1;
+    
+    let revenue = previous_revenue * (1.0 + drivers.revenue_growth.for_year(index));
+    let cost_of_goods_sold = revenue * (1.0 - drivers.gross_margin.for_year(index));
+    let gross_profit = revenue - cost_of_goods_sold;
+    let operating_expenses = revenue * drivers.operating_expense_ratio.for_year(index);
+    let ebitda = gross_profit - operating_expenses;
+    let depreciation = opening.ppe * drivers.depreciation_rate.for_year(index);
+    let ebit = ebitda - depreciation;
+    let capex = revenue * drivers.capex_ratio.for_year(index);
+    
+    let receivables = revenue * drivers.days_sales_outstanding / DAYS_PER_YEAR;
+    let inventory = cost_of_goods_sold * drivers.days_inventory_outstanding / DAYS_PER_YEAR;
+    let payables = cost_of_goods_sold * drivers.days_payables_outstanding / DAYS_PER_YEAR;
+    let change_in_working_capital = -(receivables - opening.receivables)
+        - (inventory - opening.inventory)
+        + (payables - opening.payables);
+    let scheduled_repayment = financing.debt_repayment.for_year(index).min(opening.debt);
+    
+    // Everything below depends on interest, which depends on the closing balances
+    let close = |interest_expense: f64| {
+        let pre_tax_income = ebit - interest_expense;
+        let taxes = pre_tax_income.max(0.0) * drivers.tax_rate;
+        let net_income = pre_tax_income - taxes;
+        let dividends = net_income.max(0.0) * drivers.dividend_payout;
+        
+        let operating = net_income + depreciation + change_in_working_capital;
+        let cash_before_financing = opening.cash + operating - capex - scheduled_repayment - dividends;
+        
+        let shortfall = financing.minimum_cash - cash_before_financing;
+        let revolver_draw = if shortfall > 0.0 {
+            shortfall
+        } else if opening.revolver > 0.0 {
+            shortfall.max(-opening.revolver)
+        } else {
+            0.0
+        };
+        let mut cash = cash_before_financing + revolver_draw;
+        
+        let prepayment = match financing.mode {
+            FinancingMode::Revolver => 0.0,
+            FinancingMode::CashSweep => (cash - financing.minimum_cash).max(0.0).min(opening.debt - scheduled_repayment),
+        };
+        cash -= prepayment;
+        
+        let balance = BalanceSheet {
+            cash,
+            receivables,
+            inventory,
+            ppe: opening.ppe + capex - depreciation,
+            payables,
+            revolver: opening.revolver + revolver_draw,
+            debt: opening.debt - scheduled_repayment - prepayment,
+            equity: opening.equity + net_income - dividends,
+        };
+        let income = IncomeStatement {
+            revenue,
+            cost_of_goods_sold,
+            gross_profit,
+            operating_expenses,
+            ebitda,
+            depreciation,
+            ebit,
+            interest_expense,
+            pre_tax_income,
+            taxes,
+            net_income,
+        };
+        let debt_repayment = -(scheduled_repayment + prepayment);
+        let cash_flow = CashFlowStatement {
+            net_income,
+            depreciation,
+            change_in_working_capital,
+            operating,
+            capex: -capex,
+            investing: -capex,
+            debt_repayment,
+            revolver: revolver_draw,
+            dividends: -dividends,
+            financing: debt_repayment + revolver_draw - dividends,
+            net_change_in_cash: cash - opening.cash,
+        };
+        (income, balance, cash_flow)
+    };
+    
+    let average_interest = |closing: &BalanceSheet| {
+        financing.debt_rate * (opening.debt + closing.debt) / 2.0
+            + financing.revolver_rate * (opening.revolver + closing.revolver) / 2.0
+            - financing.cash_rate * (opening.cash + closing.cash) / 2.0
+    };
+    
+    let mut interest_expense = average_interest(opening);
+    for iteration in 1..=MAX_INTEREST_ITERATIONS {
+        let (income, balance, cash_flow) = close(interest_expense);
+        let solved_interest = average_interest(&balance);
+        
+        if (solved_interest - interest_expense).abs() < INTEREST_TOLERANCE {
+            if let Some(limit) = financing.revolver_limit {
+                if balance.revolver > limit + BALANCE_TOLERANCE {
+                    return Err(FinanceError::InvalidInput(format!(
+                        "Year {}: the revolver needs {:.2}, above its limit of {:.2}", year, balance.revolver, limit
+                    )));
+                }
+            }
+            
+            return Ok(ModelYear { year, income, balance, cash_flow, interest_iterations: iteration });
+        }
+        interest_expense = solved_interest;
+    }
+    
+    Err(FinanceError::ConvergenceFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assumptions(mode: FinancingMode) -> ModelAssumptions {
+        ModelAssumptions {
+            years: 5,
+            base: BaseYear {
+                revenue: 1000.0,
+                cost_of_goods_sold: 600.0,
+                operating_expenses: 200.0,
+                depreciation: 40.0,
+                interest_expense: 24.0,
+                cash: 100.0,
+                receivables: 150.0,
+                inventory: 120.0,
+                ppe: 800.0,
+                payables: 90.0,
+                debt: 400.0,
+                revolver: 0.0,
+                equity: 680.0,
+            },
+            drivers: ModelDrivers {
+                revenue_growth: Assumption::Constant(0.08),
+                gross_margin: Assumption::Constant(0.40),
+                operating_expense_ratio: Assumption::Constant(0.20),
+                depreciation_rate: Assumption::Constant(0.05),
+                capex_ratio: Assumption::Constant(0.06),
+                days_sales_outstanding: 55.0,
+                days_inventory_outstanding: 73.0,
+                days_payables_outstanding: 55.0,
+                tax_rate: 0.25,
+                dividend_payout: 0.30,
+            },
+            financing: FinancingAssumptions {
+                mode,
+                minimum_cash: 50.0,
+                debt_rate: 0.06,
+                debt_repayment: Assumption::Constant(40.0),
+                revolver_rate: 0.08,
+                revolver_limit: None,
+                cash_rate: 0.02,
+            },
+        }
+    }
+
+    #[test]
+    fn test_statements_tie_out_every_year() {
+        let model = project_financial_model(&assumptions(FinancingMode::Revolver)).unwrap();
+        let mut opening_cash = model.base_balance.cash;
+        
+        for year in &model.years {
+            let (balance, cash_flow) = (&year.balance, &year.cash_flow);
+            assert!((balance.total_assets() - balance.total_liabilities_and_equity()).abs() < 1e-6);
+            assert!((cash_flow.operating + cash_flow.investing + cash_flow.financing - cash_flow.net_change_in_cash).abs() < 1e-6);
+            assert!((opening_cash + cash_flow.net_change_in_cash - balance.cash).abs() < 1e-9);
+            assert!(balance.cash >= 50.0 - 1e-9);
+            opening_cash = balance.cash;
+        }
+        
+        let last = &model.years[4];
+        let ratios = YearRatios::from_statements(&last.income, &last.balance);
+        assert!((ratios.return_on_equity.unwrap() - last.income.net_income / last.balance.equity).abs() < 1e-9);
+        assert!(ratios.current_ratio.unwrap() > ratios.quick_ratio.unwrap());
+    }
+
+    #[test]
+    fn test_circular_interest_matches_average_balances() {
+        let model = project_financial_model(&assumptions(FinancingMode::Revolver)).unwrap();
+        let (first, opening) = (&model.years[0], &model.base_balance);
+        
+        let expected = 0.06 * (opening.debt + first.balance.debt) / 2.0
+            + 0.08 * (opening.revolver + first.balance.revolver) / 2.0
+            - 0.02 * (opening.cash + first.balance.cash) / 2.0;
+        assert!((first.income.interest_expense - expected).abs() < 1e-8);
+        assert!(first.interest_iterations > 1);
+    }
+
+    #[test]
+    fn test_cash_sweep_prepays_debt_down_to_minimum_cash() {
+        let revolver = project_financial_model(&assumptions(FinancingMode::Revolver)).unwrap();
+        let sweep = project_financial_model(&assumptions(FinancingMode::CashSweep)).unwrap();
+        
+        let (kept, swept) = (&revolver.years[4].balance, &sweep.years[4].balance);
+        assert!(swept.debt < kept.debt);
+        assert!(swept.debt < 1e-9 || (swept.cash - 50.0).abs() < 1e-6);
+        assert!(sweep.years[4].income.net_income > revolver.years[4].income.net_income);
+    }
+
+    #[test]
+    fn test_revolver_covers_shortfalls_within_its_limit() {
+        let mut heavy_capex = assumptions(FinancingMode::Revolver);
+        heavy_capex.drivers.capex_ratio = Assumption::Constant(0.30);
+        let model = project_financial_model(&heavy_capex).unwrap();
+        assert!(model.years[0].balance.revolver > 0.0);
+        assert!((model.years[0].balance.cash - 50.0).abs() < 1e-6);
+        
+        heavy_capex.financing.revolver_limit = Some(10.0);
+        assert!(project_financial_model(&heavy_capex).is_err());
+        
+        let mut unbalanced = assumptions(FinancingMode::Revolver);
+        unbalanced.base.equity = 700.0;
+        assert!(project_financial_model(&unbalanced).is_err());
+    }
+}
@@ -0,0 +1,301 @@
+//! Rental property investment: NOI projection, financing, cap rate and hold-period returns
+
+use crate::{FinanceError, FinanceResult, validate_fraction, validate_non_negative, validate_positive};
+use crate::calculations::{calculate_future_value, calculate_irr, calculate_loan_payment, generate_amortization_schedule};
+use chrono::NaiveDate;
+
+/// Assumptions for buying, operating and selling a rental property
+#[derive(Debug, Clone)]
+pub struct PropertyInputs {
+    pub purchase_price: f64,
+    /// Closing costs as a fraction of the price
+    pub closing_cost_rate: f64,
+    /// Gross potential rent per month in the first year
+    pub monthly_rent: f64,
+    /// Annual rent increase (as a decimal)
+    pub rent_growth: f64,
+    /// Share of gross rent lost to vacancy and collections
+    pub vacancy_rate: f64,
+    /// Operating expenses in the first year (taxes, insurance, management, repairs)
+    pub operating_expenses: f64,
+    /// Annual increase in operating expenses and capex reserves (as a decimal)
+    pub expense_growth: f64,
+    /// Capital expenditure reserve in the first year, set aside below NOI
+    pub capex_reserve: f64,
+    /// Down payment as a fraction of the price; 1.0 buys with cash
+    pub down_payment_rate: f64,
+    /// The annual mortgage rate as a percentage
+    pub mortgage_rate: f64,
+    pub mortgage_term_years: i32,
+    /// Annual property value appreciation (as a decimal)
+    pub appreciation_rate: f64,
+    /// Selling costs as a fraction of the sale price
+    pub selling_cost_rate: f64,
+    pub hold_years: u32,
+}
+
+/// One year of operations
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyYear {
+    pub year: u32,
+    pub gross_rent: f64,
+    pub vacancy_loss: f64,
+    pub effective_gross_income: f64,
+    pub operating_expenses: f64,
+    pub net_operating_income: f64,
+    pub capex_reserve: f64,
+    pub interest: f64,
+    pub principal: f64,
+    /// Cash flow after capex reserves and debt service
+    pub cash_flow: f64,
+    /// NOI over the purchase price
+    pub cap_rate: f64,
+    /// Cash flow over the equity invested
+    pub cash_on_cash: f64,
+    /// `None` when the property carries no debt that year
+    pub debt_service_coverage: Option<f64>,
+}
+
+impl PropertyYear {
+    /// Principal plus interest paid during the year
+    pub fn debt_service(&self) -> f64 {
+        self.interest + self.principal
+    }
+
+    /// Cash flow before debt service
+    pub fn unlevered_cash_flow(&self) -> f64 {
+        self.net_operating_income - self.capex_reserve
+    }
+}
+
+/// Result of analyzing a rental property over the hold period
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyAnalysis {
+    pub loan_amount: f64,
+    pub monthly_payment: f64,
+    /// Down payment plus closing costs
+    pub equity_invested: f64,
+    /// First-year NOI over the purchase price
+    pub going_in_cap_rate: f64,
+    pub years: Vec<PropertyYear>,
+    pub sale_price: f64,
+    pub selling_costs: f64,
+    /// Mortgage balance repaid from the sale
+    pub loan_payoff: f64,
+    /// Sale price less selling costs and the loan payoff
+    pub exit_proceeds: f64,
+    /// Final-year NOI over the sale price
+    pub exit_cap_rate: f64,
+    /// IRR on the whole purchase before financing; `None` if the flows never turn positive
+    pub unlevered_irr: Option<f64>,
+    /// IRR on the equity invested; `None` if the flows never turn positive
+    pub levered_irr: Option<f64>,
+    /// Total cash returned to equity over the equity invested
+    pub equity_multiple: f64,
+}
+
+/// Projects a rental property's NOI, cash flow and returns over a hold period
+/// 
+/// Rent grows at the rent growth rate; operating expenses and capex reserves
+/// grow at the expense growth rate. The mortgage is a level-payment loan and
+/// its balance is repaid from the sale at the end of the hold.
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{analyze_property, PropertyInputs};
+/// 
+/// let inputs = PropertyInputs {
+///     purchase_price: 500000.0, closing_cost_rate: 0.02, monthly_rent: 4200.0,
+///     rent_growth: 0.03, vacancy_rate: 0.05, operating_expenses: 15000.0,
+///     expense_growth: 0.025, capex_reserve: 2500.0, down_payment_rate: 0.25,
+///     mortgage_rate: 6.5, mortgage_term_years: 30, appreciation_rate: 0.03,
+///     selling_cost_rate: 0.06, hold_years: 10,
+/// };
+/// let analysis = analyze_property(&inputs).unwrap();
+/// assert_eq!(analysis.loan_amount, 375000.0);
+/// assert!((analysis.going_in_cap_rate - 0.0658).abs() < 0.0001);
+/// assert!(analysis.levered_irr.unwrap() > analysis.unlevered_irr.unwrap());
+/// ```
+pub fn analyze_property(inputs: &PropertyInputs) -> FinanceResult<PropertyAnalysis> {
+    validate_positive(inputs.purchase_price, "Purchase price")?;
+    validate_positive(inputs.monthly_rent, "Monthly rent")?;
+    for (value, name) in [
+        (inputs.closing_cost_rate, "Closing cost rate"),
+        (inputs.rent_growth, "Rent growth"),
+        (inputs.operating_expenses, "Operating expenses"),
+        (inputs.expense_growth, "Expense growth"),
+        (inputs.capex_reserve, "Capex reserve"),
+        (inputs.appreciation_rate, "Appreciation rate"),
+        (inputs.selling_cost_rate, "Selling cost rate"),
+    ] {
+        validate_non_negative(value, name)?;
+    }
+    
+    validate_fraction(inputs.vacancy_rate, "Vacancy rate")?;
+    validate_fraction(inputs.down_payment_rate, "Down payment rate")?;
+    
+    if inputs.hold_years == 0 {
+        return Err(FinanceError::InvalidInput("Hold period must be at least one year".into()));
+    }
+    
+    let loan_amount = inputs.purchase_price * (1.0 - inputs.down_payment_rate);
+    let equity_invested = inputs.purchase_price * (inputs.down_payment_rate + inputs.closing_cost_rate);
+    let (monthly_payment, schedule) = if loan_amount > 0.0 {
+        (
+            calculate_loan_payment(loan_amount, inputs.mortgage_rate, inputs.mortgage_term_years as f64)?,
+            generate_amortization_schedule(loan_amount, inputs.mortgage_rate, inputs.mortgage_term_years, NaiveDate::default())?,
+        )
+    } else {
+        (0.0, Vec::new())
+    };
+    
+    let mut years = Vec::with_capacity(inputs.hold_years as usize);
+    for year in 1..=inputs.hold_years {
+        let elapsed = (year - 1) as f64;
+        let gross_rent = calculate_future_value(inputs.monthly_rent * 12.0, inputs.rent_growth, elapsed)?;
+        let vacancy_loss = gross_rent * inputs.vacancy_rate;
+        let effective_gross_income = gross_rent - vacancy_loss;
+        // Expenses may be zero, which calculate_future_value rejects
+        let expense_factor = (1.0 + inputs.expense_growth).powf(elapsed);
+        let operating_expenses = inputs.operating_expenses * expense_factor;
+        let capex_reserve = inputs.capex_reserve * expense_factor;
+        let net_operating_income = effective_gross_income - operating_expenses;
+        
+        let start = (((year - 1) * 12) as usize).min(schedule.len());
+        let payments = &schedule[start..((year * 12) as usize).min(schedule.len())];
+        let interest: f64 = payments.iter().map(|payment| payment.interest_payment).sum();
+        let principal: f64 = payments.iter().map(|payment| payment.principal_payment).sum();
+        let cash_flow = net_operating_income - capex_reserve - interest - principal;
+        
+        years.push(PropertyYear {
+            year,
+            gross_rent,
+            vacancy_loss,
+            effective_gross_income,
+            operating_expenses,
+            net_operating_income,
+            capex_reserve,
+            interest,
+            principal,
+            cash_flow,
+            cap_rate: net_operating_income / inputs.purchase_price,
+            cash_on_cash: cash_flow / equity_invested,
+            debt_service_coverage: (!payments.is_empty()).then(|| net_operating_income / (interest + principal)),
+        });
+    }
+    
+    let sale_price = calculate_future_value(inputs.purchase_price, inputs.appreciation_rate, inputs.hold_years as f64)?;
+    let selling_costs = sale_price * inputs.selling_cost_rate;
+    let payoff_index = (inputs.hold_years * 12) as usize;
+    let loan_payoff = if payoff_index <= schedule.len() { schedule[payoff_index - 1].remaining_balance } else { 0.0 };
+    let exit_proceeds = sale_price - selling_costs - loan_payoff;
+    
+    let mut unlevered_flows: Vec<f64> = years.iter().map(PropertyYear::unlevered_cash_flow).collect();
+    let mut levered_flows: Vec<f64> = years.iter().map(|year| year.cash_flow).collect();
+    *unlevered_flows.last_mut().expect("hold is at least one year") += sale_price - selling_costs;
+    *levered_flows.last_mut().expect("hold is at least one year") += exit_proceeds;
+    
+    let total_cost = inputs.purchase_price * (1.0 + inputs.closing_cost_rate);
+    let unlevered_irr = calculate_irr(total_cost, &unlevered_flows).ok();
+    let (levered_irr, equity_multiple) = if equity_invested > 0.0 {
+        (calculate_irr(equity_invested, &levered_flows).ok(), levered_flows.iter().sum::<f64>() / equity_invested)
+    } else {
+        (None, 0.0)
+    };
+    
+    let final_noi = years.last().expect("hold is at least one year").net_operating_income;
+    
+    Ok(PropertyAnalysis {
+        loan_amount,
+        monthly_payment,
+        equity_invested,
+        going_in_cap_rate: years[0].cap_rate,
+        years,
+        sale_price,
+        selling_costs,
+        loan_payoff,
+        exit_proceeds,
+        exit_cap_rate: final_noi / sale_price,
+        unlevered_irr,
+        levered_irr,
+        equity_multiple,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs() -> PropertyInputs {
+        PropertyInputs {
+            purchase_price: 400000.0,
+            closing_cost_rate: 0.0,
+            monthly_rent: 3000.0,
+            rent_growth: 0.0,
+            vacancy_rate: 0.05,
+            operating_expenses: 10000.0,
+            expense_growth: 0.0,
+            capex_reserve: 1000.0,
+            down_payment_rate: 0.25,
+            mortgage_rate: 6.0,
+            mortgage_term_years: 30,
+            appreciation_rate: 0.0,
+            selling_cost_rate: 0.0,
+            hold_years: 5,
+        }
+    }
+
+    #[test]
+    fn test_first_year_operations() {
+        let analysis = analyze_property(&inputs()).unwrap();
+        let year = &analysis.years[0];
+        
+        assert_eq!(year.gross_rent, 36000.0);
+        assert!((year.vacancy_loss - 1800.0).abs() < 1e-9);
+        assert!((year.net_operating_income - 24200.0).abs() < 1e-9);
+        assert!((analysis.going_in_cap_rate - 0.0605).abs() < 1e-12);
+        
+        let debt_service = analysis.monthly_payment * 12.0;
+        assert!((year.debt_service() - debt_service).abs() < 1e-6);
+        assert!((year.cash_flow - (24200.0 - 1000.0 - debt_service)).abs() < 1e-6);
+        assert!((year.cash_on_cash - year.cash_flow / 100000.0).abs() < 1e-12);
+        assert!((year.debt_service_coverage.unwrap() - 24200.0 / debt_service).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_all_cash_purchase_at_flat_value_earns_the_cash_yield() {
+        let analysis = analyze_property(&PropertyInputs { down_payment_rate: 1.0, ..inputs() }).unwrap();
+        
+        assert_eq!(analysis.loan_amount, 0.0);
+        assert_eq!(analysis.loan_payoff, 0.0);
+        assert!(analysis.years.iter().all(|year| year.debt_service_coverage.is_none()));
+        
+        // Flat rents and value: both IRRs equal (NOI - capex) / price
+        let cash_yield = 23200.0 / 400000.0;
+        assert!((analysis.unlevered_irr.unwrap() - cash_yield).abs() < 1e-9);
+        assert!((analysis.levered_irr.unwrap() - cash_yield).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_exit_repays_the_mortgage_balance() {
+        let analysis = analyze_property(&PropertyInputs { appreciation_rate: 0.03, selling_cost_rate: 0.06, ..inputs() }).unwrap();
+        
+        let sale_price = 400000.0 * 1.03_f64.powi(5);
+        assert!((analysis.sale_price - sale_price).abs() < 1e-6);
+        assert!(analysis.loan_payoff > 0.0 && analysis.loan_payoff < 300000.0);
+        assert!((analysis.exit_proceeds - (sale_price * 0.94 - analysis.loan_payoff)).abs() < 1e-6);
+        
+        let returned: f64 = analysis.years.iter().map(|year| year.cash_flow).sum::<f64>() + analysis.exit_proceeds;
+        assert!((analysis.equity_multiple - returned / 100000.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_rejects_invalid_inputs() {
+        assert!(analyze_property(&PropertyInputs { vacancy_rate: 1.5, ..inputs() }).is_err());
+        assert!(analyze_property(&PropertyInputs { hold_years: 0, ..inputs() }).is_err());
+        assert!(analyze_property(&PropertyInputs { purchase_price: 0.0, ..inputs() }).is_err());
+        
+        // Zero expenses are allowed
+        assert!(analyze_property(&PropertyInputs { operating_expenses: 0.0, capex_reserve: 0.0, ..inputs() }).is_ok());
+    }
+}
@@ -0,0 +1,300 @@
+//! Real options: valuing the flexibility to expand, contract, abandon or defer a project
+
+use crate::{FinanceError, FinanceResult, validate_finite, validate_fraction, validate_non_negative, validate_positive};
+use crate::calculations::calculate_npv;
+
+/// A choice management can make about a project before the option expires
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RealOption {
+    /// Scale the project up by `factor` (0.3 = 30% larger) for an extra `cost`
+    Expand { factor: f64, cost: f64 },
+    /// Scale the project down by `factor` (0.25 = 25% smaller) and recover `savings`
+    Contract { factor: f64, savings: f64 },
+    /// Walk away from the project and receive `salvage`
+    Abandon { salvage: f64 },
+    /// Wait to invest the initial investment until the project proves itself
+    Defer,
+}
+
+impl RealOption {
+    /// Human-readable name of the option
+    pub fn name(&self) -> &'static str {
+        match self {
+            RealOption::Expand { .. } => "Expand",
+            RealOption::Contract { .. } => "Contract",
+            RealOption::Abandon { .. } => "Abandon",
+            RealOption::Defer => "Defer",
+        }
+    }
+
+    /// What exercising the option does at a node
+    pub fn action(&self) -> &'static str {
+        match self {
+            RealOption::Defer => "Invest",
+            other => other.name(),
+        }
+    }
+
+    fn validate(&self) -> FinanceResult<()> {
+        match *self {
+            RealOption::Expand { factor, cost } => {
+                validate_non_negative(factor, "Expansion factor")?;
+                validate_non_negative(cost, "Expansion cost")
+            }
+            RealOption::Contract { factor, savings } => {
+                validate_non_negative(savings, "Contraction savings")?;
+                validate_fraction(factor, "Contraction factor")
+            }
+            RealOption::Abandon { salvage } => validate_non_negative(salvage, "Salvage value"),
+            RealOption::Defer => Ok(()),
+        }
+    }
+
+    /// Value of exercising at a node with the given project value
+    fn exercise_value(&self, project_value: f64, initial_investment: f64) -> f64 {
+        match *self {
+            RealOption::Expand { factor, cost } => project_value * (1.0 + factor) - cost,
+            RealOption::Contract { factor, savings } => project_value * (1.0 - factor) + savings,
+            RealOption::Abandon { salvage } => salvage,
+            RealOption::Defer => project_value - initial_investment,
+        }
+    }
+
+    /// Value of never exercising, at the option's expiry
+    fn expiry_hold_value(&self, project_value: f64) -> f64 {
+        match self {
+            RealOption::Defer => 0.0,
+            _ => project_value,
+        }
+    }
+}
+
+/// Settings for the binomial lattice of project value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatticeParameters {
+    /// Annual volatility of project value (as a decimal)
+    pub volatility: f64,
+    /// Annual risk-free rate (as a decimal)
+    pub risk_free_rate: f64,
+    /// Years until the option expires
+    pub years: f64,
+    /// Number of binomial steps to expiry
+    pub steps: usize,
+}
+
+/// One node of the lattice
+#[derive(Debug, Clone, Copy)]
+pub struct LatticeNode {
+    pub step: usize,
+    pub down_moves: usize,
+    /// Present value of the project's cash flows at this node
+    pub project_value: f64,
+    /// Value of the project including the option (for defer, the value of the opportunity)
+    pub value: f64,
+    /// Whether exercising is optimal at this node
+    pub exercise: bool,
+}
+
+/// Result of valuing a real option
+#[derive(Debug, Clone)]
+pub struct RealOptionValuation {
+    pub option: RealOption,
+    /// Present value of the project's cash flows today
+    pub project_value: f64,
+    /// NPV without any flexibility
+    pub static_npv: f64,
+    pub up: f64,
+    pub down: f64,
+    /// Risk-neutral probability of an up move
+    pub probability: f64,
+    /// Nodes by step, each ordered from most up moves to most down moves
+    pub lattice: Vec<Vec<LatticeNode>>,
+    pub option_value: f64,
+    /// Static NPV plus option value
+    pub expanded_npv: f64,
+}
+
+impl RealOptionValuation {
+    /// Nodes where exercising is optimal, by step
+    pub fn exercise_nodes(&self) -> impl Iterator<Item = &LatticeNode> {
+        self.lattice.iter().flatten().filter(|node| node.exercise)
+    }
+}
+
+/// Values the flexibility to expand, contract, abandon or defer a project with a binomial lattice
+/// 
+/// Project value today is the present value of the cash flows at the discount
+/// rate. It moves up by u = e^(σ√Δt) or down by 1/u each step, with
+/// risk-neutral probabilities from the risk-free rate. Working back from
+/// expiry, each node takes the better of holding and exercising, and the
+/// option value is what that flexibility adds to static NPV.
+/// 
+/// # Arguments
+/// * `initial_investment` - The initial cost of the project
+/// * `cash_flows` - Vector of future cash flows
+/// * `discount_rate` - The discount rate for the cash flows (as a decimal)
+/// * `parameters` - Volatility, risk-free rate, expiry and step count of the lattice
+/// * `option` - The flexibility being valued
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{value_real_option, LatticeParameters, RealOption};
+/// 
+/// let parameters = LatticeParameters { volatility: 0.30, risk_free_rate: 0.05, years: 3.0, steps: 3 };
+/// let valuation = value_real_option(1000.0, &[400.0, 400.0, 400.0], 0.10, &parameters, &RealOption::Abandon { salvage: 800.0 }).unwrap();
+/// 
+/// assert!(valuation.static_npv < 0.0);
+/// assert!(valuation.option_value > 0.0);
+/// assert!((valuation.expanded_npv - (valuation.static_npv + valuation.option_value)).abs() < 1e-9);
+/// ```
+pub fn value_real_option(
+    initial_investment: f64,
+    cash_flows: &[f64],
+    discount_rate: f64,
+    parameters: &LatticeParameters,
+    option: &RealOption
+) -> FinanceResult<RealOptionValuation> {
+    let static_npv = calculate_npv(initial_investment, cash_flows, discount_rate)?;
+    let project_value = static_npv + initial_investment;
+    validate_positive(project_value, "Present value of cash flows")?;
+    validate_positive(parameters.volatility, "Volatility")?;
+    validate_finite(parameters.risk_free_rate, "Risk-free rate")?;
+    validate_positive(parameters.years, "Years to expiry")?;
+    option.validate()?;
+    
+    if parameters.steps == 0 {
+        return Err(FinanceError::InvalidInput("The lattice needs at least one step".into()));
+    }
+    if parameters.risk_free_rate <= -1.0 {
+        return Err(FinanceError::InvalidInput(format!(
+            "Risk-free rate must be greater than -100%: {}", parameters.risk_free_rate
+        )));
+    }
+    
+    let step_years = parameters.years / parameters.steps as f64;
+    let up = (parameters.volatility * step_years.sqrt()).exp();
+    let down = 1.0 / up;
+    let growth = (1.0 + parameters.risk_free_rate).powf(step_years);
+    let probability = (growth - down) / (up - down);
+    
+    if !(0.0..=1.0).contains(&probability) {
+        return Err(FinanceError::InvalidInput(format!(
+            "Volatility of {} is too low for the risk-free rate over steps of {} years", parameters.volatility, step_years
+        )));
+    }
+    
+    let node_value = |step: usize, down_moves: usize| {
+        project_value * up.powi((step - down_moves) as i32) * down.powi(down_moves as i32)
+    };
+    let exercise_or_hold = |step: usize, down_moves: usize, hold: f64| {
+        let value = node_value(step, down_moves);
+        let exercise = option.exercise_value(value, initial_investment);
+        LatticeNode {
+            step,
+            down_moves,
+            project_value: value,
+            value: exercise.max(hold),
+            exercise: exercise > hold,
+        }
+    };
+    
+    let expiry: Vec<LatticeNode> = (0..=parameters.steps)
+        .map(|down_moves| {
+            let hold = option.expiry_hold_value(node_value(parameters.steps, down_moves));
+            exercise_or_hold(parameters.steps, down_moves, hold)
+        })
+        .collect();
+    
+    let mut lattice = vec![expiry];
+    for step in (0..parameters.steps).rev() {
+        let later = lattice.last().expect("lattice has an expiry step");
+        let nodes = (0..=step)
+            .map(|down_moves| {
+                let hold = (probability * later[down_moves].value + (1.0 - probability) * later[down_moves + 1].value) / growth;
+                exercise_or_hold(step, down_moves, hold)
+            })
+            .collect();
+        lattice.push(nodes);
+    }
+    lattice.reverse();
+    
+    let root = lattice[0][0].value;
+    let expanded_npv = match option {
+        RealOption::Defer => root,
+        _ => root - initial_investment,
+    };
+    
+    Ok(RealOptionValuation {
+        option: *option,
+        project_value,
+        static_npv,
+        up,
+        down,
+        probability,
+        lattice,
+        option_value: expanded_npv - static_npv,
+        expanded_npv,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters(volatility: f64, steps: usize) -> LatticeParameters {
+        LatticeParameters { volatility, risk_free_rate: 0.05, years: 1.0, steps }
+    }
+
+    #[test]
+    fn test_one_step_abandonment() {
+        // u = 1.25 and d = 0.8 on a project worth 100; salvage of 90 is taken only after a down move
+        let valuation = value_real_option(90.0, &[110.0], 0.10, &parameters(1.25_f64.ln(), 1), &RealOption::Abandon { salvage: 90.0 }).unwrap();
+        
+        let probability = (1.05 - 0.8) / (1.25 - 0.8);
+        assert!((valuation.probability - probability).abs() < 1e-12);
+        
+        let root = (probability * 125.0 + (1.0 - probability) * 90.0) / 1.05;
+        assert!((valuation.option_value - (root - 100.0)).abs() < 1e-9);
+        
+        let exercised: Vec<(usize, usize)> = valuation.exercise_nodes().map(|node| (node.step, node.down_moves)).collect();
+        assert_eq!(exercised, vec![(1, 1)]);
+    }
+
+    #[test]
+    fn test_worthless_options_leave_static_npv() {
+        let cash_flows = [400.0, 400.0, 400.0];
+        let options = [
+            RealOption::Abandon { salvage: 0.0 },
+            RealOption::Expand { factor: 0.0, cost: 10.0 },
+            RealOption::Contract { factor: 0.5, savings: 0.0 },
+        ];
+        
+        for option in options {
+            let valuation = value_real_option(900.0, &cash_flows, 0.10, &parameters(0.3, 4), &option).unwrap();
+            assert!(valuation.option_value.abs() < 1e-9, "{:?}", option);
+            assert_eq!(valuation.exercise_nodes().count(), 0);
+        }
+    }
+
+    #[test]
+    fn test_option_value_rises_with_volatility() {
+        let expand = RealOption::Expand { factor: 0.3, cost: 300.0 };
+        let calm = value_real_option(900.0, &[400.0; 3], 0.10, &parameters(0.15, 20), &expand).unwrap();
+        let volatile = value_real_option(900.0, &[400.0; 3], 0.10, &parameters(0.45, 20), &expand).unwrap();
+        
+        assert!(volatile.option_value > calm.option_value);
+        assert!(calm.option_value >= 0.0);
+    }
+
+    #[test]
+    fn test_defer_is_worth_at_least_investing_now() {
+        let valuation = value_real_option(1000.0, &[400.0; 3], 0.10, &parameters(0.35, 12), &RealOption::Defer).unwrap();
+        
+        assert!(valuation.static_npv < 0.0);
+        assert!(valuation.expanded_npv >= valuation.static_npv.max(0.0));
+        assert!(!valuation.lattice[0][0].exercise);
+        
+        assert!(value_real_option(1000.0, &[400.0; 3], 0.10, &parameters(0.01, 1), &RealOption::Defer).is_err());
+        assert!(value_real_option(1000.0, &[400.0; 3], 0.10, &parameters(0.3, 0), &RealOption::Defer).is_err());
+    }
+}
@@ -0,0 +1,207 @@
+//! Refinance break-even analysis
+
+use crate::{FinanceError, FinanceResult, validate_positive, validate_non_negative};
+use crate::calculations::{calculate_dcf, calculate_loan_payment, generate_amortization_schedule};
+use chrono::NaiveDate;
+use std::str::FromStr;
+
+/// A refinancing offer for the current loan balance
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefinanceOffer {
+    /// The annual interest rate as a percentage
+    pub annual_interest_rate: f64,
+    /// The new loan term in years
+    pub term_years: i32,
+    /// Discount points as a percentage of the new loan amount (1 point = 1%)
+    pub points: f64,
+    /// Closing costs paid in cash
+    pub closing_costs: f64,
+}
+
+impl FromStr for RefinanceOffer {
+    type Err = FinanceError;
+
+    /// Parses an offer written as `rate:term[:points[:closing_costs]]`, e.g. `5.75:30:1:3500`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').map(str::trim).collect();
+        
+        if parts.len() < 2 || parts.len() > 4 {
+            return Err(FinanceError::InvalidInput(format!(
+                "Offer must be rate:term[:points[:closing_costs]]: {}", s
+            )));
+        }
+        
+        let parse = |value: &str, name: &str| {
+            value.parse::<f64>()
+                .map_err(|_| FinanceError::InvalidInput(format!("Invalid {} in offer: {}", name, value)))
+        };
+        
+        let term_years = parts[1].parse::<i32>()
+            .map_err(|_| FinanceError::InvalidInput(format!("Invalid term in offer: {}", parts[1])))?;
+        
+        Ok(RefinanceOffer {
+            annual_interest_rate: parse(parts[0], "rate")?,
+            term_years,
+            points: parts.get(2).map_or(Ok(0.0), |p| parse(p, "points"))?,
+            closing_costs: parts.get(3).map_or(Ok(0.0), |c| parse(c, "closing costs"))?,
+        })
+    }
+}
+
+/// Result of comparing a refinancing offer with the current loan
+#[derive(Debug, Clone)]
+pub struct RefinanceAnalysis {
+    pub current_payment: f64,
+    pub new_payment: f64,
+    pub monthly_savings: f64,
+    pub upfront_costs: f64,
+    /// First month in which cumulative savings cover the upfront costs
+    pub break_even_month: Option<u32>,
+    /// Remaining interest on the current loan minus total interest on the new loan
+    pub interest_savings: f64,
+    /// Net present value of switching, after upfront costs
+    pub npv: f64,
+}
+
+/// Analyzes whether refinancing the current loan balance with an offer pays off
+/// 
+/// Upfront costs (points plus closing costs) are assumed to be paid in cash.
+/// Monthly cash flow differences are compared over the longer of the two
+/// terms, so a longer new term counts its extra payments against it.
+/// 
+/// # Arguments
+/// * `current_balance` - The outstanding balance of the current loan
+/// * `current_rate` - The current annual interest rate as a percentage
+/// * `remaining_term_years` - Years remaining on the current loan
+/// * `offer` - The refinancing offer
+/// * `discount_rate` - Annual discount rate for the NPV (as a decimal)
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{analyze_refinance, RefinanceOffer};
+/// 
+/// let offer = RefinanceOffer { annual_interest_rate: 5.0, term_years: 25, points: 1.0, closing_costs: 3000.0 };
+/// let analysis = analyze_refinance(300000.0, 7.0, 25, &offer, 0.04).unwrap();
+/// assert!(analysis.monthly_savings > 0.0);
+/// assert!(analysis.break_even_month.unwrap() < 24);
+/// ```
+pub fn analyze_refinance(
+    current_balance: f64,
+    current_rate: f64,
+    remaining_term_years: i32,
+    offer: &RefinanceOffer,
+    discount_rate: f64
+) -> FinanceResult<RefinanceAnalysis> {
+    validate_positive(current_balance, "Current balance")?;
+    validate_non_negative(offer.points, "Points")?;
+    validate_non_negative(offer.closing_costs, "Closing costs")?;
+    validate_non_negative(discount_rate, "Discount rate")?;
+    
+    // Payment dates play no part in the comparison
+    let start = NaiveDate::default();
+    let current_schedule = generate_amortization_schedule(current_balance, current_rate, remaining_term_years, start)?;
+    let new_schedule = generate_amortization_schedule(current_balance, offer.annual_interest_rate, offer.term_years, start)?;
+    
+    let current_payment = calculate_loan_payment(current_balance, current_rate, remaining_term_years as f64)?;
+    let new_payment = calculate_loan_payment(current_balance, offer.annual_interest_rate, offer.term_years as f64)?;
+    let upfront_costs = current_balance * offer.points / 100.0 + offer.closing_costs;
+    
+    let current_interest = current_schedule.last().map_or(0.0, |p| p.cumulative_interest);
+    let new_interest = new_schedule.last().map_or(0.0, |p| p.cumulative_interest);
+    
+    // Monthly payment saved by switching (negative once the new loan runs longer)
+    let months = current_schedule.len().max(new_schedule.len());
+    let savings: Vec<f64> = (0..months)
+        .map(|m| {
+            let old = if m < current_schedule.len() { current_payment } else { 0.0 };
+            let new = if m < new_schedule.len() { new_payment } else { 0.0 };
+            old - new
+        })
+        .collect();
+    
+    let mut cumulative = 0.0;
+    let mut break_even_month = None;
+    for (month, saving) in savings.iter().enumerate() {
+        cumulative += saving;
+        if cumulative >= upfront_costs && *saving > 0.0 {
+            break_even_month = Some(month as u32 + 1);
+            break;
+        }
+    }
+    
+    let npv = calculate_dcf(&savings, discount_rate / 12.0)? - upfront_costs;
+    
+    Ok(RefinanceAnalysis {
+        current_payment,
+        new_payment,
+        monthly_savings: current_payment - new_payment,
+        upfront_costs,
+        break_even_month,
+        interest_savings: current_interest - new_interest,
+        npv,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_offer() {
+        let offer: RefinanceOffer = "5.75:30:1:3500".parse().unwrap();
+        assert_eq!(offer, RefinanceOffer { annual_interest_rate: 5.75, term_years: 30, points: 1.0, closing_costs: 3500.0 });
+        
+        let offer: RefinanceOffer = "6:15".parse().unwrap();
+        assert_eq!(offer.points, 0.0);
+        assert_eq!(offer.closing_costs, 0.0);
+        
+        assert!("6".parse::<RefinanceOffer>().is_err());
+        assert!("6:abc".parse::<RefinanceOffer>().is_err());
+    }
+
+    #[test]
+    fn test_refinance_break_even() {
+        let offer = RefinanceOffer { annual_interest_rate: 5.0, term_years: 25, points: 1.0, closing_costs: 3000.0 };
+        let analysis = analyze_refinance(300000.0, 7.0, 25, &offer, 0.0).unwrap();
+        
+        assert!((analysis.upfront_costs - 6000.0).abs() < 1e-9);
+        let expected_month = (analysis.upfront_costs / analysis.monthly_savings).ceil() as u32;
+        assert_eq!(analysis.break_even_month, Some(expected_month));
+        assert!(analysis.interest_savings > 0.0);
+        
+        // With no discounting the NPV is just total savings less costs
+        let total_savings = analysis.monthly_savings * 300.0;
+        assert!((analysis.npv - (total_savings - 6000.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_refinance_higher_rate_never_breaks_even() {
+        let offer = RefinanceOffer { annual_interest_rate: 8.0, term_years: 25, points: 0.0, closing_costs: 2000.0 };
+        let analysis = analyze_refinance(300000.0, 7.0, 25, &offer, 0.05).unwrap();
+        
+        assert!(analysis.monthly_savings < 0.0);
+        assert_eq!(analysis.break_even_month, None);
+        assert!(analysis.npv < 0.0);
+        assert!(analysis.interest_savings < 0.0);
+    }
+
+    #[test]
+    fn test_refinance_longer_term_counts_extra_payments() {
+        // Extending from 10 to 30 years lowers the payment but adds 20 years of payments
+        let offer = RefinanceOffer { annual_interest_rate: 6.0, term_years: 30, points: 0.0, closing_costs: 0.0 };
+        let analysis = analyze_refinance(200000.0, 6.0, 10, &offer, 0.0).unwrap();
+        
+        assert!(analysis.monthly_savings > 0.0);
+        assert!(analysis.interest_savings < 0.0);
+        assert!(analysis.npv < 0.0);
+    }
+
+    #[test]
+    fn test_refinance_invalid_inputs() {
+        let offer = RefinanceOffer { annual_interest_rate: 5.0, term_years: 25, points: -1.0, closing_costs: 0.0 };
+        assert!(analyze_refinance(300000.0, 7.0, 25, &offer, 0.04).is_err());
+        
+        let offer = RefinanceOffer { annual_interest_rate: 5.0, term_years: 0, points: 0.0, closing_costs: 0.0 };
+        assert!(analyze_refinance(300000.0, 7.0, 25, &offer, 0.04).is_err());
+    }
+}
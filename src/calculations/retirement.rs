@@ -0,0 +1,354 @@
+//! Retirement planning: savings targets and decumulation schedules
+
+use crate::{FinanceError, FinanceResult, validate_non_negative, validate_fraction};
+use crate::calculations::calculate_future_value;
+
+/// Assumptions for a retirement plan
+/// 
+/// Rates are annual decimals. Contributions are made monthly but credited at
+/// the end of each year, and grow by `contribution_raise` every year.
+#[derive(Debug, Clone)]
+pub struct RetirementInputs {
+    pub current_age: u32,
+    pub retirement_age: u32,
+    pub life_expectancy: u32,
+    pub current_savings: f64,
+    pub monthly_contribution: f64,
+    /// Annual increase in contributions (as a decimal)
+    pub contribution_raise: f64,
+    /// Employer match as a fraction of the employee contribution (0.5 = 50 cents per dollar)
+    pub employer_match: f64,
+    pub pre_retirement_return: f64,
+    pub post_retirement_return: f64,
+    pub inflation: f64,
+    /// Desired annual retirement income in today's money
+    pub target_real_income: f64,
+}
+
+/// Savings target and gap for a retirement plan
+#[derive(Debug, Clone)]
+pub struct RetirementPlan {
+    pub years_to_retirement: u32,
+    pub years_in_retirement: u32,
+    /// Projected nominal savings at retirement
+    pub projected_savings: f64,
+    /// Nominal savings needed at retirement to fund the target income
+    pub required_savings: f64,
+    /// Required minus projected savings (negative when on track)
+    pub savings_gap: f64,
+    /// First-year monthly contribution, growing with raises, that meets the target
+    pub required_monthly_contribution: f64,
+    /// The target income in nominal terms in the first year of retirement
+    pub first_year_income: f64,
+}
+
+/// Rule used to set each year's withdrawal in retirement
+#[derive(Debug, Clone, PartialEq)]
+pub enum WithdrawalRule {
+    /// Withdraw a fixed fraction of the balance each year
+    FixedPercentage(f64),
+    /// Withdraw a fixed amount in today's money, adjusted for inflation
+    FixedReal(f64),
+    /// Start at `initial_rate` of the balance and adjust for inflation; cut the
+    /// withdrawal by `adjustment` when the withdrawal rate rises more than
+    /// `band` above the initial rate, and raise it when it falls more than `band` below
+    Guardrails { initial_rate: f64, band: f64, adjustment: f64 },
+}
+
+/// One year of a decumulation schedule
+#[derive(Debug, Clone)]
+pub struct DecumulationYear {
+    pub age: u32,
+    pub starting_balance: f64,
+    /// Nominal withdrawal taken at the start of the year
+    pub withdrawal: f64,
+    /// The withdrawal in today's money
+    pub real_withdrawal: f64,
+    pub investment_growth: f64,
+    pub ending_balance: f64,
+}
+
+/// Projects retirement savings and the contribution needed to fund a target income
+/// 
+/// The required nest egg is the present value at retirement of the target
+/// income, inflated to retirement and growing with inflation each year, with
+/// withdrawals at the start of each year discounted at the post-retirement return.
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{plan_retirement, RetirementInputs};
+/// 
+/// let inputs = RetirementInputs {
+///     current_age: 35, retirement_age: 65, life_expectancy: 90,
+///     current_savings: 50000.0, monthly_contribution: 800.0, contribution_raise: 0.02,
+///     employer_match: 0.5, pre_retirement_return: 0.07, post_retirement_return: 0.05,
+///     inflation: 0.025, target_real_income: 60000.0,
+/// };
+/// let plan = plan_retirement(&inputs).unwrap();
+/// assert!(plan.projected_savings > 1_000_000.0);
+/// ```
+pub fn plan_retirement(inputs: &RetirementInputs) -> FinanceResult<RetirementPlan> {
+    validate_retirement_inputs(inputs)?;
+    
+    let years_to_retirement = inputs.retirement_age - inputs.current_age;
+    let years_in_retirement = inputs.life_expectancy - inputs.retirement_age;
+    
+    let savings_growth = if inputs.current_savings > 0.0 {
+        calculate_future_value(inputs.current_savings, inputs.pre_retirement_return, years_to_retirement as f64)?
+    } else {
+        0.0
+    };
+    
+    // Future value of contributing one dollar a month in the first year
+    let contribution_factor = contribution_growth_factor(inputs, years_to_retirement);
+    let projected_savings = savings_growth + inputs.monthly_contribution * contribution_factor;
+    
+    let first_year_income = inputs.target_real_income * (1.0 + inputs.inflation).powi(years_to_retirement as i32);
+    let growth_ratio = (1.0 + inputs.inflation) / (1.0 + inputs.post_retirement_return);
+    let required_savings: f64 = (0..years_in_retirement)
+        .map(|year| first_year_income * growth_ratio.powi(year as i32))
+        .sum();
+    
+    let savings_gap = required_savings - projected_savings;
+    
+    let required_monthly_contribution = if contribution_factor > 0.0 {
+        ((required_savings - savings_growth) / contribution_factor).max(0.0)
+    } else if savings_growth >= required_savings {
+        0.0
+    } else {
+        return Err(FinanceError::InvalidInput("Retirement age must be after the current age to close a savings gap".into()));
+    };
+    
+    Ok(RetirementPlan {
+        years_to_retirement,
+        years_in_retirement,
+        projected_savings,
+        required_savings,
+        savings_gap,
+        required_monthly_contribution,
+        first_year_income,
+    })
+}
+
+/// Projects year-by-year withdrawals from retirement until life expectancy
+/// 
+/// Withdrawals are taken at the start of each year and the remainder grows at
+/// the post-retirement return. Withdrawals are capped at the remaining balance.
+/// 
+/// # Arguments
+/// * `inputs` - The retirement assumptions
+/// * `starting_balance` - Nominal savings at retirement
+/// * `rule` - The withdrawal rule
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{project_decumulation, RetirementInputs, WithdrawalRule};
+/// 
+/// let inputs = RetirementInputs {
+///     current_age: 65, retirement_age: 65, life_expectancy: 95,
+///     current_savings: 1000000.0, monthly_contribution: 0.0, contribution_raise: 0.0,
+///     employer_match: 0.0, pre_retirement_return: 0.05, post_retirement_return: 0.05,
+///     inflation: 0.02, target_real_income: 40000.0,
+/// };
+/// let schedule = project_decumulation(&inputs, 1000000.0, &WithdrawalRule::FixedPercentage(0.04)).unwrap();
+/// assert_eq!(schedule.len(), 30);
+/// assert_eq!(schedule[0].withdrawal, 40000.0);
+/// ```
+pub fn project_decumulation(
+    inputs: &RetirementInputs,
+    starting_balance: f64,
+    rule: &WithdrawalRule
+) -> FinanceResult<Vec<DecumulationYear>> {
+    validate_retirement_inputs(inputs)?;
+    validate_non_negative(starting_balance, "Starting balance")?;
+    
+    match rule {
+        WithdrawalRule::FixedPercentage(rate) => validate_fraction(*rate, "Withdrawal rate")?,
+        WithdrawalRule::FixedReal(amount) => validate_non_negative(*amount, "Withdrawal amount")?,
+        WithdrawalRule::Guardrails { initial_rate, band, adjustment } => {
+            validate_fraction(*initial_rate, "Initial withdrawal rate")?;
+            validate_fraction(*band, "Guardrail band")?;
+            validate_fraction(*adjustment, "Guardrail adjustment")?;
+        }
+    }
+    
+    let mut schedule = Vec::with_capacity((inputs.life_expectancy - inputs.retirement_age) as usize);
+    let mut balance = starting_balance;
+    let mut previous_withdrawal = 0.0;
+    
+    for age in inputs.retirement_age..inputs.life_expectancy {
+        let years_from_today = (age - inputs.current_age) as i32;
+        let price_level = (1.0 + inputs.inflation).powi(years_from_today);
+        
+        let planned = match rule {
+            WithdrawalRule::FixedPercentage(rate) => balance * rate,
+            WithdrawalRule::FixedReal(amount) => amount * price_level,
+            WithdrawalRule::Guardrails { initial_rate, band, adjustment } => {
+                if age == inputs.retirement_age {
+                    balance * initial_rate
+                } else {
+                    let inflated = previous_withdrawal * (1.0 + inputs.inflation);
+                    let current_rate = if balance > 0.0 { inflated / balance } else { f64::INFINITY };
+                    
+                    if current_rate > initial_rate * (1.0 + band) {
+                        inflated * (1.0 - adjustment)
+                    } else if current_rate < initial_rate * (1.0 - band) {
+                        inflated * (1.0 + adjustment)
+                    } else {
+                        inflated
+                    }
+                }
+            }
+        };
+        
+        let withdrawal = planned.min(balance);
+        previous_withdrawal = planned;
+        
+        let invested = balance - withdrawal;
+        let investment_growth = invested * inputs.post_retirement_return;
+        
+        schedule.push(DecumulationYear {
+            age,
+            starting_balance: balance,
+            withdrawal,
+            real_withdrawal: withdrawal / price_level,
+            investment_growth,
+            ending_balance: invested + investment_growth,
+        });
+        
+        balance = invested + investment_growth;
+    }
+    
+    Ok(schedule)
+}
+
+/// Future value at retirement of contributing one dollar a month in the first year
+fn contribution_growth_factor(inputs: &RetirementInputs, years_to_retirement: u32) -> f64 {
+    (0..years_to_retirement)
+        .map(|year| {
+            let annual = 12.0 * (1.0 + inputs.contribution_raise).powi(year as i32) * (1.0 + inputs.employer_match);
+            annual * (1.0 + inputs.pre_retirement_return).powi((years_to_retirement - 1 - year) as i32)
+        })
+        .sum()
+}
+
+fn validate_retirement_inputs(inputs: &RetirementInputs) -> FinanceResult<()> {
+    if inputs.retirement_age < inputs.current_age {
+        return Err(FinanceError::InvalidInput("Retirement age cannot be before the current age".into()));
+    }
+    
+    if inputs.life_expectancy <= inputs.retirement_age {
+        return Err(FinanceError::InvalidInput("Life expectancy must be after the retirement age".into()));
+    }
+    
+    validate_non_negative(inputs.current_savings, "Current savings")?;
+    validate_non_negative(inputs.monthly_contribution, "Monthly contribution")?;
+    validate_non_negative(inputs.contribution_raise, "Contribution raise")?;
+    validate_non_negative(inputs.employer_match, "Employer match")?;
+    validate_non_negative(inputs.pre_retirement_return, "Pre-retirement return")?;
+    validate_non_negative(inputs.post_retirement_return, "Post-retirement return")?;
+    validate_non_negative(inputs.inflation, "Inflation")?;
+    validate_non_negative(inputs.target_real_income, "Target income")?;
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_inputs() -> RetirementInputs {
+        RetirementInputs {
+            current_age: 35,
+            retirement_age: 65,
+            life_expectancy: 90,
+            current_savings: 50000.0,
+            monthly_contribution: 800.0,
+            contribution_raise: 0.02,
+            employer_match: 0.5,
+            pre_retirement_return: 0.07,
+            post_retirement_return: 0.05,
+            inflation: 0.025,
+            target_real_income: 60000.0,
+        }
+    }
+
+    #[test]
+    fn test_plan_without_growth() {
+        // With no returns, raises or inflation everything is simple arithmetic
+        let inputs = RetirementInputs {
+            current_age: 60,
+            retirement_age: 62,
+            life_expectancy: 72,
+            current_savings: 10000.0,
+            monthly_contribution: 1000.0,
+            contribution_raise: 0.0,
+            employer_match: 0.5,
+            pre_retirement_return: 0.0,
+            post_retirement_return: 0.0,
+            inflation: 0.0,
+            target_real_income: 5000.0,
+        };
+        let plan = plan_retirement(&inputs).unwrap();
+        
+        assert!((plan.projected_savings - 46000.0).abs() < 1e-9);
+        assert!((plan.required_savings - 50000.0).abs() < 1e-9);
+        assert!((plan.savings_gap - 4000.0).abs() < 1e-9);
+        // (50000 - 10000) / (2 years * 12 months * 1.5)
+        assert!((plan.required_monthly_contribution - 40000.0 / 36.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_required_contribution_closes_gap() {
+        let inputs = sample_inputs();
+        let plan = plan_retirement(&inputs).unwrap();
+        
+        let adjusted = RetirementInputs { monthly_contribution: plan.required_monthly_contribution, ..inputs };
+        let adjusted_plan = plan_retirement(&adjusted).unwrap();
+        assert!(adjusted_plan.savings_gap.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_plan_invalid_ages() {
+        assert!(plan_retirement(&RetirementInputs { retirement_age: 30, ..sample_inputs() }).is_err());
+        assert!(plan_retirement(&RetirementInputs { life_expectancy: 65, ..sample_inputs() }).is_err());
+        assert!(plan_retirement(&RetirementInputs { inflation: -0.01, ..sample_inputs() }).is_err());
+    }
+
+    #[test]
+    fn test_fixed_real_withdrawal_exhausts_required_savings() {
+        let inputs = sample_inputs();
+        let plan = plan_retirement(&inputs).unwrap();
+        let schedule = project_decumulation(&inputs, plan.required_savings, &WithdrawalRule::FixedReal(60000.0)).unwrap();
+        
+        assert_eq!(schedule.len(), 25);
+        assert!((schedule[0].withdrawal - plan.first_year_income).abs() < 1e-6);
+        assert!(schedule.iter().all(|y| (y.real_withdrawal - 60000.0).abs() < 1e-6));
+        assert!(schedule[24].ending_balance.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_fixed_percentage_never_depletes() {
+        let inputs = sample_inputs();
+        let schedule = project_decumulation(&inputs, 1000000.0, &WithdrawalRule::FixedPercentage(0.04)).unwrap();
+        
+        assert_eq!(schedule[0].withdrawal, 40000.0);
+        assert!(schedule.iter().all(|y| y.ending_balance > 0.0));
+        assert!((schedule[1].withdrawal - schedule[1].starting_balance * 0.04).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_guardrails_cut_spending_after_losses() {
+        let inputs = RetirementInputs { post_retirement_return: 0.0, inflation: 0.0, ..sample_inputs() };
+        let rule = WithdrawalRule::Guardrails { initial_rate: 0.05, band: 0.2, adjustment: 0.1 };
+        let schedule = project_decumulation(&inputs, 1000000.0, &rule).unwrap();
+        
+        // Without growth the withdrawal rate drifts up until the upper guardrail triggers a cut
+        assert_eq!(schedule[0].withdrawal, 50000.0);
+        let cut = schedule.iter().position(|y| y.withdrawal < 50000.0 - 1e-9).unwrap();
+        assert!((schedule[cut].withdrawal - 45000.0).abs() < 1e-9);
+        assert!(50000.0 / schedule[cut].starting_balance > 0.06);
+        
+        assert!(project_decumulation(&inputs, 1000000.0, &WithdrawalRule::FixedPercentage(1.5)).is_err());
+    }
+}
@@ -0,0 +1,303 @@
+//! Scenario files: a base case and named overrides evaluated side by side
+
+use crate::{FinanceError, FinanceResult, validate_finite, validate_fraction};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Tolerance when checking that scenario probabilities sum to one
+const PROBABILITY_TOLERANCE: f64 = 1e-6;
+
+/// Name given to the base case in results
+pub const BASE_SCENARIO: &str = "base";
+
+/// The value of one input in a scenario file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum ScenarioValue {
+    Number(f64),
+    List(Vec<f64>),
+}
+
+/// Inputs and optional probability weight of one case
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ScenarioCase {
+    #[serde(default)]
+    pub probability: Option<f64>,
+    /// Inputs by name; any other key in the case
+    #[serde(flatten)]
+    pub inputs: BTreeMap<String, ScenarioValue>,
+}
+
+/// A base case plus named scenarios that override some of its inputs
+/// 
+/// Files are written in TOML or YAML:
+/// 
+/// ```toml
+/// calculation = "npv"
+/// 
+/// [base]
+/// probability = 0.5
+/// initial_investment = 3000
+/// cash_inflow = 1000
+/// discount_rate = 0.10
+/// lifespan = 5
+/// 
+/// [scenarios.bear]
+/// probability = 0.25
+/// discount_rate = 0.12
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ScenarioFile {
+    /// Calculation the inputs belong to, if the file names one
+    #[serde(default)]
+    pub calculation: Option<String>,
+    pub base: ScenarioCase,
+    #[serde(default)]
+    pub scenarios: BTreeMap<String, ScenarioCase>,
+}
+
+/// Result of evaluating one case
+#[derive(Debug, Clone)]
+pub struct ScenarioResult {
+    pub name: String,
+    pub probability: Option<f64>,
+    /// Inputs this case changes relative to the base, in name order
+    pub overrides: Vec<(String, ScenarioValue)>,
+    pub value: f64,
+}
+
+/// Results of every case in a scenario file
+#[derive(Debug, Clone)]
+pub struct ScenarioComparison {
+    /// The base case first, then the scenarios in name order
+    pub results: Vec<ScenarioResult>,
+    /// Probability-weighted result, when the file assigns probabilities
+    pub expected_value: Option<f64>,
+}
+
+impl ScenarioFile {
+    /// Parses and validates a scenario file written in TOML
+    /// 
+    /// # Examples
+    /// ```
+    /// use rusty_finance::calculations::ScenarioFile;
+    /// 
+    /// let file = ScenarioFile::from_toml_str(r#"
+    ///     [base]
+    ///     discount_rate = 0.10
+    /// 
+    ///     [scenarios.bear]
+    ///     discount_rate = 0.12
+    /// "#).unwrap();
+    /// assert_eq!(file.scenarios.len(), 1);
+    /// ```
+    pub fn from_toml_str(contents: &str) -> FinanceResult<Self> {
+        let file: ScenarioFile = toml::from_str(contents)
+            .map_err(|e| FinanceError::InvalidInput(format!("Invalid scenario file: {}", e)))?;
+        file.validate()?;
+        Ok(file)
+    }
+
+    /// Parses and validates a scenario file written in YAML
+    pub fn from_yaml_str(contents: &str) -> FinanceResult<Self> {
+        let file: ScenarioFile = serde_yaml::from_str(contents)
+            .map_err(|e| FinanceError::InvalidInput(format!("Invalid scenario file: {}", e)))?;
+        file.validate()?;
+        Ok(file)
+    }
+
+    /// Checks input values and that any probabilities given sum to one
+    pub fn validate(&self) -> FinanceResult<()> {
+        if self.scenarios.contains_key(BASE_SCENARIO) {
+            return Err(FinanceError::InvalidInput(format!("'{}' is reserved for the base case", BASE_SCENARIO)));
+        }
+        
+        for (name, case) in self.cases() {
+            for value in case.inputs.values() {
+                match value {
+                    ScenarioValue::Number(number) => validate_finite(*number, "Scenario input")?,
+                    ScenarioValue::List(numbers) => {
+                        for number in numbers {
+                            validate_finite(*number, "Scenario input")?;
+                        }
+                    }
+                }
+            }
+            
+            if let Some(probability) = case.probability {
+                validate_fraction(probability, &format!("Probability for '{}'", name))?;
+            }
+        }
+        
+        if self.is_weighted() {
+            let total: f64 = self.cases().map(|(_, case)| case.probability.unwrap_or(0.0)).sum();
+            if (total - 1.0).abs() > PROBABILITY_TOLERANCE {
+                return Err(FinanceError::InvalidInput(format!("Scenario probabilities must sum to 1: {}", total)));
+            }
+        }
+        
+        Ok(())
+    }
+
+    /// Whether any case carries a probability weight
+    pub fn is_weighted(&self) -> bool {
+        self.cases().any(|(_, case)| case.probability.is_some())
+    }
+
+    /// The base case followed by each named scenario
+    fn cases(&self) -> impl Iterator<Item = (&str, &ScenarioCase)> {
+        std::iter::once((BASE_SCENARIO, &self.base))
+            .chain(self.scenarios.iter().map(|(name, case)| (name.as_str(), case)))
+    }
+}
+
+/// Evaluates the base case and every scenario with the same calculation
+/// 
+/// Each scenario starts from the base inputs and replaces the ones it lists.
+/// When probabilities are given, cases without one are weighted at zero.
+/// 
+/// # Arguments
+/// * `file` - The scenario definitions
+/// * `calculate` - Computes the result from a complete set of named inputs
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{evaluate_scenarios, calculate_capm, ScenarioFile, ScenarioValue};
+/// 
+/// let file = ScenarioFile::from_toml_str(r#"
+///     [base]
+///     probability = 0.5
+///     beta = 1.0
+/// 
+///     [scenarios.defensive]
+///     probability = 0.5
+///     beta = 0.5
+/// "#).unwrap();
+/// let comparison = evaluate_scenarios(&file, |inputs| match inputs["beta"] {
+///     ScenarioValue::Number(beta) => calculate_capm(0.02, beta, 0.08),
+///     ScenarioValue::List(_) => unreachable!(),
+/// }).unwrap();
+/// assert!((comparison.expected_value.unwrap() - 0.065).abs() < 1e-12);
+/// ```
+pub fn evaluate_scenarios<F>(file: &ScenarioFile, calculate: F) -> FinanceResult<ScenarioComparison>
+where
+    F: Fn(&BTreeMap<String, ScenarioValue>) -> FinanceResult<f64>,
+{
+    let results = file
+        .cases()
+        .map(|(name, case)| {
+            let mut inputs = file.base.inputs.clone();
+            let mut overrides = Vec::new();
+            
+            if name != BASE_SCENARIO {
+                for (input, value) in &case.inputs {
+                    inputs.insert(input.clone(), value.clone());
+                    overrides.push((input.clone(), value.clone()));
+                }
+            }
+            
+            let value = calculate(&inputs).map_err(|e| match e {
+                FinanceError::InvalidInput(message) => FinanceError::InvalidInput(format!("Scenario '{}': {}", name, message)),
+                other => other,
+            })?;
+            
+            Ok(ScenarioResult {
+                name: name.to_string(),
+                probability: case.probability,
+                overrides,
+                value,
+            })
+        })
+        .collect::<FinanceResult<Vec<_>>>()?;
+    
+    let expected_value = file.is_weighted().then(|| {
+        results.iter().map(|result| result.probability.unwrap_or(0.0) * result.value).sum()
+    });
+    
+    Ok(ScenarioComparison { results, expected_value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculations::calculate_npv;
+
+    const NPV_SCENARIOS: &str = r#"
+        calculation = "npv"
+
+        [base]
+        probability = 0.5
+        initial_investment = 3000
+        cash_flows = [1000, 1000, 1000, 1000, 1000]
+        discount_rate = 0.10
+
+        [scenarios.bull]
+        probability = 0.25
+        cash_flows = [1200, 1200, 1200, 1200, 1200]
+
+        [scenarios.bear]
+        probability = 0.25
+        discount_rate = 0.12
+    "#;
+
+    fn npv_from(inputs: &BTreeMap<String, ScenarioValue>) -> FinanceResult<f64> {
+        match (&inputs["initial_investment"], &inputs["cash_flows"], &inputs["discount_rate"]) {
+            (ScenarioValue::Number(initial), ScenarioValue::List(flows), ScenarioValue::Number(rate)) => {
+                calculate_npv(*initial, flows, *rate)
+            }
+            _ => Err(FinanceError::InvalidInput("unexpected input types".into())),
+        }
+    }
+
+    #[test]
+    fn test_scenarios_override_base_inputs() {
+        let file = ScenarioFile::from_toml_str(NPV_SCENARIOS).unwrap();
+        assert_eq!(file.calculation.as_deref(), Some("npv"));
+        
+        let comparison = evaluate_scenarios(&file, npv_from).unwrap();
+        let names: Vec<&str> = comparison.results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["base", "bear", "bull"]);
+        
+        let bear = &comparison.results[1];
+        assert_eq!(bear.overrides, vec![("discount_rate".to_string(), ScenarioValue::Number(0.12))]);
+        assert_eq!(bear.value, calculate_npv(3000.0, &[1000.0; 5], 0.12).unwrap());
+        
+        let expected: f64 = comparison.results.iter().map(|r| r.probability.unwrap() * r.value).sum();
+        assert!((comparison.expected_value.unwrap() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_yaml_scenarios_without_probabilities() {
+        let yaml = "base:\n  initial_investment: 3000\n  cash_flows: [1000, 1000, 1000]\n  discount_rate: 0.1\n\
+                    scenarios:\n  bear:\n    discount_rate: 0.15\n";
+        let file = ScenarioFile::from_yaml_str(yaml).unwrap();
+        let comparison = evaluate_scenarios(&file, npv_from).unwrap();
+        
+        assert_eq!(comparison.results.len(), 2);
+        assert!(comparison.expected_value.is_none());
+        assert!(comparison.results[1].value < comparison.results[0].value);
+    }
+
+    #[test]
+    fn test_invalid_scenario_probabilities() {
+        let unbalanced = "[base]\nprobability = 0.5\nrate = 0.1\n[scenarios.bear]\nprobability = 0.3\nrate = 0.2\n";
+        assert!(ScenarioFile::from_toml_str(unbalanced).is_err());
+        
+        let out_of_range = "[base]\nprobability = 1.5\nrate = 0.1\n";
+        assert!(ScenarioFile::from_toml_str(out_of_range).is_err());
+        
+        assert!(ScenarioFile::from_toml_str("[scenarios.bear]\nrate = 0.2\n").is_err());
+        assert!(ScenarioFile::from_toml_str("[base]\nrate = 0.1\n[scenarios.base]\nrate = 0.2\n").is_err());
+    }
+
+    #[test]
+    fn test_failed_scenario_names_the_case() {
+        let file = ScenarioFile::from_toml_str(
+            "[base]\ninitial_investment = 100\ncash_flows = [60, 60]\ndiscount_rate = 0.1\n\
+             [scenarios.broken]\ndiscount_rate = -0.5\n"
+        ).unwrap();
+        
+        let error = evaluate_scenarios(&file, npv_from).unwrap_err();
+        assert!(error.to_string().contains("broken"));
+    }
+}
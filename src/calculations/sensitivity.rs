@@ -0,0 +1,247 @@
+//! Sensitivity analysis: one-way and two-way data tables and tornado rankings
+
+use crate::{FinanceError, FinanceResult, validate_finite};
+use std::str::FromStr;
+
+/// Largest number of values a single range may produce
+const MAX_RANGE_STEPS: usize = 1000;
+
+/// A range of values to try for one named input, written `name=min:max:step`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensitivityRange {
+    pub parameter: String,
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+}
+
+impl FromStr for SensitivityRange {
+    type Err = FinanceError;
+
+    /// Parses a range written as `name=min:max:step`, e.g. `discount-rate=0.05:0.12:0.01`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (parameter, range) = s.split_once('=')
+            .ok_or_else(|| FinanceError::InvalidInput(format!("Sensitivity must be name=min:max:step: {}", s)))?;
+        
+        let bounds: Vec<f64> = range
+            .split(':')
+            .map(|part| part.trim().parse::<f64>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| FinanceError::InvalidInput(format!("Invalid sensitivity range: {}", range)))?;
+        
+        let [min, max, step] = bounds[..] else {
+            return Err(FinanceError::InvalidInput(format!("Sensitivity must be name=min:max:step: {}", s)));
+        };
+        
+        let range = SensitivityRange { parameter: parameter.trim().to_string(), min, max, step };
+        range.values()?;
+        Ok(range)
+    }
+}
+
+impl SensitivityRange {
+    /// Values from `min` to `max` inclusive in increments of `step`
+    /// 
+    /// # Examples
+    /// ```
+    /// use rusty_finance::calculations::SensitivityRange;
+    /// 
+    /// let range: SensitivityRange = "rate=0.05:0.07:0.01".parse().unwrap();
+    /// let values = range.values().unwrap();
+    /// assert_eq!(values.len(), 3);
+    /// assert!((values[2] - 0.07).abs() < 1e-12);
+    /// ```
+    pub fn values(&self) -> FinanceResult<Vec<f64>> {
+        validate_finite(self.min, "Sensitivity minimum")?;
+        validate_finite(self.max, "Sensitivity maximum")?;
+        validate_finite(self.step, "Sensitivity step")?;
+        
+        if self.max < self.min {
+            return Err(FinanceError::InvalidInput(format!("Sensitivity maximum is below the minimum for {}", self.parameter)));
+        }
+        
+        if self.step <= 0.0 {
+            return Err(FinanceError::InvalidInput(format!("Sensitivity step must be positive for {}", self.parameter)));
+        }
+        
+        // Round the count so accumulated floating-point error does not drop the last value
+        let steps = ((self.max - self.min) / self.step + 1e-9).floor() as usize;
+        if steps >= MAX_RANGE_STEPS {
+            return Err(FinanceError::InvalidInput(format!("Sensitivity range for {} has too many steps", self.parameter)));
+        }
+        
+        Ok((0..=steps).map(|i| self.min + self.step * i as f64).collect())
+    }
+}
+
+/// Results of varying two inputs together
+#[derive(Debug, Clone)]
+pub struct TwoWayTable {
+    pub row_values: Vec<f64>,
+    pub column_values: Vec<f64>,
+    /// `results[row][column]`, or `None` where the calculation failed
+    pub results: Vec<Vec<Option<f64>>>,
+}
+
+/// Effect on the result of moving one input from its minimum to its maximum
+#[derive(Debug, Clone)]
+pub struct TornadoBar {
+    pub parameter: String,
+    pub low_input: f64,
+    pub high_input: f64,
+    pub low_result: f64,
+    pub high_result: f64,
+}
+
+impl TornadoBar {
+    /// Absolute change in the result across the input's range
+    pub fn swing(&self) -> f64 {
+        (self.high_result - self.low_result).abs()
+    }
+}
+
+/// Evaluates a calculation at every value of one input
+/// 
+/// Values where the calculation fails are reported as `None` so a table can
+/// still be printed around them.
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{one_way_table, calculate_dcf, SensitivityRange};
+/// 
+/// let range: SensitivityRange = "rate=0.0:0.1:0.05".parse().unwrap();
+/// let table = one_way_table(&range, |rate| calculate_dcf(&[100.0, 100.0], rate)).unwrap();
+/// assert_eq!(table[0], (0.0, Some(200.0)));
+/// ```
+pub fn one_way_table<F>(range: &SensitivityRange, calculate: F) -> FinanceResult<Vec<(f64, Option<f64>)>>
+where
+    F: Fn(f64) -> FinanceResult<f64>,
+{
+    Ok(range.values()?
+        .into_iter()
+        .map(|value| (value, calculate(value).ok()))
+        .collect())
+}
+
+/// Evaluates a calculation at every combination of two inputs
+/// 
+/// # Arguments
+/// * `rows` - The input varied down the rows
+/// * `columns` - The input varied across the columns
+/// * `calculate` - Computes the result from a row value and a column value
+pub fn two_way_table<F>(rows: &SensitivityRange, columns: &SensitivityRange, calculate: F) -> FinanceResult<TwoWayTable>
+where
+    F: Fn(f64, f64) -> FinanceResult<f64>,
+{
+    let row_values = rows.values()?;
+    let column_values = columns.values()?;
+    
+    let results = row_values
+        .iter()
+        .map(|&row| column_values.iter().map(|&column| calculate(row, column).ok()).collect())
+        .collect();
+    
+    Ok(TwoWayTable { row_values, column_values, results })
+}
+
+/// Ranks inputs by how far the result moves across each input's range
+/// 
+/// Each input is set to its minimum and maximum with every other input at its
+/// base value. Bars are returned with the largest swing first.
+/// 
+/// # Arguments
+/// * `ranges` - The inputs to vary
+/// * `calculate` - Computes the result with one named input overridden
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{tornado, calculate_capm, SensitivityRange};
+/// 
+/// let ranges: Vec<SensitivityRange> = vec![
+///     "beta=0.8:1.2:0.1".parse().unwrap(),
+///     "risk-free-rate=0.02:0.04:0.01".parse().unwrap(),
+/// ];
+/// let bars = tornado(&ranges, |name, value| match name {
+///     "beta" => calculate_capm(0.03, value, 0.08),
+///     _ => calculate_capm(value, 1.0, 0.08),
+/// }).unwrap();
+/// assert_eq!(bars[0].parameter, "beta");
+/// ```
+pub fn tornado<F>(ranges: &[SensitivityRange], calculate: F) -> FinanceResult<Vec<TornadoBar>>
+where
+    F: Fn(&str, f64) -> FinanceResult<f64>,
+{
+    let mut bars = ranges
+        .iter()
+        .map(|range| {
+            range.values()?;
+            Ok(TornadoBar {
+                parameter: range.parameter.clone(),
+                low_input: range.min,
+                high_input: range.max,
+                low_result: calculate(&range.parameter, range.min)?,
+                high_result: calculate(&range.parameter, range.max)?,
+            })
+        })
+        .collect::<FinanceResult<Vec<_>>>()?;
+    
+    bars.sort_by(|a, b| b.swing().total_cmp(&a.swing()));
+    Ok(bars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculations::{calculate_loan_payment, calculate_npv};
+
+    #[test]
+    fn test_parse_range() {
+        let range: SensitivityRange = "discount-rate=0.05:0.12:0.01".parse().unwrap();
+        assert_eq!(range.parameter, "discount-rate");
+        assert_eq!(range.values().unwrap().len(), 8);
+        
+        assert!("discount-rate".parse::<SensitivityRange>().is_err());
+        assert!("rate=0.05:0.12".parse::<SensitivityRange>().is_err());
+        assert!("rate=0.12:0.05:0.01".parse::<SensitivityRange>().is_err());
+        assert!("rate=0.05:0.12:0".parse::<SensitivityRange>().is_err());
+        assert!("rate=0:1000000:1".parse::<SensitivityRange>().is_err());
+    }
+
+    #[test]
+    fn test_one_way_table_reports_failures() {
+        let range: SensitivityRange = "rate=-0.01:0.01:0.01".parse().unwrap();
+        let table = one_way_table(&range, |rate| calculate_npv(100.0, &[60.0, 60.0], rate)).unwrap();
+        
+        assert_eq!(table.len(), 3);
+        assert_eq!(table[0].1, None);
+        assert_eq!(table[1].1, Some(20.0));
+    }
+
+    #[test]
+    fn test_two_way_table() {
+        let rates: SensitivityRange = "rate=4:6:1".parse().unwrap();
+        let terms: SensitivityRange = "term=15:30:15".parse().unwrap();
+        let table = two_way_table(&rates, &terms, |rate, term| calculate_loan_payment(200000.0, rate, term)).unwrap();
+        
+        assert_eq!(table.results.len(), 3);
+        assert_eq!(table.results[0].len(), 2);
+        let expected = calculate_loan_payment(200000.0, 6.0, 15.0).unwrap();
+        assert_eq!(table.results[2][0], Some(expected));
+    }
+
+    #[test]
+    fn test_tornado_ranks_by_swing() {
+        let ranges: Vec<SensitivityRange> = vec![
+            "cash-inflow=900:1100:100".parse().unwrap(),
+            "discount-rate=0.05:0.15:0.05".parse().unwrap(),
+        ];
+        let bars = tornado(&ranges, |name, value| match name {
+            "cash-inflow" => calculate_npv(3000.0, &[value; 5], 0.1),
+            _ => calculate_npv(3000.0, &[1000.0; 5], value),
+        }).unwrap();
+        
+        assert_eq!(bars[0].parameter, "discount-rate");
+        assert!(bars[0].swing() > bars[1].swing());
+        assert!(bars[0].low_result > bars[0].high_result);
+    }
+}
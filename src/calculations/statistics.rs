@@ -53,7 +53,7 @@ pub fn calculate_median(numbers: &[f64]) -> FinanceResult<f64> {
     sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
     
     let len = sorted.len();
-    if len % 2 == 0 {
+    if len.is_multiple_of(2) {
         // Even number of elements - average of middle two
         Ok((sorted[len / 2 - 1] + sorted[len / 2]) / 2.0)
     } else {
@@ -332,7 +332,7 @@ mod tests {
     fn test_calculate_standard_deviation() {
         let numbers = vec![1.0, 2.0, 3.0, 4.0, 5.0];
         let std_dev = calculate_standard_deviation(&numbers).unwrap();
-        assert!((std_dev - 1.4142135623730951).abs() < 1e-10);
+        assert!((std_dev - std::f64::consts::SQRT_2).abs() < 1e-10);
     }
 
     #[test]
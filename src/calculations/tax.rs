@@ -0,0 +1,292 @@
+//! Progressive income tax brackets loaded from TOML
+
+use crate::{FinanceError, FinanceResult, validate_fraction, validate_non_negative};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// A bracket taxing income above `threshold` at `rate` (as a decimal)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TaxBracket {
+    pub threshold: f64,
+    pub rate: f64,
+}
+
+/// Brackets and standard deduction for one filing status
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BracketSchedule {
+    #[serde(default)]
+    pub standard_deduction: f64,
+    /// Brackets in ascending order of threshold, starting at zero
+    pub brackets: Vec<TaxBracket>,
+}
+
+/// A set of bracket schedules keyed by filing status
+/// 
+/// Tables are written in TOML with one section per filing status:
+/// 
+/// ```toml
+/// name = "Example"
+/// 
+/// [filing_status.single]
+/// standard_deduction = 14600
+/// brackets = [
+///     { threshold = 0, rate = 0.10 },
+///     { threshold = 11600, rate = 0.12 },
+/// ]
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TaxTable {
+    #[serde(default)]
+    pub name: String,
+    pub filing_status: BTreeMap<String, BracketSchedule>,
+}
+
+/// Tax due on the part of income falling within one bracket
+#[derive(Debug, Clone)]
+pub struct BracketTax {
+    pub threshold: f64,
+    /// Threshold of the next bracket, or `None` for the top bracket
+    pub ceiling: Option<f64>,
+    pub rate: f64,
+    pub taxable_amount: f64,
+    pub tax: f64,
+}
+
+/// Result of applying a bracket schedule to an income
+#[derive(Debug, Clone)]
+pub struct TaxCalculation {
+    pub gross_income: f64,
+    pub deduction: f64,
+    pub taxable_income: f64,
+    pub tax_owed: f64,
+    /// Rate applied to the next dollar of taxable income
+    pub marginal_rate: f64,
+    /// Tax owed as a fraction of gross income
+    pub effective_rate: f64,
+    pub brackets: Vec<BracketTax>,
+}
+
+impl TaxTable {
+    /// Parses and validates a bracket table from TOML
+    /// 
+    /// # Examples
+    /// ```
+    /// use rusty_finance::calculations::TaxTable;
+    /// 
+    /// let table = TaxTable::from_toml_str(r#"
+    ///     [filing_status.single]
+    ///     standard_deduction = 10000
+    ///     brackets = [{ threshold = 0, rate = 0.1 }, { threshold = 20000, rate = 0.2 }]
+    /// "#).unwrap();
+    /// assert_eq!(table.schedule("single").unwrap().brackets.len(), 2);
+    /// ```
+    pub fn from_toml_str(contents: &str) -> FinanceResult<Self> {
+        let table: TaxTable = toml::from_str(contents)
+            .map_err(|e| FinanceError::InvalidInput(format!("Invalid tax table: {}", e)))?;
+        
+        if table.filing_status.is_empty() {
+            return Err(FinanceError::InvalidInput("Tax table defines no filing statuses".into()));
+        }
+        
+        for (status, schedule) in &table.filing_status {
+            schedule.validate()
+                .map_err(|e| FinanceError::InvalidInput(format!("Filing status '{}': {}", status, e)))?;
+        }
+        
+        Ok(table)
+    }
+
+    /// Looks up the schedule for a filing status
+    pub fn schedule(&self, filing_status: &str) -> FinanceResult<&BracketSchedule> {
+        self.filing_status.get(filing_status).ok_or_else(|| {
+            let known: Vec<&str> = self.filing_status.keys().map(String::as_str).collect();
+            FinanceError::InvalidInput(format!(
+                "Unknown filing status '{}' (expected one of: {})", filing_status, known.join(", ")
+            ))
+        })
+    }
+}
+
+impl BracketSchedule {
+    /// Checks that brackets start at zero, ascend and have rates between 0 and 1
+    pub fn validate(&self) -> FinanceResult<()> {
+        validate_non_negative(self.standard_deduction, "Standard deduction")?;
+        
+        match self.brackets.first() {
+            Some(first) if first.threshold == 0.0 => {}
+            Some(_) => return Err(FinanceError::InvalidInput("The first bracket must start at zero".into())),
+            None => return Err(FinanceError::InvalidInput("At least one bracket is required".into())),
+        }
+        
+        for bracket in &self.brackets {
+            validate_fraction(bracket.rate, "Bracket rate")?;
+        }
+        
+        if self.brackets.windows(2).any(|pair| pair[1].threshold <= pair[0].threshold) {
+            return Err(FinanceError::InvalidInput("Bracket thresholds must be in ascending order".into()));
+        }
+        
+        Ok(())
+    }
+
+    /// Tax owed on an amount of taxable income (after deductions)
+    pub fn tax_on(&self, taxable_income: f64) -> f64 {
+        self.bracket_breakdown(taxable_income).iter().map(|b| b.tax).sum()
+    }
+
+    /// Rate of the bracket containing the next dollar of taxable income
+    pub fn marginal_rate(&self, taxable_income: f64) -> f64 {
+        self.brackets
+            .iter()
+            .rev()
+            .find(|bracket| taxable_income >= bracket.threshold)
+            .map_or(0.0, |bracket| bracket.rate)
+    }
+
+    fn bracket_breakdown(&self, taxable_income: f64) -> Vec<BracketTax> {
+        self.brackets
+            .iter()
+            .enumerate()
+            .map(|(index, bracket)| {
+                let ceiling = self.brackets.get(index + 1).map(|next| next.threshold);
+                let top = ceiling.map_or(taxable_income, |c| c.min(taxable_income));
+                let taxable_amount = (top - bracket.threshold).max(0.0);
+                
+                BracketTax {
+                    threshold: bracket.threshold,
+                    ceiling,
+                    rate: bracket.rate,
+                    taxable_amount,
+                    tax: taxable_amount * bracket.rate,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Calculates income tax under a progressive bracket schedule
+/// 
+/// # Arguments
+/// * `gross_income` - Income before deductions
+/// * `schedule` - The brackets for the taxpayer's filing status
+/// * `itemized_deduction` - Deduction to use instead of the standard deduction
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{calculate_income_tax, BracketSchedule, TaxBracket};
+/// 
+/// let schedule = BracketSchedule {
+///     standard_deduction: 10000.0,
+///     brackets: vec![
+///         TaxBracket { threshold: 0.0, rate: 0.10 },
+///         TaxBracket { threshold: 20000.0, rate: 0.20 },
+///     ],
+/// };
+/// let tax = calculate_income_tax(50000.0, &schedule, None).unwrap();
+/// assert_eq!(tax.taxable_income, 40000.0);
+/// assert!((tax.tax_owed - 6000.0).abs() < 1e-9);
+/// assert_eq!(tax.marginal_rate, 0.20);
+/// ```
+pub fn calculate_income_tax(
+    gross_income: f64,
+    schedule: &BracketSchedule,
+    itemized_deduction: Option<f64>
+) -> FinanceResult<TaxCalculation> {
+    validate_non_negative(gross_income, "Gross income")?;
+    schedule.validate()?;
+    
+    let deduction = match itemized_deduction {
+        Some(amount) => {
+            validate_non_negative(amount, "Itemized deduction")?;
+            amount
+        }
+        None => schedule.standard_deduction,
+    };
+    
+    let taxable_income = (gross_income - deduction).max(0.0);
+    let brackets = schedule.bracket_breakdown(taxable_income);
+    let tax_owed: f64 = brackets.iter().map(|b| b.tax).sum();
+    
+    Ok(TaxCalculation {
+        gross_income,
+        deduction,
+        taxable_income,
+        tax_owed,
+        marginal_rate: schedule.marginal_rate(taxable_income),
+        effective_rate: if gross_income > 0.0 { tax_owed / gross_income } else { 0.0 },
+        brackets,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: &str = r#"
+        name = "Test brackets"
+
+        [filing_status.single]
+        standard_deduction = 14600
+        brackets = [
+            { threshold = 0, rate = 0.10 },
+            { threshold = 11600, rate = 0.12 },
+            { threshold = 47150, rate = 0.22 },
+            { threshold = 100525, rate = 0.24 },
+        ]
+
+        [filing_status.married_joint]
+        standard_deduction = 29200
+        brackets = [
+            { threshold = 0, rate = 0.10 },
+            { threshold = 23200, rate = 0.12 },
+        ]
+    "#;
+
+    #[test]
+    fn test_load_tax_table() {
+        let table = TaxTable::from_toml_str(TABLE).unwrap();
+        assert_eq!(table.name, "Test brackets");
+        assert_eq!(table.schedule("married_joint").unwrap().standard_deduction, 29200.0);
+        assert!(table.schedule("head_of_household").is_err());
+    }
+
+    #[test]
+    fn test_progressive_tax() {
+        let table = TaxTable::from_toml_str(TABLE).unwrap();
+        let tax = calculate_income_tax(75000.0, table.schedule("single").unwrap(), None).unwrap();
+        
+        // 60,400 taxable: 10% of 11,600 + 12% of 35,550 + 22% of 13,250
+        assert!((tax.taxable_income - 60400.0).abs() < 1e-9);
+        assert!((tax.tax_owed - 8341.0).abs() < 1e-9);
+        assert_eq!(tax.marginal_rate, 0.22);
+        assert!((tax.effective_rate - 8341.0 / 75000.0).abs() < 1e-12);
+        assert_eq!(tax.brackets[3].taxable_amount, 0.0);
+    }
+
+    #[test]
+    fn test_deductions_and_low_income() {
+        let table = TaxTable::from_toml_str(TABLE).unwrap();
+        let schedule = table.schedule("single").unwrap();
+        
+        let itemized = calculate_income_tax(75000.0, schedule, Some(20000.0)).unwrap();
+        assert_eq!(itemized.taxable_income, 55000.0);
+        
+        let below_deduction = calculate_income_tax(10000.0, schedule, None).unwrap();
+        assert_eq!(below_deduction.tax_owed, 0.0);
+        assert_eq!(below_deduction.marginal_rate, 0.10);
+    }
+
+    #[test]
+    fn test_invalid_tax_tables() {
+        let unordered = "[filing_status.single]\nbrackets = [{ threshold = 0, rate = 0.1 }, { threshold = 0, rate = 0.2 }]";
+        assert!(TaxTable::from_toml_str(unordered).is_err());
+        
+        let no_zero = "[filing_status.single]\nbrackets = [{ threshold = 100, rate = 0.1 }]";
+        assert!(TaxTable::from_toml_str(no_zero).is_err());
+        
+        let bad_rate = "[filing_status.single]\nbrackets = [{ threshold = 0, rate = 10 }]";
+        assert!(TaxTable::from_toml_str(bad_rate).is_err());
+        
+        assert!(TaxTable::from_toml_str("name = \"empty\"\n[filing_status]").is_err());
+    }
+}
@@ -0,0 +1,253 @@
+//! After-tax projections for taxable, tax-deferred and tax-free accounts
+
+use crate::{FinanceError, FinanceResult, validate_fraction, validate_non_negative};
+
+/// Tax treatment of an investment account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountType {
+    /// Dividends and interest taxed each year, capital gains taxed at withdrawal
+    Taxable,
+    /// Contributions made before tax, withdrawals taxed as income
+    TaxDeferred,
+    /// Contributions made after tax, withdrawals untaxed
+    TaxFree,
+}
+
+impl AccountType {
+    /// Human-readable name of the account type
+    pub fn name(&self) -> &'static str {
+        match self {
+            AccountType::Taxable => "Taxable",
+            AccountType::TaxDeferred => "Tax-Deferred",
+            AccountType::TaxFree => "Tax-Free",
+        }
+    }
+}
+
+/// Assumptions shared by every account in the comparison
+/// 
+/// Contributions are pre-tax earnings: the tax-deferred account receives them
+/// in full while the taxable and tax-free accounts receive what is left after
+/// tax at `current_income_tax_rate`. All rates are annual decimals.
+#[derive(Debug, Clone)]
+pub struct TaxProjectionInputs {
+    /// Pre-tax amount available to invest today
+    pub initial_investment: f64,
+    /// Pre-tax amount available to invest at the end of each year
+    pub annual_contribution: f64,
+    pub years: u32,
+    /// Total annual return before tax
+    pub annual_return: f64,
+    /// Part of the return paid out each year as dividends and interest
+    pub dividend_yield: f64,
+    pub current_income_tax_rate: f64,
+    pub retirement_income_tax_rate: f64,
+    pub dividend_tax_rate: f64,
+    pub capital_gains_tax_rate: f64,
+}
+
+/// Balances of each account at the end of a year
+#[derive(Debug, Clone)]
+pub struct AccountBalances {
+    pub year: u32,
+    pub taxable: f64,
+    pub tax_deferred: f64,
+    pub tax_free: f64,
+}
+
+/// Ending position of one account
+#[derive(Debug, Clone)]
+pub struct AccountOutcome {
+    pub account: AccountType,
+    /// Amount actually deposited into the account
+    pub contributions: f64,
+    pub ending_balance: f64,
+    /// Tax on dividends over the projection plus tax due at withdrawal
+    pub taxes_paid: f64,
+    /// Balance left after paying withdrawal taxes
+    pub after_tax_value: f64,
+}
+
+/// Year-by-year balances and ending outcomes of a tax comparison
+#[derive(Debug, Clone)]
+pub struct TaxProjection {
+    pub years: Vec<AccountBalances>,
+    /// Outcomes in the order taxable, tax-deferred, tax-free
+    pub outcomes: Vec<AccountOutcome>,
+}
+
+/// Projects the same contribution stream through taxable, tax-deferred and tax-free accounts
+/// 
+/// The taxable account pays tax on dividends every year and reinvests what is
+/// left, adding it to its cost basis; unrealized gains are taxed when the
+/// account is liquidated at the end. The tax-deferred balance is taxed as
+/// income on withdrawal and the tax-free balance is not taxed at all.
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{project_tax_accounts, TaxProjectionInputs};
+/// 
+/// let inputs = TaxProjectionInputs {
+///     initial_investment: 10000.0, annual_contribution: 6000.0, years: 30,
+///     annual_return: 0.07, dividend_yield: 0.02,
+///     current_income_tax_rate: 0.24, retirement_income_tax_rate: 0.22,
+///     dividend_tax_rate: 0.15, capital_gains_tax_rate: 0.15,
+/// };
+/// let projection = project_tax_accounts(&inputs).unwrap();
+/// let taxable = &projection.outcomes[0];
+/// let tax_free = &projection.outcomes[2];
+/// assert!(tax_free.after_tax_value > taxable.after_tax_value);
+/// ```
+pub fn project_tax_accounts(inputs: &TaxProjectionInputs) -> FinanceResult<TaxProjection> {
+    validate_non_negative(inputs.initial_investment, "Initial investment")?;
+    validate_non_negative(inputs.annual_contribution, "Annual contribution")?;
+    validate_non_negative(inputs.annual_return, "Annual return")?;
+    validate_non_negative(inputs.dividend_yield, "Dividend yield")?;
+    
+    for (rate, name) in [
+        (inputs.current_income_tax_rate, "Current income tax rate"),
+        (inputs.retirement_income_tax_rate, "Retirement income tax rate"),
+        (inputs.dividend_tax_rate, "Dividend tax rate"),
+        (inputs.capital_gains_tax_rate, "Capital gains tax rate"),
+    ] {
+        validate_fraction(rate, name)?;
+    }
+    
+    if inputs.dividend_yield > inputs.annual_return {
+        return Err(FinanceError::InvalidInput("Dividend yield cannot exceed the annual return".into()));
+    }
+    
+    let after_tax_share = 1.0 - inputs.current_income_tax_rate;
+    let after_tax_initial = inputs.initial_investment * after_tax_share;
+    let after_tax_contribution = inputs.annual_contribution * after_tax_share;
+    
+    let mut taxable = after_tax_initial;
+    let mut cost_basis = after_tax_initial;
+    let mut dividend_taxes = 0.0;
+    let mut tax_deferred = inputs.initial_investment;
+    let mut tax_free = after_tax_initial;
+    let mut years = Vec::with_capacity(inputs.years as usize);
+    
+    for year in 1..=inputs.years {
+        // Dividends are taxed and reinvested; the rest of the return is unrealized gain
+        let dividends = taxable * inputs.dividend_yield;
+        let dividend_tax = dividends * inputs.dividend_tax_rate;
+        dividend_taxes += dividend_tax;
+        taxable += taxable * inputs.annual_return - dividend_tax;
+        cost_basis += dividends - dividend_tax;
+        
+        tax_deferred *= 1.0 + inputs.annual_return;
+        tax_free *= 1.0 + inputs.annual_return;
+        
+        taxable += after_tax_contribution;
+        cost_basis += after_tax_contribution;
+        tax_deferred += inputs.annual_contribution;
+        tax_free += after_tax_contribution;
+        
+        years.push(AccountBalances { year, taxable, tax_deferred, tax_free });
+    }
+    
+    let capital_gains_tax = (taxable - cost_basis).max(0.0) * inputs.capital_gains_tax_rate;
+    let withdrawal_tax = tax_deferred * inputs.retirement_income_tax_rate;
+    let after_tax_contributions = after_tax_initial + after_tax_contribution * inputs.years as f64;
+    
+    let outcomes = vec![
+        AccountOutcome {
+            account: AccountType::Taxable,
+            contributions: after_tax_contributions,
+            ending_balance: taxable,
+            taxes_paid: dividend_taxes + capital_gains_tax,
+            after_tax_value: taxable - capital_gains_tax,
+        },
+        AccountOutcome {
+            account: AccountType::TaxDeferred,
+            contributions: inputs.initial_investment + inputs.annual_contribution * inputs.years as f64,
+            ending_balance: tax_deferred,
+            taxes_paid: withdrawal_tax,
+            after_tax_value: tax_deferred - withdrawal_tax,
+        },
+        AccountOutcome {
+            account: AccountType::TaxFree,
+            contributions: after_tax_contributions,
+            ending_balance: tax_free,
+            taxes_paid: 0.0,
+            after_tax_value: tax_free,
+        },
+    ];
+    
+    Ok(TaxProjection { years, outcomes })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_inputs() -> TaxProjectionInputs {
+        TaxProjectionInputs {
+            initial_investment: 10000.0,
+            annual_contribution: 5000.0,
+            years: 25,
+            annual_return: 0.07,
+            dividend_yield: 0.02,
+            current_income_tax_rate: 0.24,
+            retirement_income_tax_rate: 0.24,
+            dividend_tax_rate: 0.15,
+            capital_gains_tax_rate: 0.15,
+        }
+    }
+
+    #[test]
+    fn test_no_taxes_makes_accounts_equal() {
+        let inputs = TaxProjectionInputs {
+            current_income_tax_rate: 0.0,
+            retirement_income_tax_rate: 0.0,
+            dividend_tax_rate: 0.0,
+            capital_gains_tax_rate: 0.0,
+            ..sample_inputs()
+        };
+        let projection = project_tax_accounts(&inputs).unwrap();
+        
+        let values: Vec<f64> = projection.outcomes.iter().map(|o| o.after_tax_value).collect();
+        assert!((values[0] - values[1]).abs() < 1e-6);
+        assert!((values[1] - values[2]).abs() < 1e-6);
+        assert_eq!(projection.years.len(), 25);
+    }
+
+    #[test]
+    fn test_equal_rates_make_deferred_and_free_equivalent() {
+        let projection = project_tax_accounts(&sample_inputs()).unwrap();
+        let deferred = &projection.outcomes[1];
+        let free = &projection.outcomes[2];
+        
+        assert!((deferred.after_tax_value - free.after_tax_value).abs() < 1e-6);
+        assert!(projection.outcomes[0].after_tax_value < free.after_tax_value);
+    }
+
+    #[test]
+    fn test_taxable_account_single_year() {
+        let inputs = TaxProjectionInputs {
+            initial_investment: 1000.0,
+            annual_contribution: 0.0,
+            years: 1,
+            annual_return: 0.10,
+            dividend_yield: 0.04,
+            current_income_tax_rate: 0.0,
+            retirement_income_tax_rate: 0.3,
+            dividend_tax_rate: 0.25,
+            capital_gains_tax_rate: 0.2,
+        };
+        let taxable = &project_tax_accounts(&inputs).unwrap().outcomes[0];
+        
+        // $40 of dividends taxed at 25% leaves 1090, with $60 of unrealized gain taxed at 20%
+        assert!((taxable.ending_balance - 1090.0).abs() < 1e-9);
+        assert!((taxable.taxes_paid - 22.0).abs() < 1e-9);
+        assert!((taxable.after_tax_value - 1078.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_tax_projection_invalid_inputs() {
+        assert!(project_tax_accounts(&TaxProjectionInputs { dividend_tax_rate: 1.5, ..sample_inputs() }).is_err());
+        assert!(project_tax_accounts(&TaxProjectionInputs { dividend_yield: 0.08, ..sample_inputs() }).is_err());
+        assert!(project_tax_accounts(&TaxProjectionInputs { annual_contribution: -1.0, ..sample_inputs() }).is_err());
+    }
+}
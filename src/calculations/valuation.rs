@@ -0,0 +1,464 @@
+//! Company valuation from projected free cash flow with a terminal value
+
+use crate::{FinanceError, FinanceResult, validate_finite, validate_fraction, validate_non_negative, validate_positive};
+use crate::calculations::calculate_wacc;
+use serde::Deserialize;
+
+/// An assumption given once for every year or once per projected year
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Assumption {
+    Constant(f64),
+    PerYear(Vec<f64>),
+}
+
+impl Assumption {
+    /// Value for a projection year, counted from zero
+    pub fn for_year(&self, index: usize) -> f64 {
+        match self {
+            Assumption::Constant(value) => *value,
+            Assumption::PerYear(values) => values[index],
+        }
+    }
+
+    pub(crate) fn validate(&self, years: u32, name: &str) -> FinanceResult<()> {
+        match self {
+            Assumption::Constant(value) => validate_finite(*value, name),
+            Assumption::PerYear(values) => {
+                if values.len() != years as usize {
+                    return Err(FinanceError::InvalidInput(format!(
+                        "{} needs one value per projected year ({}), found {}", name, years, values.len()
+                    )));
+                }
+                values.iter().try_for_each(|value| validate_finite(*value, name))
+            }
+        }
+    }
+}
+
+/// Which free cash flow is projected and the rate it is discounted at
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CashFlowBasis {
+    /// Free cash flow to the firm, discounted at WACC
+    #[default]
+    Fcff,
+    /// Free cash flow to equity, discounted at the cost of equity
+    Fcfe,
+}
+
+/// How the value beyond the final projected year is estimated
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(tag = "method", rename_all = "kebab-case")]
+pub enum TerminalValueMethod {
+    /// Final-year free cash flow growing forever at `growth`
+    Gordon { growth: f64 },
+    /// Final-year EBITDA times an enterprise-value multiple
+    ExitMultiple { multiple: f64 },
+}
+
+/// Inputs to the WACC used as the discount rate
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DiscountRateInputs {
+    pub cost_of_equity: f64,
+    pub cost_of_debt: f64,
+    pub market_value_equity: f64,
+    pub market_value_debt: f64,
+}
+
+/// Balance sheet items that bridge enterprise value to equity value
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EquityBridge {
+    #[serde(default)]
+    pub cash: f64,
+    #[serde(default)]
+    pub debt: f64,
+    #[serde(default)]
+    pub minority_interest: f64,
+    pub shares_outstanding: f64,
+}
+
+/// Operating assumptions for a valuation, read from TOML
+/// 
+/// Margins, D&A, capex and working capital are fractions of revenue.
+/// Working capital is a level, so its yearly change is the investment.
+/// 
+/// ```toml
+/// base_revenue = 1000
+/// years = 5
+/// revenue_growth = [0.10, 0.08, 0.06, 0.05, 0.04]
+/// ebitda_margin = 0.25
+/// depreciation = 0.04
+/// capex = 0.05
+/// working_capital = 0.10
+/// tax_rate = 0.25
+/// 
+/// [terminal]
+/// method = "gordon"
+/// growth = 0.025
+/// 
+/// [discount]
+/// cost_of_equity = 0.10
+/// cost_of_debt = 0.05
+/// market_value_equity = 4000
+/// market_value_debt = 1000
+/// 
+/// [bridge]
+/// cash = 200
+/// debt = 1000
+/// shares_outstanding = 100
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ValuationAssumptions {
+    /// Revenue in the most recent year
+    pub base_revenue: f64,
+    pub years: u32,
+    pub revenue_growth: Assumption,
+    pub ebitda_margin: Assumption,
+    /// Depreciation and amortization
+    pub depreciation: Assumption,
+    pub capex: Assumption,
+    pub working_capital: Assumption,
+    pub tax_rate: f64,
+    #[serde(default)]
+    pub cash_flow: CashFlowBasis,
+    /// New borrowing less repayments each year, as an amount; used for FCFE
+    #[serde(default)]
+    pub net_borrowing: Option<Assumption>,
+    pub terminal: TerminalValueMethod,
+    pub discount: DiscountRateInputs,
+    pub bridge: EquityBridge,
+}
+
+/// One projected year
+#[derive(Debug, Clone)]
+pub struct ProjectedYear {
+    pub year: u32,
+    pub revenue: f64,
+    pub ebitda: f64,
+    pub depreciation: f64,
+    pub ebit: f64,
+    /// Tax on operating profit
+    pub taxes: f64,
+    pub capex: f64,
+    pub change_in_working_capital: f64,
+    pub free_cash_flow: f64,
+    pub present_value: f64,
+}
+
+/// Result of a discounted cash flow valuation
+#[derive(Debug, Clone)]
+pub struct Valuation {
+    pub basis: CashFlowBasis,
+    /// WACC for FCFF, the cost of equity for FCFE
+    pub discount_rate: f64,
+    pub years: Vec<ProjectedYear>,
+    pub terminal_value: f64,
+    pub present_value_of_terminal: f64,
+    pub enterprise_value: f64,
+    pub equity_value: f64,
+    pub value_per_share: f64,
+}
+
+impl Valuation {
+    /// Present value of the projected free cash flows
+    pub fn present_value_of_flows(&self) -> f64 {
+        self.years.iter().map(|year| year.present_value).sum()
+    }
+
+    /// Share of the valuation that comes from the terminal value
+    pub fn terminal_share(&self) -> f64 {
+        let total = self.present_value_of_flows() + self.present_value_of_terminal;
+        if total == 0.0 { 0.0 } else { self.present_value_of_terminal / total }
+    }
+}
+
+impl ValuationAssumptions {
+    /// Parses and validates valuation assumptions from TOML
+    pub fn from_toml_str(contents: &str) -> FinanceResult<Self> {
+        let assumptions: ValuationAssumptions = toml::from_str(contents)
+            .map_err(|e| FinanceError::InvalidInput(format!("Invalid valuation assumptions: {}", e)))?;
+        assumptions.validate()?;
+        Ok(assumptions)
+    }
+
+    /// Checks that every assumption is usable
+    pub fn validate(&self) -> FinanceResult<()> {
+        validate_positive(self.base_revenue, "Base revenue")?;
+        if self.years == 0 {
+            return Err(FinanceError::InvalidInput("At least one projected year is required".into()));
+        }
+        
+        self.revenue_growth.validate(self.years, "Revenue growth")?;
+        self.ebitda_margin.validate(self.years, "EBITDA margin")?;
+        self.depreciation.validate(self.years, "Depreciation")?;
+        self.capex.validate(self.years, "Capex")?;
+        self.working_capital.validate(self.years, "Working capital")?;
+        if let Some(net_borrowing) = &self.net_borrowing {
+            net_borrowing.validate(self.years, "Net borrowing")?;
+        }
+        
+        validate_fraction(self.tax_rate, "Tax rate")?;
+        
+        match self.terminal {
+            TerminalValueMethod::Gordon { growth } => validate_finite(growth, "Terminal growth")?,
+            TerminalValueMethod::ExitMultiple { multiple } => validate_non_negative(multiple, "Exit multiple")?,
+        }
+        
+        validate_non_negative(self.bridge.cash, "Cash")?;
+        validate_non_negative(self.bridge.debt, "Debt")?;
+        validate_non_negative(self.bridge.minority_interest, "Minority interest")?;
+        validate_positive(self.bridge.shares_outstanding, "Shares outstanding")
+    }
+
+    /// Rate the projected flows are discounted at
+    pub fn discount_rate(&self) -> FinanceResult<f64> {
+        let discount = &self.discount;
+        match self.cash_flow {
+            CashFlowBasis::Fcff => calculate_wacc(
+                discount.cost_of_equity,
+                discount.cost_of_debt,
+                self.tax_rate,
+                discount.market_value_equity,
+                discount.market_value_debt,
+            ),
+            CashFlowBasis::Fcfe => {
+                validate_non_negative(discount.cost_of_equity, "Cost of equity")?;
+                Ok(discount.cost_of_equity)
+            }
+        }
+    }
+}
+
+/// Values a company by discounting projected free cash flow and a terminal value
+/// 
+/// Free cash flow to the firm is EBIT after tax plus D&A, less capex and the
+/// increase in working capital. Free cash flow to equity further deducts
+/// after-tax interest on the bridge debt at the cost of debt and adds net
+/// borrowing. An exit multiple prices the firm, so under FCFE the claims
+/// ahead of equity at exit (the bridge debt plus all projected net borrowing,
+/// and minority interest, less cash) are deducted to leave the equity's share.
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{value_company, ValuationAssumptions};
+/// 
+/// let assumptions = ValuationAssumptions::from_toml_str(r#"
+///     base_revenue = 1000
+///     years = 3
+///     revenue_growth = 0.0
+///     ebitda_margin = 0.25
+///     depreciation = 0.05
+///     capex = 0.05
+///     working_capital = 0.0
+///     tax_rate = 0.25
+///     terminal = { method = "gordon", growth = 0.0 }
+///     discount = { cost_of_equity = 0.10, cost_of_debt = 0.0, market_value_equity = 1, market_value_debt = 0 }
+///     bridge = { shares_outstanding = 10 }
+/// "#).unwrap();
+/// let valuation = value_company(&assumptions).unwrap();
+/// // A flat 150 a year forever at 10% is worth 1,500
+/// assert!((valuation.enterprise_value - 1500.0).abs() < 1e-9);
+/// assert!((valuation.value_per_share - 150.0).abs() < 1e-9);
+/// ```
+pub fn value_company(assumptions: &ValuationAssumptions) -> FinanceResult<Valuation> {
+    assumptions.validate()?;
+    
+    let discount_rate = assumptions.discount_rate()?;
+    if discount_rate <= -1.0 {
+        return Err(FinanceError::InvalidInput("Discount rate must be greater than -100%".into()));
+    }
+    
+    let bridge = &assumptions.bridge;
+    let after_tax_interest = bridge.debt * assumptions.discount.cost_of_debt * (1.0 - assumptions.tax_rate);
+    
+    let mut revenue = assumptions.base_revenue;
+    let mut working_capital = revenue * assumptions.working_capital.for_year(0);
+    let mut years = Vec::with_capacity(assumptions.years as usize);
+    
+    for index in 0..assumptions.years as usize {
+        revenue *= 1.0 + assumptions.revenue_growth.for_year(index);
+        let ebitda = revenue * assumptions.ebitda_margin.for_year(index);
+        let depreciation = revenue * assumptions.depreciation.for_year(index);
+        let ebit = ebitda - depreciation;
+        let taxes = ebit.max(0.0) * assumptions.tax_rate;
+        let capex = revenue * assumptions.capex.for_year(index);
+        
+        let new_working_capital = revenue * assumptions.working_capital.for_year(index);
+        let change_in_working_capital = new_working_capital - working_capital;
+        working_capital = new_working_capital;
+        
+        let mut free_cash_flow = ebit - taxes + depreciation - capex - change_in_working_capital;
+        if assumptions.cash_flow == CashFlowBasis::Fcfe {
+            let net_borrowing = assumptions.net_borrowing.as_ref().map_or(0.0, |b| b.for_year(index));
+            free_cash_flow += net_borrowing - after_tax_interest;
+        }
+        
+        let year = index as u32 + 1;
+        years.push(ProjectedYear {
+            year,
+            revenue,
+            ebitda,
+            depreciation,
+            ebit,
+            taxes,
+            capex,
+            change_in_working_capital,
+            free_cash_flow,
+            present_value: free_cash_flow / (1.0 + discount_rate).powi(year as i32),
+        });
+    }
+    
+    let last = years.last().expect("at least one projected year");
+    let terminal_value = match assumptions.terminal {
+        TerminalValueMethod::Gordon { growth } => {
+            if discount_rate <= growth {
+                return Err(FinanceError::InvalidInput(format!(
+                    "Discount rate ({}) must exceed terminal growth ({})", discount_rate, growth
+                )));
+            }
+            last.free_cash_flow * (1.0 + growth) / (discount_rate - growth)
+        }
+        TerminalValueMethod::ExitMultiple { multiple } => match assumptions.cash_flow {
+            CashFlowBasis::Fcff => last.ebitda * multiple,
+            CashFlowBasis::Fcfe => {
+                let borrowed: f64 = assumptions.net_borrowing.as_ref()
+                    .map_or(0.0, |b| (0..assumptions.years as usize).map(|index| b.for_year(index)).sum());
+                let exit_claims = bridge.debt + borrowed + bridge.minority_interest - bridge.cash;
+                last.ebitda * multiple - exit_claims
+            }
+        },
+    };
+    let present_value_of_terminal = terminal_value / (1.0 + discount_rate).powi(assumptions.years as i32);
+    let present_value: f64 = years.iter().map(|year| year.present_value).sum::<f64>() + present_value_of_terminal;
+    
+    let net_claims = bridge.debt + bridge.minority_interest - bridge.cash;
+    let (enterprise_value, equity_value) = match assumptions.cash_flow {
+        CashFlowBasis::Fcff => (present_value, present_value - net_claims),
+        CashFlowBasis::Fcfe => (present_value + net_claims, present_value),
+    };
+    
+    Ok(Valuation {
+        basis: assumptions.cash_flow,
+        discount_rate,
+        years,
+        terminal_value,
+        present_value_of_terminal,
+        enterprise_value,
+        equity_value,
+        value_per_share: equity_value / bridge.shares_outstanding,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASSUMPTIONS: &str = r#"
+        base_revenue = 1000
+        years = 3
+        revenue_growth = [0.10, 0.10, 0.05]
+        ebitda_margin = 0.30
+        depreciation = 0.05
+        capex = 0.06
+        working_capital = 0.10
+        tax_rate = 0.25
+
+        [terminal]
+        method = "exit-multiple"
+        multiple = 8
+
+        [discount]
+        cost_of_equity = 0.12
+        cost_of_debt = 0.06
+        market_value_equity = 3000
+        market_value_debt = 1000
+
+        [bridge]
+        cash = 150
+        debt = 1000
+        minority_interest = 50
+        shares_outstanding = 100
+    "#;
+
+    #[test]
+    fn test_fcff_projection() {
+        let assumptions = ValuationAssumptions::from_toml_str(ASSUMPTIONS).unwrap();
+        let valuation = value_company(&assumptions).unwrap();
+        
+        // WACC = 0.75 × 12% + 0.25 × 6% × 0.75
+        assert!((valuation.discount_rate - 0.10125).abs() < 1e-12);
+        
+        // Year 1: revenue 1,100, EBIT 275, tax 68.75, D&A 55, capex 66, working capital up 10
+        let first = &valuation.years[0];
+        assert!((first.revenue - 1100.0).abs() < 1e-9);
+        assert!((first.change_in_working_capital - 10.0).abs() < 1e-9);
+        assert!((first.free_cash_flow - 185.25).abs() < 1e-9);
+        
+        let last = &valuation.years[2];
+        assert!((valuation.terminal_value - last.ebitda * 8.0).abs() < 1e-9);
+        assert!((valuation.equity_value - (valuation.enterprise_value - 900.0)).abs() < 1e-9);
+        assert!((valuation.value_per_share - valuation.equity_value / 100.0).abs() < 1e-12);
+        assert!(valuation.terminal_share() > 0.5);
+    }
+
+    #[test]
+    fn test_gordon_terminal_value() {
+        let mut assumptions = ValuationAssumptions::from_toml_str(ASSUMPTIONS).unwrap();
+        assumptions.terminal = TerminalValueMethod::Gordon { growth: 0.02 };
+        let valuation = value_company(&assumptions).unwrap();
+        
+        let last = &valuation.years[2];
+        let expected = last.free_cash_flow * 1.02 / (valuation.discount_rate - 0.02);
+        assert!((valuation.terminal_value - expected).abs() < 1e-9);
+        
+        assumptions.terminal = TerminalValueMethod::Gordon { growth: 0.15 };
+        assert!(value_company(&assumptions).is_err());
+    }
+
+    #[test]
+    fn test_fcfe_discounts_at_cost_of_equity() {
+        let mut assumptions = ValuationAssumptions::from_toml_str(ASSUMPTIONS).unwrap();
+        assumptions.cash_flow = CashFlowBasis::Fcfe;
+        assumptions.net_borrowing = Some(Assumption::Constant(20.0));
+        let fcff = value_company(&ValuationAssumptions { cash_flow: CashFlowBasis::Fcff, ..assumptions.clone() }).unwrap();
+        let fcfe = value_company(&assumptions).unwrap();
+        
+        assert_eq!(fcfe.discount_rate, 0.12);
+        // Interest of 60 after 25% tax is 45, offset by 20 of new borrowing
+        assert!((fcfe.years[0].free_cash_flow - (fcff.years[0].free_cash_flow - 25.0)).abs() < 1e-9);
+        assert!((fcfe.enterprise_value - fcfe.equity_value - 900.0).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_fcfe_and_fcff_exit_multiples_bridge_to_the_same_equity() {
+        // Without discounting or interest, the two bases differ only in how the bridge is applied
+        let mut assumptions = ValuationAssumptions::from_toml_str(ASSUMPTIONS).unwrap();
+        assumptions.discount = DiscountRateInputs {
+            cost_of_equity: 0.0,
+            cost_of_debt: 0.0,
+            market_value_equity: 3000.0,
+            market_value_debt: 1000.0,
+        };
+        assumptions.net_borrowing = Some(Assumption::PerYear(vec![20.0, -40.0, 60.0]));
+        
+        let fcff = value_company(&assumptions).unwrap();
+        let fcfe = value_company(&ValuationAssumptions { cash_flow: CashFlowBasis::Fcfe, ..assumptions }).unwrap();
+        
+        // Exit claims: 1,000 of debt, 40 borrowed, 50 minority interest, less 150 of cash
+        assert!((fcff.terminal_value - fcfe.terminal_value - 940.0).abs() < 1e-9);
+        assert!((fcfe.equity_value - fcff.equity_value).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_invalid_valuation_assumptions() {
+        let short_list = ASSUMPTIONS.replace("[0.10, 0.10, 0.05]", "[0.10, 0.10]");
+        assert!(ValuationAssumptions::from_toml_str(&short_list).is_err());
+        
+        let no_shares = ASSUMPTIONS.replace("shares_outstanding = 100", "shares_outstanding = 0");
+        assert!(ValuationAssumptions::from_toml_str(&no_shares).is_err());
+        
+        let unknown_method = ASSUMPTIONS.replace("exit-multiple", "liquidation");
+        assert!(ValuationAssumptions::from_toml_str(&unknown_method).is_err());
+    }
+}
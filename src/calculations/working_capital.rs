@@ -0,0 +1,370 @@
+//! Working capital: days inventory, sales and payables outstanding, the cash conversion cycle and trends
+
+use crate::{FinanceError, FinanceResult, validate_finite, validate_non_negative, validate_positive};
+use serde::Deserialize;
+use std::io::Read;
+
+/// Days in a period when none is given
+pub const DAYS_IN_YEAR: f64 = 365.0;
+
+/// Changes smaller than this many days are reported as flat
+const TREND_TOLERANCE_DAYS: f64 = 0.05;
+
+/// Calculates days inventory outstanding (DIO)
+/// 
+/// Formula: DIO = Inventory / Cost of Goods Sold × Days in Period
+/// 
+/// # Arguments
+/// * `inventory` - Inventory balance
+/// * `cost_of_goods_sold` - Cost of goods sold for the period
+/// * `days_in_period` - Days the income statement covers (365 for a year, 30 for a month)
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_days_inventory_outstanding;
+/// 
+/// let dio = calculate_days_inventory_outstanding(100000.0, 730000.0, 365.0).unwrap();
+/// assert_eq!(dio, 50.0);
+/// ```
+pub fn calculate_days_inventory_outstanding(inventory: f64, cost_of_goods_sold: f64, days_in_period: f64) -> FinanceResult<f64> {
+    days_outstanding(inventory, "Inventory", cost_of_goods_sold, "Cost of goods sold", days_in_period)
+}
+
+/// Calculates days sales outstanding (DSO)
+/// 
+/// Formula: DSO = Accounts Receivable / Revenue × Days in Period
+/// 
+/// # Arguments
+/// * `receivables` - Accounts receivable balance
+/// * `revenue` - Revenue for the period
+/// * `days_in_period` - Days the income statement covers
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_days_sales_outstanding;
+/// 
+/// let dso = calculate_days_sales_outstanding(150000.0, 1095000.0, 365.0).unwrap();
+/// assert_eq!(dso, 50.0);
+/// ```
+pub fn calculate_days_sales_outstanding(receivables: f64, revenue: f64, days_in_period: f64) -> FinanceResult<f64> {
+    days_outstanding(receivables, "Receivables", revenue, "Revenue", days_in_period)
+}
+
+/// Calculates days payables outstanding (DPO)
+/// 
+/// Formula: DPO = Accounts Payable / Cost of Goods Sold × Days in Period
+/// 
+/// # Arguments
+/// * `payables` - Accounts payable balance
+/// * `cost_of_goods_sold` - Cost of goods sold for the period
+/// * `days_in_period` - Days the income statement covers
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_days_payables_outstanding;
+/// 
+/// let dpo = calculate_days_payables_outstanding(60000.0, 730000.0, 365.0).unwrap();
+/// assert_eq!(dpo, 30.0);
+/// ```
+pub fn calculate_days_payables_outstanding(payables: f64, cost_of_goods_sold: f64, days_in_period: f64) -> FinanceResult<f64> {
+    days_outstanding(payables, "Payables", cost_of_goods_sold, "Cost of goods sold", days_in_period)
+}
+
+/// Calculates the cash conversion cycle: days between paying suppliers and collecting from customers
+/// 
+/// Formula: CCC = DIO + DSO - DPO
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_cash_conversion_cycle;
+/// 
+/// assert_eq!(calculate_cash_conversion_cycle(50.0, 50.0, 30.0), 70.0);
+/// ```
+pub fn calculate_cash_conversion_cycle(
+    days_inventory_outstanding: f64,
+    days_sales_outstanding: f64,
+    days_payables_outstanding: f64
+) -> f64 {
+    days_inventory_outstanding + days_sales_outstanding - days_payables_outstanding
+}
+
+/// Calculates the extra working capital needed to support revenue growth
+/// 
+/// Assumes inventory, receivables and payables grow in proportion to revenue,
+/// so the investment needed is today's net working capital times the growth rate.
+/// 
+/// Formula: Financing Need = (Inventory + Receivables - Payables) × Growth Rate
+/// 
+/// # Arguments
+/// * `net_working_capital` - Inventory plus receivables less payables
+/// * `growth_rate` - Revenue growth rate (as a decimal)
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::calculate_working_capital_financing_need;
+/// 
+/// let need = calculate_working_capital_financing_need(190000.0, 0.10).unwrap();
+/// assert!((need - 19000.0).abs() < 1e-9);
+/// ```
+pub fn calculate_working_capital_financing_need(net_working_capital: f64, growth_rate: f64) -> FinanceResult<f64> {
+    validate_finite(net_working_capital, "Net working capital")?;
+    validate_finite(growth_rate, "Growth rate")?;
+    
+    Ok(net_working_capital * growth_rate)
+}
+
+/// Balance-sheet and income-statement figures for one period
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WorkingCapitalPeriod {
+    pub period: String,
+    /// Days the income statement covers; a year when empty
+    #[serde(default)]
+    pub days: Option<f64>,
+    pub revenue: f64,
+    pub cost_of_goods_sold: f64,
+    pub inventory: f64,
+    pub receivables: f64,
+    pub payables: f64,
+}
+
+/// Direction of a working-capital measure relative to the previous period
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Improving,
+    Worsening,
+    Flat,
+}
+
+impl Trend {
+    /// Compares two values of a measure where fewer days are better (DIO, DSO, CCC) or worse (DPO)
+    /// 
+    /// # Examples
+    /// ```
+    /// use rusty_finance::calculations::Trend;
+    /// 
+    /// assert_eq!(Trend::between(70.0, 64.0, true), Trend::Improving);
+    /// assert_eq!(Trend::between(30.0, 28.0, false), Trend::Worsening);
+    /// ```
+    pub fn between(previous: f64, current: f64, lower_is_better: bool) -> Trend {
+        let change = current - previous;
+        if change.abs() < TREND_TOLERANCE_DAYS {
+            Trend::Flat
+        } else if (change < 0.0) == lower_is_better {
+            Trend::Improving
+        } else {
+            Trend::Worsening
+        }
+    }
+
+    /// Human-readable name of the trend
+    pub fn name(&self) -> &'static str {
+        match self {
+            Trend::Improving => "Improving",
+            Trend::Worsening => "Worsening",
+            Trend::Flat => "Flat",
+        }
+    }
+}
+
+/// Working-capital measures for one period
+#[derive(Debug, Clone)]
+pub struct WorkingCapitalMetrics {
+    pub period: String,
+    pub revenue: f64,
+    pub days_inventory_outstanding: f64,
+    pub days_sales_outstanding: f64,
+    pub days_payables_outstanding: f64,
+    pub cash_conversion_cycle: f64,
+    /// Inventory plus receivables less payables
+    pub net_working_capital: f64,
+    /// Change in the cash conversion cycle since the previous period
+    pub trend: Option<Trend>,
+}
+
+/// First and latest value of one measure across the periods analyzed
+#[derive(Debug, Clone)]
+pub struct MetricTrend {
+    pub name: &'static str,
+    pub first: f64,
+    pub latest: f64,
+    pub direction: Trend,
+}
+
+impl WorkingCapitalPeriod {
+    /// Days the period covers
+    pub fn days_in_period(&self) -> f64 {
+        self.days.unwrap_or(DAYS_IN_YEAR)
+    }
+
+    /// Calculates DIO, DSO, DPO, the cash conversion cycle and net working capital
+    pub fn metrics(&self) -> FinanceResult<WorkingCapitalMetrics> {
+        let days = self.days_in_period();
+        let dio = calculate_days_inventory_outstanding(self.inventory, self.cost_of_goods_sold, days)?;
+        let dso = calculate_days_sales_outstanding(self.receivables, self.revenue, days)?;
+        let dpo = calculate_days_payables_outstanding(self.payables, self.cost_of_goods_sold, days)?;
+        
+        Ok(WorkingCapitalMetrics {
+            period: self.period.clone(),
+            revenue: self.revenue,
+            days_inventory_outstanding: dio,
+            days_sales_outstanding: dso,
+            days_payables_outstanding: dpo,
+            cash_conversion_cycle: calculate_cash_conversion_cycle(dio, dso, dpo),
+            net_working_capital: self.inventory + self.receivables - self.payables,
+            trend: None,
+        })
+    }
+}
+
+/// Reads periods from CSV with the header `period,days,revenue,cost_of_goods_sold,inventory,receivables,payables`
+/// 
+/// The `days` column is optional and may be left empty for annual figures.
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::read_working_capital_csv;
+/// 
+/// let csv = "period,days,revenue,cost_of_goods_sold,inventory,receivables,payables\n\
+///            2024-01,31,93000,62000,100000,90000,40000\n";
+/// let periods = read_working_capital_csv(csv.as_bytes()).unwrap();
+/// assert_eq!(periods[0].days_in_period(), 31.0);
+/// ```
+pub fn read_working_capital_csv<R: Read>(reader: R) -> FinanceResult<Vec<WorkingCapitalPeriod>> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(reader);
+    
+    csv_reader
+        .deserialize()
+        .enumerate()
+        .map(|(index, record)| {
+            record.map_err(|e| FinanceError::InvalidInput(format!("Invalid period on row {}: {}", index + 1, e)))
+        })
+        .collect()
+}
+
+/// Calculates working-capital measures for each period and the cash conversion cycle trend
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::calculations::{analyze_working_capital, read_working_capital_csv, Trend};
+/// 
+/// let csv = "period,days,revenue,cost_of_goods_sold,inventory,receivables,payables\n\
+///            FY2023,,1095000,730000,100000,150000,60000\n\
+///            FY2024,,1200000,800000,100000,150000,60000\n";
+/// let metrics = analyze_working_capital(&read_working_capital_csv(csv.as_bytes()).unwrap()).unwrap();
+/// assert_eq!(metrics[1].trend, Some(Trend::Improving));
+/// ```
+pub fn analyze_working_capital(periods: &[WorkingCapitalPeriod]) -> FinanceResult<Vec<WorkingCapitalMetrics>> {
+    let mut metrics: Vec<WorkingCapitalMetrics> = Vec::with_capacity(periods.len());
+    
+    for period in periods {
+        let mut current = period.metrics()
+            .map_err(|e| match e {
+                FinanceError::InvalidInput(message) => FinanceError::InvalidInput(format!("Period {}: {}", period.period, message)),
+                other => other,
+            })?;
+        current.trend = metrics
+            .last()
+            .map(|previous| Trend::between(previous.cash_conversion_cycle, current.cash_conversion_cycle, true));
+        metrics.push(current);
+    }
+    
+    Ok(metrics)
+}
+
+/// Compares the first and latest period for each measure
+/// 
+/// Returns nothing when fewer than two periods were analyzed.
+pub fn summarize_trends(metrics: &[WorkingCapitalMetrics]) -> Vec<MetricTrend> {
+    if metrics.len() < 2 {
+        return Vec::new();
+    }
+    let (first, latest) = (&metrics[0], &metrics[metrics.len() - 1]);
+    
+    let trend = |name, first: f64, latest: f64, lower_is_better| MetricTrend {
+        name,
+        first,
+        latest,
+        direction: Trend::between(first, latest, lower_is_better),
+    };
+    
+    vec![
+        trend("Days Inventory Outstanding", first.days_inventory_outstanding, latest.days_inventory_outstanding, true),
+        trend("Days Sales Outstanding", first.days_sales_outstanding, latest.days_sales_outstanding, true),
+        trend("Days Payables Outstanding", first.days_payables_outstanding, latest.days_payables_outstanding, false),
+        trend("Cash Conversion Cycle", first.cash_conversion_cycle, latest.cash_conversion_cycle, true),
+    ]
+}
+
+/// Balance as days of a flow over the period
+fn days_outstanding(balance: f64, balance_name: &str, flow: f64, flow_name: &str, days_in_period: f64) -> FinanceResult<f64> {
+    validate_non_negative(balance, balance_name)?;
+    validate_positive(flow, flow_name)?;
+    validate_positive(days_in_period, "Days in period")?;
+    
+    Ok(balance / flow * days_in_period)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn period(name: &str, days: Option<f64>, revenue: f64, inventory: f64, receivables: f64, payables: f64) -> WorkingCapitalPeriod {
+        WorkingCapitalPeriod {
+            period: name.to_string(),
+            days,
+            revenue,
+            cost_of_goods_sold: revenue * 0.6,
+            inventory,
+            receivables,
+            payables,
+        }
+    }
+
+    #[test]
+    fn test_cash_conversion_cycle_from_statements() {
+        let metrics = period("FY2024", None, 1000000.0, 120000.0, 100000.0, 60000.0).metrics().unwrap();
+        
+        assert!((metrics.days_inventory_outstanding - 120000.0 / 600000.0 * 365.0).abs() < 1e-9);
+        assert!((metrics.days_sales_outstanding - 36.5).abs() < 1e-9);
+        assert!((metrics.days_payables_outstanding - 36.5).abs() < 1e-9);
+        assert!((metrics.cash_conversion_cycle - metrics.days_inventory_outstanding).abs() < 1e-9);
+        assert_eq!(metrics.net_working_capital, 160000.0);
+    }
+
+    #[test]
+    fn test_monthly_periods_use_their_own_days() {
+        let january = period("Jan", Some(31.0), 100000.0, 60000.0, 50000.0, 30000.0).metrics().unwrap();
+        let annualized = period("FY", None, 100000.0 * 365.0 / 31.0, 60000.0, 50000.0, 30000.0).metrics().unwrap();
+        
+        assert!((january.cash_conversion_cycle - annualized.cash_conversion_cycle).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_trends_across_periods() {
+        let periods = vec![
+            period("Q1", Some(90.0), 300000.0, 90000.0, 100000.0, 40000.0),
+            period("Q2", Some(91.0), 300000.0, 80000.0, 110000.0, 40000.0),
+            period("Q3", Some(92.0), 330000.0, 80000.0, 95000.0, 50000.0),
+        ];
+        let metrics = analyze_working_capital(&periods).unwrap();
+        
+        assert_eq!(metrics[0].trend, None);
+        assert_eq!(metrics[2].trend, Some(Trend::Improving));
+        
+        let trends = summarize_trends(&metrics);
+        assert_eq!(trends.len(), 4);
+        assert_eq!(trends[2].direction, Trend::Improving);
+        assert!(summarize_trends(&metrics[..1]).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_period_is_named() {
+        let error = analyze_working_capital(&[period("Q4", None, 0.0, 1.0, 1.0, 1.0)]).unwrap_err();
+        assert!(error.to_string().contains("Q4"));
+        
+        assert_eq!(Trend::between(50.0, 50.01, true), Trend::Flat);
+    }
+}
@@ -3,6 +3,7 @@
 use comfy_table::{Cell, CellAlignment, Color, ContentArrangement, Table};
 use rust_decimal::prelude::*;
 use log::warn;
+use crate::money::Currency;
 
 /// Creates a styled table with the given headers
 /// 
@@ -34,7 +35,7 @@ pub fn create_table(headers: Vec<&str>) -> Table {
 /// use rusty_finance::display::{create_table, add_row};
 /// use comfy_table::CellAlignment;
 /// 
-/// let mut table = create_table(&["Account", "Balance"]);
+/// let mut table = create_table(vec!["Account", "Balance"]);
 /// add_row(&mut table, &[
 ///     ("Checking", CellAlignment::Left),
 ///     ("$1,234.56", CellAlignment::Right),
@@ -70,12 +71,40 @@ pub fn format_currency(number: f64) -> String {
 
 /// Formats a number as currency without colors for better table alignment
 /// 
+/// Uses the display currency set with [`set_display_currency`] (US dollars by
+/// default), with that currency's symbol and minor units.
+/// 
 /// # Arguments
 /// * `number` - The number to format as currency
 /// 
 /// # Returns
 /// * A formatted currency string without color codes
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::display::format_currency_plain;
+/// 
+/// assert_eq!(format_currency_plain(27.5), "$27.50");
+/// ```
 pub fn format_currency_plain(number: f64) -> String {
+    format_currency_in(number, display_currency())
+}
+
+/// Formats a number as an amount of the given currency
+/// 
+/// # Arguments
+/// * `number` - The number to format
+/// * `currency` - The currency whose symbol and minor units to use
+/// 
+/// # Examples
+/// ```
+/// use rusty_finance::display::format_currency_in;
+/// use rusty_finance::money::Currency;
+/// 
+/// assert_eq!(format_currency_in(1234.56, Currency::JPY), "¥1,235");
+/// assert_eq!(format_currency_in(1234.5, Currency::KWD), "KWD 1,234.500");
+/// ```
+pub fn format_currency_in(number: f64, currency: Currency) -> String {
     // Convert the f64 to a Decimal for accurate handling
     let decimal = Decimal::from_f64(number)
         .unwrap_or_else(|| {
@@ -83,24 +112,21 @@ pub fn format_currency_plain(number: f64) -> String {
             Decimal::ZERO
         });
     
-    // Round to 2 decimal places
-    let rounded = decimal.round_dp(2);
-    
-    // Use the Decimal formatting functionality directly
-    let formatted = rounded.to_string();
-    
-    // Split into whole and decimal parts
-    let parts: Vec<&str> = formatted.split('.').collect();
-    let whole_part = parts[0];
-    let decimal_part = parts.get(1).map_or("00", |&s| {
-        if s.len() >= 2 { &s[0..2] } else { s }
-    });
-    
-    // Add commas to the whole part
-    let whole_with_commas = add_thousands_separators(whole_part);
-    
-    // Format as currency without colors
-    format!("${}.{}", whole_with_commas, decimal_part)
+    currency.format(decimal)
+}
+
+/// Sets the currency used by [`format_currency`] and [`format_currency_plain`] on this thread
+pub fn set_display_currency(currency: Currency) {
+    DISPLAY_CURRENCY.with(|current| current.set(currency));
+}
+
+/// The currency amounts are currently displayed in
+pub fn display_currency() -> Currency {
+    DISPLAY_CURRENCY.with(std::cell::Cell::get)
+}
+
+thread_local! {
+    static DISPLAY_CURRENCY: std::cell::Cell<Currency> = std::cell::Cell::new(Currency::default());
 }
 
 /// Formats a percentage with appropriate coloring
@@ -158,7 +184,7 @@ pub fn format_number(number: f64, decimal_places: usize) -> String {
 /// 
 /// # Returns
 /// * The number string with comma separators
-fn add_thousands_separators(number_str: &str) -> String {
+pub(crate) fn add_thousands_separators(number_str: &str) -> String {
     let is_negative = number_str.starts_with('-');
     let digits = if is_negative { &number_str[1..] } else { number_str };
     
@@ -282,6 +308,17 @@ mod tests {
         assert!(result.contains("$0.00"));
     }
 
+    #[test]
+    fn test_format_currency_uses_display_currency() {
+        assert_eq!(format_currency_plain(27.5), "$27.50");
+        
+        set_display_currency(Currency::JPY);
+        assert_eq!(format_currency_plain(1234.56), "¥1,235");
+        set_display_currency(Currency::KWD);
+        assert_eq!(format_currency_plain(-0.0001), "KWD 0.000");
+        set_display_currency(Currency::USD);
+    }
+
     #[test]
     fn test_add_thousands_separators() {
         assert_eq!(add_thousands_separators("1234567"), "1,234,567");
@@ -15,6 +15,7 @@ use thiserror::Error;
 pub mod calculations;
 pub mod cli;
 pub mod display;
+pub mod money;
 
 /// Custom finance calculation errors
 #[derive(Error, Debug)]
@@ -65,6 +66,15 @@ pub fn validate_finite(value: f64, name: &str) -> FinanceResult<()> {
     Ok(())
 }
 
+/// Validates that a number is a fraction between 0 and 1 inclusive
+pub fn validate_fraction(value: f64, name: &str) -> FinanceResult<()> {
+    validate_finite(value, name)?;
+    if !(0.0..=1.0).contains(&value) {
+        return Err(FinanceError::InvalidInput(format!("{} must be between 0 and 1: {}", name, value)));
+    }
+    Ok(())
+}
+
 /// Converts f64 to Decimal with error handling
 pub fn to_decimal(value: f64, name: &str) -> FinanceResult<Decimal> {
     Decimal::from_f64(value)
@@ -1,22 +1,28 @@
+// Command structs are named after the financial acronyms they compute (NPV, IRR, ...)
+#![allow(clippy::upper_case_acronyms)]
+
 use anyhow::{Context, Result};
-use chrono::{Local, Months};
+use chrono::{Datelike, Local, NaiveDate};
 
 use clap::{CommandFactory, Parser};
 use clap_complete::{generate, shells::{Bash, Fish, Zsh, PowerShell}};
+use std::collections::HashMap;
+use std::fs::File;
 use std::io;
+use std::path::{Path, PathBuf};
 use clap_verbosity_flag::{InfoLevel, Verbosity};
-use comfy_table::{Cell, CellAlignment, Color, ContentArrangement, Table};
+use comfy_table::CellAlignment;
 use dialoguer::{Input, Select, theme::ColorfulTheme};
 
 use env_logger::Env;
-use log::{debug, info, warn};
+use log::{debug, info};
 use owo_colors::OwoColorize;
-use rust_decimal::{Decimal, prelude::FromPrimitive};
 
 // Import from rusty_finance library
 use rusty_finance::FinanceError;
 use rusty_finance::calculations::*;
 use rusty_finance::display::*;
+use rusty_finance::money::{Currency, Money};
 
 
 /// Financial calculation tool
@@ -31,6 +37,14 @@ struct Opts {
     #[clap(short, long)]
     interactive: bool,
     
+    /// Also report results in today's money, using an annual inflation rate (e.g. 0.03) or a CPI CSV file (date,cpi) with start and end rows; retirement plans with this rate
+    #[clap(long, global = true, value_name = "RATE|CPI_FILE")]
+    inflation: Option<String>,
+    
+    /// ISO 4217 currency code used to display amounts (e.g. EUR, JPY, KWD)
+    #[clap(long, global = true, default_value = "USD", value_name = "CODE")]
+    currency: Currency,
+    
     #[clap(subcommand)]
     command: Option<Command>,
 }
@@ -116,6 +130,66 @@ enum Command {
     /// Calculates the return on equity (ROE).
     ReturnOnEquity(ReturnOnEquity),
     
+    /// Compares refinancing offers against the current loan.
+    Refinance(Refinance),
+    
+    /// Plans payoff of multiple debts with snowball, avalanche and custom strategies.
+    DebtPlan(DebtPlan),
+    
+    /// Compares the net worth of buying a home against renting.
+    RentVsBuy(RentVsBuy),
+    
+    /// Compares the net worth of leasing an asset against buying it.
+    LeaseVsBuy(LeaseVsBuy),
+    
+    /// Plans retirement savings and withdrawals.
+    Retirement(Retirement),
+    
+    /// Compares after-tax growth in taxable, tax-deferred and tax-free accounts.
+    TaxAccounts(TaxAccounts),
+    
+    /// Calculates income tax from a progressive bracket table.
+    Tax(Tax),
+    
+    /// Matches trades into tax lots and reports realized and unrealized gains.
+    Lots(Lots),
+    
+    /// Evaluates a calculation for a base case and named scenarios from a file.
+    Scenario(Scenario),
+    
+    /// Values a company from projected free cash flow and a terminal value.
+    Valuation(Valuation),
+    
+    /// Values a share with the Gordon growth, two-stage or H-model dividend discount model.
+    DDM(DDM),
+    
+    /// Compares projects by NPV, MIRR, profitability index, discounted payback and equivalent annual annuity.
+    CompareProjects(CompareProjects),
+    
+    /// Values the option to expand, contract, abandon or defer a project with a binomial lattice.
+    RealOption(RealOptionAnalysis),
+    
+    /// Cost-volume-profit analysis: sales-mix break-even, target profit, margin of safety and operating leverage.
+    CVP(CVP),
+    
+    /// Calculates DIO, DSO, DPO, the cash conversion cycle and working-capital financing needs across periods.
+    WorkingCapital(WorkingCapital),
+    
+    /// Projects a three-statement model with a revolver or cash sweep from a base year and drivers.
+    Model(Model),
+    
+    /// Runs a leveraged buyout with a debt tranche stack, returning sponsor IRR, MOIC and an entry/exit grid.
+    Lbo(Lbo),
+    
+    /// Tests DSCR, interest coverage, debt yield and LTV covenants by period and sizes the maximum loan.
+    Covenants(Covenants),
+    
+    /// Projects a rental property's NOI, cash flow, cap rate, cash-on-cash, DSCR and hold-period IRRs.
+    Property(Property),
+    
+    /// Converts currencies with cross rates, prices FX forwards and converts dated cash flows for NPV.
+    Fx(Fx),
+    
     /// Generate shell completions.
     Completion(Completion),
 }
@@ -165,59 +239,6 @@ struct CompoundInterest {
     t: i32,
 }
 
-impl ReturnOnEquity {
-    /// Calculate ROE and display results with error handling
-    fn execute(&self) -> Result<()> {
-        debug!("Calculating ROE with: {:?}", self);
-        
-        // Validate inputs are finite numbers
-        if !self.net_income.is_finite() {
-            return Err(FinanceError::InvalidInput(format!("Net income must be a valid number: {}", self.net_income)).into());
-        }
-        
-        if !self.equity.is_finite() {
-            return Err(FinanceError::InvalidInput(format!("Equity must be a valid number: {}", self.equity)).into());
-        }
-        
-        // Convert net_income and equity to Decimal with proper error handling
-        let net_income = Decimal::from_f64(self.net_income)
-            .ok_or_else(|| FinanceError::InvalidInput(format!("Invalid net income: {}", self.net_income)))?;
-        
-        let equity = Decimal::from_f64(self.equity)
-            .ok_or_else(|| FinanceError::InvalidInput(format!("Invalid equity: {}", self.equity)))?;
-            
-        // Validate inputs
-        if equity.is_zero() {
-            return Err(FinanceError::DivisionByZero.into());
-        }
-        
-        // Equity should be positive for a meaningful ROE calculation
-        if equity < Decimal::ZERO {
-            return Err(FinanceError::InvalidInput(format!("Equity should be positive: {}", self.equity)).into());
-        }
-
-        // Calculate the return on equity
-        let roe = (net_income / equity) * Decimal::from_f64(100.0).unwrap();
-        info!("Calculated ROE: {:.4}%", roe);
-
-        // Create the table with modern styling
-        let mut table = create_table(vec!["Net Income", "Equity", "Return on Equity"]);
-        
-        // Add data row with colorful formatting
-        table.add_row(vec![
-            Cell::new(&format_currency(self.net_income)),
-            Cell::new(&format_currency(self.equity)),
-            Cell::new(&format!("{:.2}%", roe)).fg(Color::Green).set_alignment(CellAlignment::Right),
-        ]);
-
-        // Print the table
-        println!("{table}");
-        
-        info!("ROE calculation completed successfully");
-        Ok(())
-    }
-}
-
 #[derive(Parser, Debug)]
 struct PresentValue {
     /// The future value of the investment.
@@ -259,7 +280,7 @@ struct DividendYield {
     price: f64,
 }
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 struct NPV {
     /// The initial investment or cost
     #[clap(short, long, name = "initial-investment")]
@@ -276,6 +297,18 @@ struct NPV {
     /// The lifespan of the investment in years
     #[clap(short, long, name = "lifespan")]
     lifespan: i32,
+
+    /// Vary an input over name=min:max:step and print a sensitivity table (repeatable)
+    #[clap(long)]
+    sensitivity: Vec<SensitivityRange>,
+
+    /// Input to solve for so the result equals --target; the input's own flag is the starting guess
+    #[clap(long, requires = "target", conflicts_with = "sensitivity")]
+    solve_for: Option<String>,
+
+    /// Result the --solve-for input should produce
+    #[clap(long, requires = "solve_for")]
+    target: Option<f64>,
 }
 
 #[derive(Parser, Debug)]
@@ -288,9 +321,50 @@ struct Amortization {
     #[clap(short = 'i', long)]
     annual_interest_rate: f64,
 
-    /// The loan term in years
+    /// The loan term in years (the balloon term for balloon loans)
     #[clap(short = 't', long)]
     loan_term_years: i32,
+
+    /// Number of interest-only months before amortization begins
+    #[clap(long, conflicts_with_all = ["balloon_amortization_years", "constant_principal", "payments"])]
+    interest_only_months: Option<u32>,
+
+    /// Amortization period in years for a balloon loan (balance due at the end of the term)
+    #[clap(long, conflicts_with_all = ["constant_principal", "payments"])]
+    balloon_amortization_years: Option<u32>,
+
+    /// Repay equal principal each month instead of level payments
+    #[clap(long, conflicts_with = "payments")]
+    constant_principal: bool,
+
+    /// Explicit monthly payments, comma-separated (e.g., 500,500,750)
+    #[clap(long, value_delimiter = ',')]
+    payments: Option<Vec<f64>>,
+
+    /// Due date of the first payment (YYYY-MM-DD); defaults to the first of next month
+    #[clap(long)]
+    first_payment_date: Option<NaiveDate>,
+
+    /// Show every payment instead of the calendar-year summary
+    #[clap(long)]
+    detailed: bool,
+}
+
+impl Amortization {
+    /// Repayment structure selected by the command-line flags
+    fn structure(&self) -> LoanStructure {
+        if let Some(months) = self.interest_only_months {
+            LoanStructure::InterestOnly { months }
+        } else if let Some(amortization_years) = self.balloon_amortization_years {
+            LoanStructure::Balloon { amortization_years }
+        } else if self.constant_principal {
+            LoanStructure::ConstantPrincipal
+        } else if let Some(payments) = &self.payments {
+            LoanStructure::CustomPayments(payments.clone())
+        } else {
+            LoanStructure::FullyAmortizing
+        }
+    }
 }
 
 /// Calculate present value
@@ -436,34 +510,12 @@ struct IRR {
 
 /// Format a number as currency with colored output
 fn format_currency(number: f64) -> String {
-    // Convert the f64 to a Decimal for accurate handling
-    let decimal = Decimal::from_f64(number)
-        .unwrap_or_else(|| {
-            warn!("Invalid number for currency formatting: {}", number);
-            Decimal::ZERO
-        });
-    
-    // Round to 2 decimal places
-    let rounded = decimal.round_dp(2);
-    
-    // Use the Decimal formatting functionality directly
-    let formatted = rounded.to_string();
-    
-    // Split into whole and decimal parts
-    let parts: Vec<&str> = formatted.split('.').collect();
-    let whole_part = parts[0];
-    let decimal_part = parts.get(1).map_or("00", |&s| {
-        if s.len() >= 2 { &s[0..2] } else { s }
-    });
-    
-    // Add commas to the whole part
-    let whole_with_commas = add_thousands_separators(whole_part);
+    let formatted = format_currency_plain(number);
     
-    // Format as currency
     if number >= 0.0 {
-        format!("${}.{}", whole_with_commas, decimal_part).green().to_string()
+        formatted.green().to_string()
     } else {
-        format!("${}.{}", whole_with_commas, decimal_part).red().to_string()
+        formatted.red().to_string()
     }
 }
 
@@ -492,7 +544,7 @@ struct Probability {
     trials: u32,
 }
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 struct CAPM {
     /// The risk-free rate
     #[clap(short, long)]
@@ -505,31 +557,21 @@ struct CAPM {
     /// The expected return of the market
     #[clap(short, long)]
     market_return: f64,
-}
 
-/// Add thousands separators (commas) to a number string
-fn add_thousands_separators(number_str: &str) -> String {
-    let is_negative = number_str.starts_with('-');
-    let digits = if is_negative { &number_str[1..] } else { number_str };
-    
-    let mut result = String::new();
-    let len = digits.len();
-    
-    for (i, c) in digits.chars().enumerate() {
-        if i > 0 && (len - i) % 3 == 0 {
-            result.push(',');
-        }
-        result.push(c);
-    }
-    
-    if is_negative {
-        format!("-{}", result)
-    } else {
-        result
-    }
+    /// Vary an input over name=min:max:step and print a sensitivity table (repeatable)
+    #[clap(long)]
+    sensitivity: Vec<SensitivityRange>,
+
+    /// Input to solve for so the result equals --target; the input's own flag is the starting guess
+    #[clap(long, requires = "target", conflicts_with = "sensitivity")]
+    solve_for: Option<String>,
+
+    /// Result the --solve-for input should produce
+    #[clap(long, requires = "solve_for")]
+    target: Option<f64>,
 }
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 struct LoanPayment {
     /// The principal amount of the loan
     #[clap(short, long)]
@@ -542,9 +584,25 @@ struct LoanPayment {
     /// The loan term in years
     #[clap(short, long)]
     loan_term: f64,
+
+    /// Due date of the first payment (YYYY-MM-DD); defaults to the first of next month
+    #[clap(long)]
+    first_payment_date: Option<NaiveDate>,
+
+    /// Vary an input over name=min:max:step and print a sensitivity table (repeatable)
+    #[clap(long)]
+    sensitivity: Vec<SensitivityRange>,
+
+    /// Input to solve for so the result equals --target; the input's own flag is the starting guess
+    #[clap(long, requires = "target", conflicts_with = "sensitivity")]
+    solve_for: Option<String>,
+
+    /// Result the --solve-for input should produce
+    #[clap(long, requires = "solve_for")]
+    target: Option<f64>,
 }
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 struct BreakEvenUnits {
     /// The fixed costs incurred by the business
     #[clap(short, long, name = "fixed-costs")]
@@ -557,9 +615,17 @@ struct BreakEvenUnits {
     /// The price per unit of the product or service
     #[clap(short, long, name = "price-per-unit")]
     price_per_unit: f64,
+
+    /// Input to solve for so the result equals --target; the input's own flag is the starting guess
+    #[clap(long, requires = "target")]
+    solve_for: Option<String>,
+
+    /// Result the --solve-for input should produce
+    #[clap(long, requires = "solve_for")]
+    target: Option<f64>,
 }
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 struct DCF {
     /// The discount rate
     #[clap(short, long, name = "discount-rate")]
@@ -568,6 +634,18 @@ struct DCF {
     /// The cash flows for the investment/project
     #[clap(name = "cash-flows")]
     cash_flows: Vec<f64>,
+
+    /// Vary an input over name=min:max:step and print a sensitivity table (repeatable)
+    #[clap(long)]
+    sensitivity: Vec<SensitivityRange>,
+
+    /// Input to solve for so the result equals --target; the input's own flag is the starting guess
+    #[clap(long, requires = "target", conflicts_with = "sensitivity")]
+    solve_for: Option<String>,
+
+    /// Result the --solve-for input should produce
+    #[clap(long, requires = "solve_for")]
+    target: Option<f64>,
 }
 
 #[derive(Parser, Debug)]
@@ -583,6 +661,10 @@ struct Mortgage {
     /// The loan term in years
     #[clap(short, long)]
     term: i32,
+
+    /// Due date of the first payment (YYYY-MM-DD); defaults to the first of next month
+    #[clap(long)]
+    first_payment_date: Option<NaiveDate>,
 }
 
 #[derive(Parser, Debug)]
@@ -596,7 +678,7 @@ struct WeightedAverage {
     weights: String,
 }
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 struct WACC {
     /// The cost of equity (Ke)
     #[clap(long)]
@@ -607,8 +689,8 @@ struct WACC {
     cost_of_debt: f64,
 
     /// The tax rate
-    #[clap(long)]
-    tax_rate: f64,
+    #[clap(long, required_unless_present = "tax_brackets")]
+    tax_rate: Option<f64>,
 
     /// The market value of equity (E)
     #[clap(long)]
@@ -617,485 +699,3114 @@ struct WACC {
     /// The market value of debt (D)
     #[clap(long)]
     market_value_debt: f64,
+
+    /// TOML bracket table to take the marginal tax rate from instead of --tax-rate
+    #[clap(long, conflicts_with = "tax_rate", requires = "taxable_income")]
+    tax_brackets: Option<PathBuf>,
+
+    /// Taxable income used to find the marginal rate in --tax-brackets
+    #[clap(long)]
+    taxable_income: Option<f64>,
+
+    /// Filing status in --tax-brackets
+    #[clap(long, default_value = "single")]
+    filing_status: String,
+
+    /// Vary an input over name=min:max:step and print a sensitivity table (repeatable)
+    #[clap(long)]
+    sensitivity: Vec<SensitivityRange>,
+
+    /// Input to solve for so the result equals --target; the input's own flag is the starting guess
+    #[clap(long, requires = "target", conflicts_with = "sensitivity")]
+    solve_for: Option<String>,
+
+    /// Result the --solve-for input should produce
+    #[clap(long, requires = "solve_for")]
+    target: Option<f64>,
 }
 
+impl WACC {
+    /// The flat tax rate, or the marginal rate from the bracket table
+    fn effective_tax_rate(&self) -> Result<f64> {
+        match (&self.tax_brackets, self.tax_rate) {
+            (Some(path), _) => {
+                let table = load_tax_table(path)?;
+                let schedule = table.schedule(&self.filing_status)?;
+                Ok(schedule.marginal_rate(self.taxable_income.unwrap_or_default()))
+            }
+            (None, Some(rate)) => Ok(rate),
+            (None, None) => Err(anyhow::anyhow!("Either --tax-rate or --tax-brackets is required")),
+        }
+    }
+}
 
 #[derive(Parser, Debug)]
-struct Completion {
-    /// Shell to generate completions for
-    #[clap(value_enum)]
-    shell: Shell,
+struct Refinance {
+    /// The outstanding balance of the current loan
+    #[clap(short, long)]
+    balance: f64,
+
+    /// The current annual interest rate (as a percentage)
+    #[clap(short, long)]
+    rate: f64,
+
+    /// The remaining term of the current loan in years
+    #[clap(short = 't', long)]
+    remaining_term: i32,
+
+    /// A new offer as rate:term[:points[:closing_costs]], e.g. 5.75:30:1:3500 (repeatable)
+    #[clap(short, long = "offer", required = true)]
+    offers: Vec<RefinanceOffer>,
+
+    /// The annual discount rate used for the NPV of switching (as a decimal)
+    #[clap(short, long, default_value_t = 0.05)]
+    discount_rate: f64,
 }
 
-#[derive(clap::ValueEnum, Clone, Debug)]
-enum Shell {
-    Bash,
-    Fish,
-    Zsh,
-    PowerShell,
+#[derive(Parser, Debug)]
+struct DebtPlan {
+    /// CSV file of debts with the header name,balance,apr,minimum_payment
+    #[clap(short, long)]
+    file: PathBuf,
+
+    /// The total monthly budget for debt payments
+    #[clap(short, long)]
+    budget: f64,
+
+    /// Custom payoff order as comma-separated debt names
+    #[clap(short, long, value_delimiter = ',')]
+    order: Option<Vec<String>>,
+
+    /// Date of the first payment (YYYY-MM-DD); defaults to the first of next month
+    #[clap(long)]
+    start_date: Option<NaiveDate>,
 }
 
-/// Run the application with proper error handling
-fn run() -> Result<()> {
-    // Parse command line arguments
-    let opts: Opts = Opts::parse();
-    
-    // Configure logging based on verbosity
-    env_logger::Builder::from_env(Env::default().default_filter_or("info"))
-        .filter_level(opts.verbose.log_level_filter())
-        .init();
-    
-    info!("Starting rusty-finance");
-    
-    // Handle interactive mode or regular command mode
-    let command = if opts.interactive {
-        show_interactive_menu()?
-    } else {
-        match opts.command {
-            Some(cmd) => cmd,
-            None => {
-                eprintln!("Error: No command specified. Use --interactive for interactive mode or specify a command.");
-                eprintln!("Run 'rusty-finance --help' for usage information.");
-                return Ok(());
-            }
+#[derive(Parser, Debug)]
+struct RentVsBuy {
+    /// The purchase price of the home
+    #[clap(long)]
+    home_price: f64,
+
+    /// The down payment as a fraction of the price
+    #[clap(long, default_value_t = 0.20)]
+    down_payment: f64,
+
+    /// Closing costs as a fraction of the price
+    #[clap(long, default_value_t = 0.03)]
+    closing_costs: f64,
+
+    /// The annual mortgage rate (as a percentage)
+    #[clap(long)]
+    mortgage_rate: f64,
+
+    /// The mortgage term in years
+    #[clap(long, default_value_t = 30)]
+    term: i32,
+
+    /// Annual property tax as a fraction of the home value
+    #[clap(long, default_value_t = 0.011)]
+    property_tax_rate: f64,
+
+    /// Annual maintenance as a fraction of the home value
+    #[clap(long, default_value_t = 0.01)]
+    maintenance_rate: f64,
+
+    /// Annual home price appreciation (as a decimal)
+    #[clap(long, default_value_t = 0.03)]
+    appreciation: f64,
+
+    /// Selling costs as a fraction of the sale price
+    #[clap(long, default_value_t = 0.06)]
+    selling_costs: f64,
+
+    /// The current monthly rent
+    #[clap(long)]
+    rent: f64,
+
+    /// Annual rent increase (as a decimal)
+    #[clap(long, default_value_t = 0.03)]
+    rent_inflation: f64,
+
+    /// Annual return on invested cash (as a decimal)
+    #[clap(long, default_value_t = 0.06)]
+    investment_return: f64,
+
+    /// The number of years to compare
+    #[clap(long, default_value_t = 10)]
+    years: u32,
+}
+
+#[derive(Parser, Debug)]
+struct LeaseVsBuy {
+    /// The purchase price of the asset
+    #[clap(long)]
+    price: f64,
+
+    /// Cash paid at signing of each lease
+    #[clap(long, default_value_t = 0.0)]
+    lease_down_payment: f64,
+
+    /// The lease term in months
+    #[clap(long, default_value_t = 36)]
+    lease_term: u32,
+
+    /// Residual value as a fraction of the price
+    #[clap(long)]
+    residual: f64,
+
+    /// The lease money factor (APR / 2400)
+    #[clap(long)]
+    money_factor: f64,
+
+    /// Down payment when buying
+    #[clap(long, default_value_t = 0.0)]
+    down_payment: f64,
+
+    /// The annual loan rate (as a percentage)
+    #[clap(long)]
+    loan_rate: f64,
+
+    /// The loan term in years
+    #[clap(long, default_value_t = 5)]
+    loan_term: i32,
+
+    /// Annual decline in the asset's value (as a decimal)
+    #[clap(long, default_value_t = 0.15)]
+    depreciation: f64,
+
+    /// Annual return on invested cash (as a decimal)
+    #[clap(long, default_value_t = 0.05)]
+    investment_return: f64,
+
+    /// The number of years to compare
+    #[clap(long, default_value_t = 6)]
+    years: u32,
+}
+
+#[derive(Parser, Debug)]
+struct Retirement {
+    /// Your current age
+    #[clap(long)]
+    current_age: u32,
+
+    /// The age at which you plan to retire
+    #[clap(long, default_value_t = 65)]
+    retirement_age: u32,
+
+    /// The age to plan withdrawals until
+    #[clap(long, default_value_t = 95)]
+    life_expectancy: u32,
+
+    /// Current retirement savings
+    #[clap(long, default_value_t = 0.0)]
+    savings: f64,
+
+    /// Your current monthly contribution
+    #[clap(long, default_value_t = 0.0)]
+    contribution: f64,
+
+    /// Annual increase in contributions (as a decimal)
+    #[clap(long, default_value_t = 0.02)]
+    raise: f64,
+
+    /// Employer match as a fraction of your contribution
+    #[clap(long, default_value_t = 0.0)]
+    employer_match: f64,
+
+    /// Annual return before retirement (as a decimal)
+    #[clap(long, default_value_t = 0.07)]
+    pre_return: f64,
+
+    /// Annual return during retirement (as a decimal)
+    #[clap(long, default_value_t = 0.05)]
+    post_return: f64,
+
+    /// Desired annual retirement income in today's money
+    #[clap(long)]
+    target_income: f64,
+
+    /// How withdrawals are set each year
+    #[clap(long, value_enum, default_value_t = WithdrawalMethod::FixedReal)]
+    withdrawal_rule: WithdrawalMethod,
+
+    /// Withdrawal rate for the fixed-percentage and guardrails rules (as a decimal)
+    #[clap(long, default_value_t = 0.04)]
+    withdrawal_rate: f64,
+
+    /// How far the withdrawal rate may drift before guardrails adjust spending (as a decimal)
+    #[clap(long, default_value_t = 0.20)]
+    guardrail_band: f64,
+
+    /// How much guardrails cut or raise spending (as a decimal)
+    #[clap(long, default_value_t = 0.10)]
+    guardrail_adjustment: f64,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum WithdrawalMethod {
+    FixedPercentage,
+    FixedReal,
+    Guardrails,
+}
+
+impl Retirement {
+    /// Build the withdrawal rule selected on the command line
+    fn withdrawal_rule(&self) -> WithdrawalRule {
+        match self.withdrawal_rule {
+            WithdrawalMethod::FixedPercentage => WithdrawalRule::FixedPercentage(self.withdrawal_rate),
+            WithdrawalMethod::FixedReal => WithdrawalRule::FixedReal(self.target_income),
+            WithdrawalMethod::Guardrails => WithdrawalRule::Guardrails {
+                initial_rate: self.withdrawal_rate,
+                band: self.guardrail_band,
+                adjustment: self.guardrail_adjustment,
+            },
         }
-    };
-    
-    debug!("Command type: {}", match &command {
-        Command::Interest(_) => "Interest",
-        Command::CompoundInterest(_) => "CompoundInterest", 
-        Command::PresentValue(_) => "PresentValue",
-        Command::FutureValue(_) => "FutureValue",
-        Command::NPV(_) => "NPV",
-        Command::Amortization(_) => "Amortization",
-        Command::ROI(_) => "ROI",
-        Command::Average(_) => "Average",
-        Command::Mode(_) => "Mode",
-        Command::Medium(_) => "Median",
-        Command::PaybackPeriod(_) => "PaybackPeriod",
-        Command::BreakEven(_) => "BreakEven",
-        Command::Depreciation(_) => "Depreciation",
-        Command::IRR(_) => "IRR",
-        Command::Variance(_) => "Variance",
-        Command::StandardDeviation(_) => "StandardDeviation",
-        Command::Probability(_) => "Probability",
-        Command::CAPM(_) => "CAPM",
-        Command::LoanPayment(_) => "LoanPayment",
-        Command::BreakEvenUnits(_) => "BreakEvenUnits",
-        Command::DCF(_) => "DCF",
-        Command::Mortgage(_) => "Mortgage",
-        Command::WeightedAverage(_) => "WeightedAverage",
-        Command::WACC(_) => "WACC",
-        Command::DividendYield(_) => "DividendYield",
-        Command::ReturnOnEquity(_) => "ReturnOnEquity",
-        Command::Completion(_) => "Completion",
-    });
-    
-    // Execute the selected command
-    match command {
-        Command::Interest(interest) => {
-            debug!("Calculating simple interest");
-            
-            let result = calculate_simple_interest(interest.principal, interest.rate, interest.time)
-                .context("Failed to calculate simple interest")?;
-            
-            info!("Calculated simple interest: {:.4}", result);
+    }
+}
+
+#[derive(Parser, Debug)]
+struct TaxAccounts {
+    /// Pre-tax amount invested today
+    #[clap(long, default_value_t = 0.0)]
+    initial: f64,
+
+    /// Pre-tax amount invested at the end of each year
+    #[clap(long)]
+    contribution: f64,
+
+    /// The number of years to project
+    #[clap(long)]
+    years: u32,
+
+    /// Total annual return before tax (as a decimal)
+    #[clap(long = "return", default_value_t = 0.07)]
+    annual_return: f64,
+
+    /// Part of the return paid as dividends and interest each year (as a decimal)
+    #[clap(long, default_value_t = 0.02)]
+    dividend_yield: f64,
+
+    /// Income tax rate on contributions today (as a decimal)
+    #[clap(long, default_value_t = 0.24)]
+    current_tax_rate: f64,
+
+    /// Income tax rate on tax-deferred withdrawals (as a decimal)
+    #[clap(long, default_value_t = 0.22)]
+    retirement_tax_rate: f64,
+
+    /// Tax rate on dividends and interest in the taxable account (as a decimal)
+    #[clap(long, default_value_t = 0.15)]
+    dividend_tax_rate: f64,
+
+    /// Tax rate on capital gains in the taxable account (as a decimal)
+    #[clap(long, default_value_t = 0.15)]
+    capital_gains_rate: f64,
+}
+
+#[derive(Parser, Debug)]
+struct Tax {
+    /// Gross income before deductions
+    #[clap(long)]
+    income: f64,
+
+    /// TOML file with the bracket table
+    #[clap(long)]
+    brackets: PathBuf,
+
+    /// Filing status as named in the bracket table
+    #[clap(long, default_value = "single")]
+    filing_status: String,
+
+    /// Itemized deduction to use instead of the standard deduction
+    #[clap(long)]
+    deduction: Option<f64>,
+}
+
+#[derive(Parser, Debug)]
+struct Lots {
+    /// CSV file of trades with the header date,symbol,action,quantity,price,lot_id
+    #[clap(short, long)]
+    file: PathBuf,
+
+    /// How sold shares are matched against open lots
+    #[clap(short, long, value_enum, default_value_t = LotMatching::Fifo)]
+    method: LotMatching,
+
+    /// Current price for unrealized gains, as SYMBOL=PRICE (repeatable)
+    #[clap(long = "price", value_parser = parse_symbol_price)]
+    prices: Vec<(String, f64)>,
+
+    /// Date for classifying open lots as short- or long-term (YYYY-MM-DD); defaults to today
+    #[clap(long)]
+    as_of: Option<NaiveDate>,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum LotMatching {
+    Fifo,
+    Lifo,
+    Hifo,
+    Average,
+    SpecificId,
+}
+
+impl From<LotMatching> for LotMethod {
+    fn from(matching: LotMatching) -> Self {
+        match matching {
+            LotMatching::Fifo => LotMethod::Fifo,
+            LotMatching::Lifo => LotMethod::Lifo,
+            LotMatching::Hifo => LotMethod::Hifo,
+            LotMatching::Average => LotMethod::AverageCost,
+            LotMatching::SpecificId => LotMethod::SpecificId,
+        }
+    }
+}
+
+/// Parse a `SYMBOL=PRICE` pair
+fn parse_symbol_price(value: &str) -> std::result::Result<(String, f64), String> {
+    let (symbol, price) = value.split_once('=')
+        .ok_or_else(|| format!("expected SYMBOL=PRICE: {}", value))?;
+    let price = price.trim().parse::<f64>()
+        .map_err(|_| format!("invalid price for {}: {}", symbol, price))?;
+    Ok((symbol.trim().to_string(), price))
+}
+
+#[derive(Parser, Debug)]
+struct Scenario {
+    /// TOML or YAML file with a base case and named scenarios
+    #[clap(short, long)]
+    file: PathBuf,
+
+    /// Calculation to evaluate; defaults to the file's `calculation` entry
+    #[clap(short, long, value_enum)]
+    calculation: Option<ScenarioCalculation>,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum ScenarioCalculation {
+    Npv,
+    LoanPayment,
+    Dcf,
+    Capm,
+    Wacc,
+}
+
+#[derive(Parser, Debug)]
+struct Valuation {
+    /// TOML file with the revenue, margin, capex, working capital, discount and bridge assumptions
+    #[clap(short, long)]
+    file: PathBuf,
+}
+
+#[derive(Parser, Debug)]
+struct DDM {
+    /// The annual dividend per share just paid
+    #[clap(short, long)]
+    dividend: f64,
+
+    /// Required return on equity; alternatively give the CAPM inputs
+    #[clap(long, required_unless_present = "beta", conflicts_with_all = ["risk_free_rate", "beta", "market_return"])]
+    required_return: Option<f64>,
+
+    /// Risk-free rate for a CAPM required return
+    #[clap(long, requires_all = ["beta", "market_return"])]
+    risk_free_rate: Option<f64>,
+
+    /// Beta for a CAPM required return
+    #[clap(long, requires_all = ["risk_free_rate", "market_return"])]
+    beta: Option<f64>,
+
+    /// Expected market return for a CAPM required return
+    #[clap(long, requires_all = ["risk_free_rate", "beta"])]
+    market_return: Option<f64>,
+
+    /// Dividend discount model to apply
+    #[clap(short, long, value_enum, default_value_t = DividendModelKind::Gordon)]
+    model: DividendModelKind,
+
+    /// Perpetual growth rate: terminal growth for two-stage, long-term growth for the H-model
+    #[clap(short, long)]
+    growth: f64,
+
+    /// Early growth: one rate or per-year rates (comma-separated) for two-stage, the starting rate for the H-model
+    #[clap(long, value_delimiter = ',')]
+    high_growth: Vec<f64>,
+
+    /// Years of high growth (two-stage) or years for growth to fade to the long-term rate (H-model)
+    #[clap(long)]
+    high_growth_years: Option<u32>,
+
+    /// Current share price, to back out the market-implied growth rate
+    #[clap(short, long)]
+    price: Option<f64>,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum DividendModelKind {
+    Gordon,
+    TwoStage,
+    HModel,
+}
+
+impl DDM {
+    /// The given required return, or the CAPM expected return
+    fn required_return(&self) -> Result<f64> {
+        match (self.required_return, self.risk_free_rate, self.beta, self.market_return) {
+            (Some(rate), _, _, _) => Ok(rate),
+            (None, Some(risk_free_rate), Some(beta), Some(market_return)) => {
+                calculate_capm(risk_free_rate, beta, market_return).context("Failed to calculate CAPM required return")
+            }
+            _ => anyhow::bail!("--required-return or --risk-free-rate, --beta and --market-return are required"),
+        }
+    }
+    
+    /// The selected model with its growth assumptions
+    fn dividend_model(&self) -> Result<DividendModel> {
+        match self.model {
+            DividendModelKind::Gordon => Ok(DividendModel::Gordon { growth: self.growth }),
+            DividendModelKind::TwoStage => {
+                let high_growth = match (self.high_growth.as_slice(), self.high_growth_years) {
+                    ([], _) => anyhow::bail!("The two-stage model needs --high-growth"),
+                    ([rate], Some(years)) => vec![*rate; years as usize],
+                    ([_], None) => anyhow::bail!("--high-growth-years is required with a single --high-growth rate"),
+                    (rates, Some(years)) if rates.len() != years as usize => {
+                        anyhow::bail!("{} high-growth rates given for {} years", rates.len(), years)
+                    }
+                    (rates, _) => rates.to_vec(),
+                };
+                Ok(DividendModel::MultiStage { high_growth, terminal_growth: self.growth })
+            }
+            DividendModelKind::HModel => match (self.high_growth.as_slice(), self.high_growth_years) {
+                ([rate], Some(years)) => Ok(DividendModel::HModel {
+                    short_term_growth: *rate,
+                    long_term_growth: self.growth,
+                    half_life: years as f64 / 2.0,
+                }),
+                _ => anyhow::bail!("The H-model needs one --high-growth rate and --high-growth-years"),
+            },
+        }
+    }
+}
+
+#[derive(Parser, Debug)]
+struct CompareProjects {
+    /// Project as name=outlay,flow,... with the outlay negative, e.g. plant=-5000,1500,2000,2500 (repeatable)
+    #[clap(short, long = "project", required = true)]
+    projects: Vec<Project>,
+
+    /// Discount rate for NPV, profitability index, discounted payback and EAA (as a decimal)
+    #[clap(short, long)]
+    discount_rate: f64,
+
+    /// Rate paid to fund outflows for MIRR; defaults to the discount rate
+    #[clap(long)]
+    finance_rate: Option<f64>,
+
+    /// Rate earned on reinvested inflows for MIRR; defaults to the discount rate
+    #[clap(long)]
+    reinvestment_rate: Option<f64>,
+}
+
+#[derive(Parser, Debug)]
+struct RealOptionAnalysis {
+    /// The initial cost of the project
+    #[clap(short, long)]
+    initial_investment: f64,
+
+    /// The project's future cash flows, comma-separated
+    #[clap(short, long, value_delimiter = ',', required = true)]
+    cash_flows: Vec<f64>,
+
+    /// Discount rate for the cash flows (as a decimal)
+    #[clap(short, long)]
+    discount_rate: f64,
+
+    /// Annual volatility of project value (as a decimal)
+    #[clap(long)]
+    volatility: f64,
+
+    /// Annual risk-free rate (as a decimal)
+    #[clap(short, long)]
+    risk_free_rate: f64,
+
+    /// Years until the option expires
+    #[clap(short, long)]
+    years: f64,
+
+    /// Number of binomial steps to expiry
+    #[clap(short, long, default_value_t = 5)]
+    steps: usize,
+
+    /// Flexibility to value
+    #[clap(short, long, value_enum)]
+    option: RealOptionKind,
+
+    /// Scale change as a decimal: 0.3 expands by 30%, 0.25 contracts by 25%
+    #[clap(long, required_if_eq_any = [("option", "expand"), ("option", "contract")])]
+    factor: Option<f64>,
+
+    /// Extra investment needed to expand
+    #[clap(long, required_if_eq("option", "expand"))]
+    cost: Option<f64>,
+
+    /// Cost saved by contracting
+    #[clap(long, required_if_eq("option", "contract"))]
+    savings: Option<f64>,
+
+    /// Amount recovered by abandoning
+    #[clap(long, required_if_eq("option", "abandon"))]
+    salvage: Option<f64>,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum RealOptionKind {
+    Expand,
+    Contract,
+    Abandon,
+    Defer,
+}
+
+impl RealOptionAnalysis {
+    /// The selected option with its terms
+    fn real_option(&self) -> Result<RealOption> {
+        match (self.option, self.factor, self.cost, self.savings, self.salvage) {
+            (RealOptionKind::Expand, Some(factor), Some(cost), _, _) => Ok(RealOption::Expand { factor, cost }),
+            (RealOptionKind::Contract, Some(factor), _, Some(savings), _) => Ok(RealOption::Contract { factor, savings }),
+            (RealOptionKind::Abandon, _, _, _, Some(salvage)) => Ok(RealOption::Abandon { salvage }),
+            (RealOptionKind::Defer, _, _, _, _) => Ok(RealOption::Defer),
+            _ => anyhow::bail!("Expand needs --factor and --cost, contract --factor and --savings, abandon --salvage"),
+        }
+    }
+}
+
+#[derive(Parser, Debug)]
+struct CVP {
+    /// Total fixed costs
+    #[clap(short, long)]
+    fixed_costs: f64,
+
+    /// Selling price per unit of a single product
+    #[clap(short, long, required_unless_present = "products", requires = "variable_cost")]
+    price: Option<f64>,
+
+    /// Variable cost per unit of a single product
+    #[clap(short = 'c', long, requires = "price")]
+    variable_cost: Option<f64>,
+
+    /// Product in a sales mix as name=price:variable_cost:mix, e.g. standard=20:12:3 (repeatable)
+    #[clap(long = "product", conflicts_with_all = ["price", "variable_cost"])]
+    products: Vec<Product>,
+
+    /// Budgeted or actual units sold, for margin of safety and operating leverage
+    #[clap(short, long)]
+    sales: Option<f64>,
+
+    /// Operating profit before tax to find the volume for
+    #[clap(long)]
+    target_profit: Option<f64>,
+
+    /// Net income after tax to find the volume for
+    #[clap(long, requires = "tax_rate")]
+    target_net_income: Option<f64>,
+
+    /// Income tax rate for --target-net-income (as a decimal)
+    #[clap(long)]
+    tax_rate: Option<f64>,
+
+    /// Volumes for the profit-volume table as min:max:step, e.g. 0:2000:250
+    #[clap(long)]
+    volumes: Option<VolumeRange>,
+}
+
+#[derive(Parser, Debug)]
+struct WorkingCapital {
+    /// CSV with columns period,days,revenue,cost_of_goods_sold,inventory,receivables,payables
+    #[clap(short, long, conflicts_with_all = ["revenue", "cost_of_goods_sold", "inventory", "receivables", "payables"])]
+    file: Option<PathBuf>,
+
+    /// Revenue for a single period
+    #[clap(short, long, required_unless_present = "file")]
+    revenue: Option<f64>,
+
+    /// Cost of goods sold for a single period
+    #[clap(short, long, required_unless_present = "file")]
+    cost_of_goods_sold: Option<f64>,
+
+    /// Inventory balance for a single period
+    #[clap(short, long, required_unless_present = "file")]
+    inventory: Option<f64>,
+
+    /// Accounts receivable balance for a single period
+    #[clap(long, required_unless_present = "file")]
+    receivables: Option<f64>,
+
+    /// Accounts payable balance for a single period
+    #[clap(short, long, required_unless_present = "file")]
+    payables: Option<f64>,
+
+    /// Days the single period covers
+    #[clap(short, long, default_value_t = DAYS_IN_YEAR)]
+    days: f64,
+
+    /// Revenue growth rate to size the working-capital financing need (as a decimal)
+    #[clap(short, long)]
+    growth: Option<f64>,
+}
+
+#[derive(Parser, Debug)]
+struct Model {
+    /// TOML file with the base-year statements, operating drivers and financing terms
+    #[clap(short, long)]
+    file: PathBuf,
+}
+
+#[derive(Parser, Debug)]
+struct Lbo {
+    /// TOML file with entry and exit terms, projected EBITDA and the debt tranches
+    #[clap(short, long)]
+    file: PathBuf,
+
+    /// Entry multiples for the IRR grid (comma-separated); defaults to the deal's multiple ±1x
+    #[clap(long, value_delimiter = ',')]
+    entry_multiples: Vec<f64>,
+
+    /// Exit multiples for the IRR grid (comma-separated); defaults to the deal's multiple ±1x
+    #[clap(long, value_delimiter = ',')]
+    exit_multiples: Vec<f64>,
+}
+
+#[derive(Parser, Debug)]
+struct Covenants {
+    /// CSV with columns period,cash_flow,value (value is optional and needed for LTV)
+    #[clap(short, long)]
+    file: PathBuf,
+
+    /// The initial loan amount
+    #[clap(short = 'a', long)]
+    loan_amount: f64,
+
+    /// The annual interest rate
+    #[clap(short = 'i', long)]
+    annual_interest_rate: f64,
+
+    /// The loan term in years
+    #[clap(short = 't', long)]
+    loan_term_years: i32,
+
+    /// Due date of the first payment (YYYY-MM-DD); defaults to the first of next month
+    #[clap(long)]
+    first_payment_date: Option<NaiveDate>,
+
+    /// Months covered by each row of the projection (12 for annual, 3 for quarterly)
+    #[clap(long, default_value_t = 12)]
+    period_months: u32,
+
+    /// Minimum debt service coverage ratio [default: 1.25]
+    #[clap(long)]
+    min_dscr: Option<f64>,
+
+    /// Minimum interest coverage ratio [default: 2.0]
+    #[clap(long)]
+    min_interest_coverage: Option<f64>,
+
+    /// Minimum debt yield as a decimal [default: 0.08]
+    #[clap(long)]
+    min_debt_yield: Option<f64>,
+
+    /// Maximum loan-to-value as a decimal [default: 0.75]
+    #[clap(long)]
+    max_ltv: Option<f64>,
+
+    /// DSCR used to size the maximum loan; defaults to the DSCR covenant
+    #[clap(long)]
+    target_dscr: Option<f64>,
+}
+
+#[derive(Parser, Debug)]
+struct Property {
+    /// The purchase price of the property
+    #[clap(long)]
+    price: f64,
+
+    /// Closing costs as a fraction of the price
+    #[clap(long, default_value_t = 0.02)]
+    closing_costs: f64,
+
+    /// Gross monthly rent in the first year
+    #[clap(long)]
+    rent: f64,
+
+    /// Annual rent increase (as a decimal)
+    #[clap(long, default_value_t = 0.03)]
+    rent_growth: f64,
+
+    /// Share of gross rent lost to vacancy and collections
+    #[clap(long, default_value_t = 0.05)]
+    vacancy: f64,
+
+    /// Annual operating expenses in the first year (taxes, insurance, management, repairs)
+    #[clap(long)]
+    operating_expenses: f64,
+
+    /// Annual increase in operating expenses and capex reserves (as a decimal)
+    #[clap(long, default_value_t = 0.03)]
+    expense_growth: f64,
+
+    /// Annual capital expenditure reserve in the first year
+    #[clap(long, default_value_t = 0.0)]
+    capex_reserve: f64,
+
+    /// The down payment as a fraction of the price (1 for an all-cash purchase)
+    #[clap(long, default_value_t = 0.25)]
+    down_payment: f64,
+
+    /// The annual mortgage rate (as a percentage)
+    #[clap(long)]
+    mortgage_rate: Option<f64>,
+
+    /// The mortgage term in years
+    #[clap(long, default_value_t = 30)]
+    term: i32,
+
+    /// Annual property value appreciation (as a decimal)
+    #[clap(long, default_value_t = 0.03)]
+    appreciation: f64,
+
+    /// Selling costs as a fraction of the sale price
+    #[clap(long, default_value_t = 0.06)]
+    selling_costs: f64,
+
+    /// The hold period in years
+    #[clap(long, default_value_t = 10)]
+    years: u32,
+}
+
+#[derive(Parser, Debug)]
+struct Fx {
+    /// Spot rates and zero curves: a .json file, or CSV with columns kind,currency,quote,tenor_years,rate
+    #[clap(short, long)]
+    rates: PathBuf,
+
+    /// Currency to convert from
+    #[clap(long, required_unless_present = "cash_flows")]
+    from: Option<Currency>,
+
+    /// Currency to convert into, and the base currency for cash flows
+    #[clap(long)]
+    to: Currency,
+
+    /// Amount to convert
+    #[clap(short, long, default_value_t = 1.0)]
+    amount: f64,
+
+    /// Forward tenors in years (comma-separated, e.g. 0.25,0.5,1)
+    #[clap(long, value_delimiter = ',', requires = "from")]
+    tenors: Vec<f64>,
+
+    /// CSV of dated cash flows with columns date,currency,amount
+    #[clap(short, long)]
+    cash_flows: Option<PathBuf>,
+
+    /// Date of the spot rates (YYYY-MM-DD); defaults to today
+    #[clap(long)]
+    valuation_date: Option<NaiveDate>,
+
+    /// Convert cash flows at spot instead of each flow's forward rate
+    #[clap(long)]
+    at_spot: bool,
+
+    /// Discount rate for the converted cash flows (as a decimal)
+    #[clap(short, long, requires = "cash_flows")]
+    discount_rate: Option<f64>,
+
+    /// Initial investment in the base currency, for NPV
+    #[clap(short, long, requires = "discount_rate")]
+    initial_investment: Option<f64>,
+}
+
+#[derive(Parser, Debug)]
+struct Completion {
+    /// Shell to generate completions for
+    #[clap(value_enum)]
+    shell: Shell,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+#[allow(clippy::enum_variant_names)]
+enum Shell {
+    Bash,
+    Fish,
+    Zsh,
+    PowerShell,
+}
+
+/// A command whose numeric inputs can be changed by name and re-evaluated
+/// 
+/// Input names are the command's long flag names; underscores are accepted in
+/// place of hyphens.
+trait Evaluate: Clone {
+    /// Label for the calculated result
+    fn output_name(&self) -> &'static str;
+    
+    /// Format a result for display
+    fn format_output(&self, value: f64) -> String;
+    
+    /// Numeric inputs by name with their current values
+    fn inputs(&self) -> Vec<(&'static str, f64)>;
+    
+    /// Replace one numeric input by name
+    fn set_input(&mut self, name: &str, value: f64) -> Result<(), FinanceError>;
+    
+    /// Replace one list input by name
+    fn set_values(&mut self, name: &str, _values: &[f64]) -> Result<(), FinanceError> {
+        Err(unknown_input(self, name))
+    }
+    
+    /// Run the underlying library calculation
+    fn evaluate(&self) -> Result<f64, FinanceError>;
+    
+    /// Evaluate with one input replaced
+    fn evaluate_with(&self, name: &str, value: f64) -> Result<f64, FinanceError> {
+        let mut changed = self.clone();
+        changed.set_input(name, value)?;
+        changed.evaluate()
+    }
+}
+
+/// Normalize an input name to its flag form
+fn input_key(name: &str) -> String {
+    name.trim().replace('_', "-").to_lowercase()
+}
+
+/// Error for an input name a command does not have
+fn unknown_input<T: Evaluate>(command: &T, name: &str) -> FinanceError {
+    let known: Vec<&str> = command.inputs().iter().map(|(input, _)| *input).collect();
+    FinanceError::InvalidInput(format!("Unknown input '{}' (expected one of: {})", name, known.join(", ")))
+}
+
+impl Evaluate for NPV {
+    fn output_name(&self) -> &'static str {
+        "NPV"
+    }
+    
+    fn format_output(&self, value: f64) -> String {
+        format_currency_plain(value)
+    }
+    
+    fn inputs(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("initial-investment", self.initial_investment),
+            ("cash-inflow", self.cash_inflow),
+            ("discount-rate", self.discount_rate),
+            ("lifespan", self.lifespan as f64),
+        ]
+    }
+    
+    fn set_input(&mut self, name: &str, value: f64) -> Result<(), FinanceError> {
+        match input_key(name).as_str() {
+            "initial-investment" => self.initial_investment = value,
+            "cash-inflow" => self.cash_inflow = value,
+            "discount-rate" => self.discount_rate = value,
+            "lifespan" => self.lifespan = value.round() as i32,
+            _ => return Err(unknown_input(self, name)),
+        }
+        Ok(())
+    }
+    
+    fn evaluate(&self) -> Result<f64, FinanceError> {
+        let cash_flows = vec![self.cash_inflow; self.lifespan.max(0) as usize];
+        calculate_npv(self.initial_investment, &cash_flows, self.discount_rate)
+    }
+}
+
+impl Evaluate for LoanPayment {
+    fn output_name(&self) -> &'static str {
+        "Monthly Payment"
+    }
+    
+    fn format_output(&self, value: f64) -> String {
+        format_currency_plain(value)
+    }
+    
+    fn inputs(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("principal", self.principal),
+            ("interest-rate", self.interest_rate),
+            ("loan-term", self.loan_term),
+        ]
+    }
+    
+    fn set_input(&mut self, name: &str, value: f64) -> Result<(), FinanceError> {
+        match input_key(name).as_str() {
+            "principal" => self.principal = value,
+            "interest-rate" => self.interest_rate = value,
+            "loan-term" => self.loan_term = value,
+            _ => return Err(unknown_input(self, name)),
+        }
+        Ok(())
+    }
+    
+    fn evaluate(&self) -> Result<f64, FinanceError> {
+        calculate_loan_payment(self.principal, self.interest_rate, self.loan_term)
+    }
+}
+
+impl Evaluate for DCF {
+    fn output_name(&self) -> &'static str {
+        "DCF Value"
+    }
+    
+    fn format_output(&self, value: f64) -> String {
+        format_currency_plain(value)
+    }
+    
+    fn inputs(&self) -> Vec<(&'static str, f64)> {
+        vec![("discount-rate", self.discount_rate)]
+    }
+    
+    fn set_input(&mut self, name: &str, value: f64) -> Result<(), FinanceError> {
+        match input_key(name).as_str() {
+            "discount-rate" => self.discount_rate = value,
+            _ => return Err(unknown_input(self, name)),
+        }
+        Ok(())
+    }
+    
+    fn set_values(&mut self, name: &str, values: &[f64]) -> Result<(), FinanceError> {
+        match input_key(name).as_str() {
+            "cash-flows" => self.cash_flows = values.to_vec(),
+            _ => return Err(unknown_input(self, name)),
+        }
+        Ok(())
+    }
+    
+    fn evaluate(&self) -> Result<f64, FinanceError> {
+        calculate_dcf(&self.cash_flows, self.discount_rate)
+    }
+}
+
+impl Evaluate for CAPM {
+    fn output_name(&self) -> &'static str {
+        "Expected Return"
+    }
+    
+    fn format_output(&self, value: f64) -> String {
+        format_rate_as_percentage(value)
+    }
+    
+    fn inputs(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("risk-free-rate", self.risk_free_rate),
+            ("beta", self.beta),
+            ("market-return", self.market_return),
+        ]
+    }
+    
+    fn set_input(&mut self, name: &str, value: f64) -> Result<(), FinanceError> {
+        match input_key(name).as_str() {
+            "risk-free-rate" => self.risk_free_rate = value,
+            "beta" => self.beta = value,
+            "market-return" => self.market_return = value,
+            _ => return Err(unknown_input(self, name)),
+        }
+        Ok(())
+    }
+    
+    fn evaluate(&self) -> Result<f64, FinanceError> {
+        calculate_capm(self.risk_free_rate, self.beta, self.market_return)
+    }
+}
+
+impl Evaluate for WACC {
+    fn output_name(&self) -> &'static str {
+        "WACC"
+    }
+    
+    fn format_output(&self, value: f64) -> String {
+        format_rate_as_percentage(value)
+    }
+    
+    fn inputs(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("cost-of-equity", self.cost_of_equity),
+            ("cost-of-debt", self.cost_of_debt),
+            ("tax-rate", self.tax_rate.unwrap_or_default()),
+            ("market-value-equity", self.market_value_equity),
+            ("market-value-debt", self.market_value_debt),
+        ]
+    }
+    
+    fn set_input(&mut self, name: &str, value: f64) -> Result<(), FinanceError> {
+        match input_key(name).as_str() {
+            "cost-of-equity" => self.cost_of_equity = value,
+            "cost-of-debt" => self.cost_of_debt = value,
+            "tax-rate" => {
+                self.tax_rate = Some(value);
+                self.tax_brackets = None;
+            }
+            "market-value-equity" => self.market_value_equity = value,
+            "market-value-debt" => self.market_value_debt = value,
+            _ => return Err(unknown_input(self, name)),
+        }
+        Ok(())
+    }
+    
+    fn evaluate(&self) -> Result<f64, FinanceError> {
+        // Bracket tables are resolved to a flat rate before evaluation
+        let tax_rate = self.tax_rate
+            .ok_or_else(|| FinanceError::InvalidInput("A tax rate is required".into()))?;
+        calculate_wacc(self.cost_of_equity, self.cost_of_debt, tax_rate, self.market_value_equity, self.market_value_debt)
+    }
+}
+
+impl Evaluate for BreakEvenUnits {
+    fn output_name(&self) -> &'static str {
+        "Break-Even Units"
+    }
+    
+    fn format_output(&self, value: f64) -> String {
+        format!("{:.2}", value)
+    }
+    
+    fn inputs(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("fixed-costs", self.fixed_costs),
+            ("variable-costs", self.variable_costs),
+            ("price-per-unit", self.price_per_unit),
+        ]
+    }
+    
+    fn set_input(&mut self, name: &str, value: f64) -> Result<(), FinanceError> {
+        match input_key(name).as_str() {
+            "fixed-costs" => self.fixed_costs = value,
+            "variable-costs" => self.variable_costs = value,
+            "price-per-unit" => self.price_per_unit = value,
+            _ => return Err(unknown_input(self, name)),
+        }
+        Ok(())
+    }
+    
+    fn evaluate(&self) -> Result<f64, FinanceError> {
+        calculate_break_even_units(self.fixed_costs, self.variable_costs, self.price_per_unit)
+    }
+}
+
+/// Run the application with proper error handling
+fn run() -> Result<()> {
+    // Parse command line arguments
+    let opts: Opts = Opts::parse();
+    
+    // Configure logging based on verbosity
+    env_logger::Builder::from_env(Env::default().default_filter_or("info"))
+        .filter_level(opts.verbose.log_level_filter())
+        .init();
+    
+    info!("Starting rusty-finance");
+    
+    let inflation = resolve_inflation(opts.inflation.as_deref())?;
+    set_display_currency(opts.currency);
+    
+    // Handle interactive mode or regular command mode
+    let command = if opts.interactive {
+        show_interactive_menu()?
+    } else {
+        match opts.command {
+            Some(cmd) => cmd,
+            None => {
+                eprintln!("Error: No command specified. Use --interactive for interactive mode or specify a command.");
+                eprintln!("Run 'rusty-finance --help' for usage information.");
+                return Ok(());
+            }
+        }
+    };
+    
+    debug!("Command type: {}", match &command {
+        Command::Interest(_) => "Interest",
+        Command::CompoundInterest(_) => "CompoundInterest", 
+        Command::PresentValue(_) => "PresentValue",
+        Command::FutureValue(_) => "FutureValue",
+        Command::NPV(_) => "NPV",
+        Command::Amortization(_) => "Amortization",
+        Command::ROI(_) => "ROI",
+        Command::Average(_) => "Average",
+        Command::Mode(_) => "Mode",
+        Command::Medium(_) => "Median",
+        Command::PaybackPeriod(_) => "PaybackPeriod",
+        Command::BreakEven(_) => "BreakEven",
+        Command::Depreciation(_) => "Depreciation",
+        Command::IRR(_) => "IRR",
+        Command::Variance(_) => "Variance",
+        Command::StandardDeviation(_) => "StandardDeviation",
+        Command::Probability(_) => "Probability",
+        Command::CAPM(_) => "CAPM",
+        Command::LoanPayment(_) => "LoanPayment",
+        Command::BreakEvenUnits(_) => "BreakEvenUnits",
+        Command::DCF(_) => "DCF",
+        Command::Mortgage(_) => "Mortgage",
+        Command::WeightedAverage(_) => "WeightedAverage",
+        Command::WACC(_) => "WACC",
+        Command::DividendYield(_) => "DividendYield",
+        Command::ReturnOnEquity(_) => "ReturnOnEquity",
+        Command::Refinance(_) => "Refinance",
+        Command::DebtPlan(_) => "DebtPlan",
+        Command::RentVsBuy(_) => "RentVsBuy",
+        Command::LeaseVsBuy(_) => "LeaseVsBuy",
+        Command::Retirement(_) => "Retirement",
+        Command::TaxAccounts(_) => "TaxAccounts",
+        Command::Tax(_) => "Tax",
+        Command::Lots(_) => "Lots",
+        Command::Scenario(_) => "Scenario",
+        Command::Valuation(_) => "Valuation",
+        Command::DDM(_) => "DDM",
+        Command::CompareProjects(_) => "CompareProjects",
+        Command::RealOption(_) => "RealOption",
+        Command::CVP(_) => "CVP",
+        Command::WorkingCapital(_) => "WorkingCapital",
+        Command::Model(_) => "Model",
+        Command::Lbo(_) => "LBO",
+        Command::Covenants(_) => "Covenants",
+        Command::Property(_) => "Property",
+        Command::Fx(_) => "FX",
+        Command::Completion(_) => "Completion",
+    });
+    
+    if inflation.is_some() && !supports_inflation(&command) {
+        anyhow::bail!("--inflation is only supported by compound-interest, future-value, npv, amortization and retirement");
+    }
+    
+    // Execute the selected command
+    match command {
+        Command::Interest(interest) => {
+            debug!("Calculating simple interest");
+            
+            let result = calculate_simple_interest(interest.principal, interest.rate, interest.time)
+                .context("Failed to calculate simple interest")?;
+            
+            info!("Calculated simple interest: {:.4}", result);
+            
+            // Create table using dynamic helper
+            let mut table = create_table(vec!["Principal", "Rate", "Time", "Simple Interest"]);
+            
+            // Add row with dynamic alignment - no manual padding needed
+            add_row(&mut table, &[
+                (&format_currency_plain(interest.principal), CellAlignment::Right),
+                (&format_rate_as_percentage(interest.rate), CellAlignment::Right),
+                (&format_years(interest.time), CellAlignment::Right),
+                (&format_currency_plain(result), CellAlignment::Right),
+            ]);
+            
+            println!("{table}");
+            info!("Simple interest calculation completed");
+            Ok(())
+        }
+        Command::CompoundInterest(ci) => {
+            debug!("Calculating compound interest with: {:?}", ci);
+            
+            // Create table using dynamic helper
+            let mut headers = vec!["Year", "Amount"];
+            if inflation.is_some() {
+                headers.push("Amount (Today's Money)");
+            }
+            let mut table = create_table(headers);
+            
+            // Calculate compound interest for each year
+            // Interactive mode already converts percentage to decimal, CLI mode needs conversion
+            let rate = if ci.rate > 1.0 { ci.rate / 100.0 } else { ci.rate };
+            for year in 1..=ci.t {
+                let amount = calculate_compound_interest(ci.principal, rate, ci.n, year)
+                    .context("Failed to calculate compound interest")?;
+                
+                // Add row with dynamic alignment - no manual padding needed
+                let year_label = format!("{}", year);
+                let nominal = format_currency_plain(amount);
+                let mut row = vec![(year_label.as_str(), CellAlignment::Center), (nominal.as_str(), CellAlignment::Right)];
+                let real = inflation.map(|i| format_currency_plain(i.to_real(amount, year as f64)));
+                if let Some(real) = &real {
+                    row.push((real.as_str(), CellAlignment::Right));
+                }
+                add_row(&mut table, &row);
+            }
+            
+            println!("{table}");
+            
+            if let Some(inflation) = &inflation {
+                let effective_rate = (1.0 + rate / ci.n as f64).powi(ci.n) - 1.0;
+                print_real_rate("Real Annual Return", effective_rate, inflation);
+            }
+            
+            info!("Compound interest calculation completed");
+            Ok(())
+        }
+        Command::PresentValue(pv) => {
+            debug!("Calculating present value with: {:?}", pv);
+            
+            let result = calculate_present_value(pv.future_value, pv.rate, pv.time)
+                .context("Failed to calculate present value")?;
+            
+            info!("Calculated present value: {:.4}", result);
+            
+            let mut table = create_table(vec!["Future Value", "Rate", "Time", "Present Value"]);
+            
+            add_row(&mut table, &[
+                (&format_currency_plain(pv.future_value), CellAlignment::Right),
+                (&format_rate_as_percentage(pv.rate), CellAlignment::Right),
+                (&format_years(pv.time), CellAlignment::Right),
+                (&format_currency_plain(result), CellAlignment::Right),
+            ]);
+            
+            println!("{table}");
+            info!("Present value calculation completed");
+            Ok(())
+        }
+        Command::FutureValue(fv) => {
+            debug!("Calculating future value with: {:?}", fv);
+            
+            let result = calculate_future_value(fv.present_value, fv.rate, fv.time)
+                .context("Failed to calculate future value")?;
+            
+            info!("Calculated future value: {:.4}", result);
+            
+            let mut headers = vec!["Present Value", "Rate", "Time", "Future Value"];
+            if inflation.is_some() {
+                headers.push("Future Value (Today's Money)");
+            }
+            let mut table = create_table(headers);
+            
+            let present_value = format_currency_plain(fv.present_value);
+            let rate = format_rate_as_percentage(fv.rate);
+            let time = format_years(fv.time);
+            let future_value = format_currency_plain(result);
+            let mut row = vec![
+                (present_value.as_str(), CellAlignment::Right),
+                (rate.as_str(), CellAlignment::Right),
+                (time.as_str(), CellAlignment::Right),
+                (future_value.as_str(), CellAlignment::Right),
+            ];
+            let real = inflation.map(|i| format_currency_plain(i.to_real(result, fv.time)));
+            if let Some(real) = &real {
+                row.push((real.as_str(), CellAlignment::Right));
+            }
+            add_row(&mut table, &row);
+            
+            println!("{table}");
+            
+            if let Some(inflation) = &inflation {
+                print_real_rate("Real Rate", fv.rate, inflation);
+            }
+            info!("Future value calculation completed");
+            Ok(())
+        }
+        Command::NPV(npv) => {
+            debug!("Calculating NPV with: {:?}", npv);
+            
+            if !npv.sensitivity.is_empty() {
+                return print_sensitivity(&npv, &npv.sensitivity);
+            }
+            
+            if let (Some(input), Some(target)) = (&npv.solve_for, npv.target) {
+                return print_goal_seek(&npv, input, target);
+            }
+            
+            // Create cash flows vector from the NPV inputs
+            let cash_flows: Vec<f64> = (0..npv.lifespan).map(|_| npv.cash_inflow).collect();
+            
+            let npv_value = calculate_npv(npv.initial_investment, &cash_flows, npv.discount_rate)
+                .context("Failed to calculate NPV")?;
+            
+            // Create and format table
+            let mut headers = vec!["Year", "Cash Inflow", "Discounted Cash Flow"];
+            if inflation.is_some() {
+                headers.insert(2, "Cash Inflow (Today's Money)");
+            }
+            let mut table = create_table(headers);
+            
+            // Calculate and display each year's discounted cash flow
+            for year in 1..=npv.lifespan {
+                let discounted_cash_flow = npv.cash_inflow / (1.0 + npv.discount_rate).powf(year as f64);
+                
+                let year_label = format!("{}", year);
+                let cash_inflow = format_currency_plain(npv.cash_inflow);
+                let discounted = format_currency_plain(discounted_cash_flow);
+                let real = inflation.map(|i| format_currency_plain(i.to_real(npv.cash_inflow, year as f64)));
+                let mut row = vec![(year_label.as_str(), CellAlignment::Center), (cash_inflow.as_str(), CellAlignment::Right)];
+                if let Some(real) = &real {
+                    row.push((real.as_str(), CellAlignment::Right));
+                }
+                row.push((discounted.as_str(), CellAlignment::Right));
+                add_row(&mut table, &row);
+            }
+            
+            println!("{table}");
+            
+            if let Some(inflation) = &inflation {
+                // Nominal flows at the nominal rate equal real flows at the real rate, so NPV is unchanged
+                print_real_rate("Real Discount Rate", npv.discount_rate, inflation);
+            }
+            
+            // Print the net present value
+            println!("\n{}: {}", 
+                "Net Present Value (NPV)".bold(), 
+                format_currency(npv_value)
+            );
+            
+            info!("NPV calculation completed. NPV: {:.2}", npv_value);
+            Ok(())
+        }
+        Command::ROI(roi) => {
+            debug!("Calculating ROI with: {:?}", roi);
+            
+            let roi_value = calculate_roi(roi.net_profit, roi.cost_of_investment)
+                .context("Failed to calculate ROI")?;
+            
+            info!("Calculated ROI: {:.4}%", roi_value);
+            
+            let mut table = create_table(vec!["Net Profit", "Cost of Investment", "ROI"]);
+            
+            add_row(&mut table, &[
+                (&format_currency_plain(roi.net_profit), CellAlignment::Right),
+                (&format_currency_plain(roi.cost_of_investment), CellAlignment::Right),
+                (&format_percentage_plain(roi_value / 100.0, 2), CellAlignment::Right),
+            ]);
+            
+            println!("{table}");
+            info!("ROI calculation completed");
+            Ok(())
+        }
+        Command::Average(average) => {
+            debug!("Calculating average with: {:?}", average);
             
-            // Create table using dynamic helper
-            let mut table = create_table(vec!["Principal", "Rate", "Time", "Simple Interest"]);
+            let avg = calculate_mean(&average.numbers)
+                .context("Failed to calculate average")?;
+            
+            let mut table = create_table(vec!["Number"]);
+            
+            for number in &average.numbers {
+                add_row(&mut table, &[
+                    (&format!("{:.2}", number), CellAlignment::Right),
+                ]);
+            }
             
-            // Add row with dynamic alignment - no manual padding needed
             add_row(&mut table, &[
-                (&format_currency_plain(interest.principal), CellAlignment::Right),
-                (&format_rate_as_percentage(interest.rate), CellAlignment::Right),
-                (&format_years(interest.time), CellAlignment::Right),
-                (&format_currency_plain(result), CellAlignment::Right),
+                (&format!("Average: {:.2}", avg), CellAlignment::Right),
+            ]);
+            
+            println!("{table}");
+            info!("Average calculation completed: {:.4}", avg);
+            Ok(())
+        }
+        Command::Mode(mode) => {
+            debug!("Calculating mode with: {:?}", mode);
+            
+            let mode_value = calculate_mode(&mode.numbers)
+                .context("Failed to calculate mode")?;
+            
+            let mut table = create_table(vec!["Number", "Mode"]);
+            
+            for number in &mode.numbers {
+                add_row(&mut table, &[
+                    (&format!("{:.2}", number), CellAlignment::Right),
+                    ("", CellAlignment::Left),
+                ]);
+            }
+            
+            match mode_value {
+                Some(m) => add_row(&mut table, &[
+                    ("Mode:", CellAlignment::Left),
+                    (&format!("{:.2}", m), CellAlignment::Right),
+                ]),
+                None => add_row(&mut table, &[
+                    ("Mode:", CellAlignment::Left),
+                    ("No mode", CellAlignment::Left),
+                ]),
+            };
+            
+            println!("{table}");
+            info!("Mode calculation completed");
+            Ok(())
+        }
+        Command::Medium(medium) => {
+            debug!("Calculating median with: {:?}", medium);
+            
+            let median = calculate_median(&medium.numbers)
+                .context("Failed to calculate median")?;
+            
+            let mut table = create_table(vec!["Number"]);
+            
+            // Sort for display
+            let mut sorted = medium.numbers.clone();
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+            
+            for number in &sorted {
+                add_row(&mut table, &[
+                    (&format!("{:.2}", number), CellAlignment::Right),
+                ]);
+            }
+            
+            add_row(&mut table, &[
+                (&format!("Median: {:.2}", median), CellAlignment::Right),
+            ]);
+            
+            println!("{table}");
+            info!("Median calculation completed: {:.4}", median);
+            Ok(())
+        }
+        Command::PaybackPeriod(payback) => {
+            debug!("Calculating payback period with: {:?}", payback);
+            
+            let payback_period = calculate_payback_period(payback.initial_cost, &payback.cash_flows)
+                .context("Failed to calculate payback period")?;
+            
+            let mut table = create_table(vec!["Cash Flows", "Initial Cost", "Payback Period"]);
+            
+            let payback_str = match payback_period {
+                Some(period) => format!("{:.2} years", period),
+                None => "Never pays back".to_string(),
+            };
+            
+            add_row(&mut table, &[
+                (&format!("{:?}", payback.cash_flows), CellAlignment::Left),
+                (&format_currency_plain(payback.initial_cost), CellAlignment::Right),
+                (&payback_str, CellAlignment::Right),
+            ]);
+            
+            println!("{table}");
+            info!("Payback period calculation completed");
+            Ok(())
+        }
+        Command::BreakEven(break_even) => {
+            debug!("Calculating break-even analysis with: {:?}", break_even);
+            
+            let (break_even_units, break_even_revenue) = calculate_break_even_analysis(
+                break_even.fixed_costs,
+                break_even.variable_costs,
+                break_even.price_per_unit
+            ).context("Failed to calculate break-even analysis")?;
+            
+            let summary_items = vec![
+                ("Break-Even Point (units)", format!("{:.0}", break_even_units)),
+                ("Total Revenue Required", format_currency(break_even_revenue)),
+            ];
+            
+            let table = create_summary_table("Metric", summary_items);
+            println!("{table}");
+            
+            info!("Break-even analysis completed");
+            Ok(())
+        }
+        Command::BreakEvenUnits(units) => {
+            debug!("Calculating break-even units with: {:?}", units);
+            
+            if let (Some(input), Some(target)) = (&units.solve_for, units.target) {
+                return print_goal_seek(&units, input, target);
+            }
+            
+            let break_even_units = units.evaluate().context("Failed to calculate break-even units")?;
+            
+            let summary_items = vec![
+                ("Fixed Costs", format_currency(units.fixed_costs)),
+                ("Contribution Margin per Unit", format_currency(units.price_per_unit - units.variable_costs)),
+                ("Break-Even Point (units)", format!("{:.2}", break_even_units)),
+            ];
+            
+            let table = create_summary_table("Metric", summary_items);
+            println!("{table}");
+            
+            info!("Break-even units calculation completed");
+            Ok(())
+        }
+        Command::LoanPayment(loan) => {
+            debug!("Calculating loan payment with: {:?}", loan);
+            
+            if !loan.sensitivity.is_empty() {
+                return print_sensitivity(&loan, &loan.sensitivity);
+            }
+            
+            if let (Some(input), Some(target)) = (&loan.solve_for, loan.target) {
+                return print_goal_seek(&loan, input, target);
+            }
+            
+            let monthly_payment = calculate_loan_payment(loan.principal, loan.interest_rate, loan.loan_term)
+                .context("Failed to calculate loan payment")?;
+            
+            let total_payment = monthly_payment * loan.loan_term * 12.0;
+            let total_interest = total_payment - loan.principal;
+            
+            // The payoff date is the due date of the final payment
+            let first_payment_date = loan.first_payment_date.unwrap_or_else(default_first_payment_date);
+            let total_payments = (loan.loan_term * 12.0).round().max(1.0) as u32;
+            let payoff_date = calculate_payment_date(first_payment_date, total_payments - 1)
+                .context("Failed to calculate payoff date")?;
+            
+            let summary_items = vec![
+                ("Principal", format_currency(loan.principal)),
+                ("Annual Interest Rate", format_rate_as_percentage(loan.interest_rate / 100.0)),
+                ("Loan Term", format_years(loan.loan_term)),
+                ("Monthly Payment", format_currency(monthly_payment)),
+                ("Total Interest", format_currency(total_interest)),
+                ("Payoff Date", payoff_date.format("%Y-%m-%d").to_string()),
+            ];
+            
+            let table = create_summary_table("Component", summary_items);
+            println!("{table}");
+            
+            info!("Loan payment calculation completed. Monthly payment: {:.2}", monthly_payment);
+            Ok(())
+        }
+        Command::Amortization(amortization) => {
+            debug!("Calculating amortization schedule with: {:?}", amortization);
+            
+            let schedule = generate_structured_schedule(
+                amortization.loan_amount,
+                amortization.annual_interest_rate,
+                amortization.loan_term_years,
+                &amortization.structure(),
+                amortization.first_payment_date.unwrap_or_else(default_first_payment_date)
+            ).context("Failed to generate amortization schedule")?;
+            let real_schedule = inflation.map(|i| real_amortization_schedule(&schedule, &i));
+            
+            if amortization.detailed {
+                let mut headers = vec![
+                    "Payment", "Date", "Principal", "Interest",
+                    "Cumulative Principal", "Cumulative Interest", "Remaining Balance",
+                ];
+                if real_schedule.is_some() {
+                    headers.extend(["Interest (Today's Money)", "Balance (Today's Money)"]);
+                }
+                let mut table = create_table(headers);
+                
+                for (index, payment) in schedule.iter().enumerate() {
+                    let mut cells = vec![
+                        (format!("{}", payment.month), CellAlignment::Center),
+                        (payment.payment_date.format("%Y-%m-%d").to_string(), CellAlignment::Center),
+                        (format_currency_plain(payment.principal_payment), CellAlignment::Right),
+                        (format_currency_plain(payment.interest_payment), CellAlignment::Right),
+                        (format_currency_plain(payment.cumulative_principal), CellAlignment::Right),
+                        (format_currency_plain(payment.cumulative_interest), CellAlignment::Right),
+                        (format_currency_plain(payment.remaining_balance), CellAlignment::Right),
+                    ];
+                    if let Some(real) = &real_schedule {
+                        cells.push((format_currency_plain(real[index].interest_payment), CellAlignment::Right));
+                        cells.push((format_currency_plain(real[index].remaining_balance), CellAlignment::Right));
+                    }
+                    let row: Vec<(&str, CellAlignment)> = cells.iter().map(|(text, align)| (text.as_str(), *align)).collect();
+                    add_row(&mut table, &row);
+                }
+                
+                println!("{table}");
+            } else {
+                // Group by calendar year for tax reporting of interest paid
+                let mut headers = vec!["Year", "Payments", "Principal", "Interest", "Ending Balance"];
+                if real_schedule.is_some() {
+                    headers.extend(["Interest (Today's Money)", "Ending Balance (Today's Money)"]);
+                }
+                let mut table = create_table(headers);
+                
+                let real_years = real_schedule.as_deref().map(summarize_schedule_by_year);
+                for (index, year) in summarize_schedule_by_year(&schedule).iter().enumerate() {
+                    let mut cells = vec![
+                        (format!("{}", year.year), CellAlignment::Center),
+                        (format!("{}", year.payments), CellAlignment::Center),
+                        (format_currency_plain(year.principal_paid), CellAlignment::Right),
+                        (format_currency_plain(year.interest_paid), CellAlignment::Right),
+                        (format_currency_plain(year.ending_balance), CellAlignment::Right),
+                    ];
+                    if let Some(real) = &real_years {
+                        cells.push((format_currency_plain(real[index].interest_paid), CellAlignment::Right));
+                        cells.push((format_currency_plain(real[index].ending_balance), CellAlignment::Right));
+                    }
+                    let row: Vec<(&str, CellAlignment)> = cells.iter().map(|(text, align)| (text.as_str(), *align)).collect();
+                    add_row(&mut table, &row);
+                }
+                
+                println!("{table}");
+            }
+            
+            if let Some(last) = schedule.last() {
+                println!("\n{}: {} (final payment {})",
+                    "Total Interest".bold(),
+                    format_currency(last.cumulative_interest),
+                    last.payment_date.format("%Y-%m-%d")
+                );
+            }
+            
+            if let Some(last) = real_schedule.as_ref().and_then(|real| real.last()) {
+                println!("{}: {}", "Total Interest (Today's Money)".bold(), format_currency(last.cumulative_interest));
+            }
+            
+            info!("Amortization calculation completed");
+            Ok(())
+        }
+        Command::Mortgage(mortgage) => {
+            debug!("Calculating mortgage details with: {:?}", mortgage);
+            
+            let first_payment_date = mortgage.first_payment_date.unwrap_or_else(default_first_payment_date);
+            let (monthly_payment, total_interest, payoff_date) = calculate_mortgage_details(
+                mortgage.loan_amount,
+                mortgage.interest_rate,
+                mortgage.term,
+                first_payment_date
+            ).context("Failed to calculate mortgage details")?;
+            
+            let summary_items = vec![
+                ("Loan Amount", format_currency(mortgage.loan_amount)),
+                ("Annual Interest Rate", format_rate_as_percentage(mortgage.interest_rate / 100.0)),
+                ("Term", format_years(mortgage.term as f64)),
+                ("Monthly Payment", format_currency(monthly_payment)),
+                ("Total Interest", format_currency(total_interest)),
+                ("First Payment Date", first_payment_date.format("%Y-%m-%d").to_string()),
+                ("Payoff Date", payoff_date.format("%Y-%m-%d").to_string()),
+            ];
+            
+            let table = create_summary_table("Component", summary_items);
+            println!("{table}");
+            
+            info!("Mortgage calculation completed. Monthly payment: {:.2}", monthly_payment);
+            Ok(())
+        }
+        Command::ReturnOnEquity(roe) => {
+            debug!("Calculating ROE with: {:?}", roe);
+            
+            let roe_value = calculate_roe(roe.net_income, roe.equity)
+                .context("Failed to calculate ROE")?;
+            
+            info!("Calculated ROE: {:.4}%", roe_value);
+            
+            let mut table = create_table(vec!["Net Income", "Equity", "Return on Equity"]);
+            
+            add_row(&mut table, &[
+                (&format_currency_plain(roe.net_income), CellAlignment::Right),
+                (&format_currency_plain(roe.equity), CellAlignment::Right),
+                (&format_percentage_plain(roe_value / 100.0, 2), CellAlignment::Right),
+            ]);
+            
+            println!("{table}");
+            info!("ROE calculation completed successfully");
+            Ok(())
+        }
+        Command::DividendYield(dividend_yield) => {
+            debug!("Calculating dividend yield with: {:?}", dividend_yield);
+            
+            let result = calculate_dividend_yield(&dividend_yield)
+                .context("Failed to calculate dividend yield")?;
+            
+            info!("Calculated dividend yield: {:.4}", result);
+            
+            let mut table = create_table(vec!["Dividend", "Price", "Dividend Yield"]);
+            
+            add_row(&mut table, &[
+                (&format!("{:.2}", dividend_yield.dividend), CellAlignment::Right),
+                (&format!("{:.2}", dividend_yield.price), CellAlignment::Right),
+                (&format_percentage_plain(result, 2), CellAlignment::Right),
             ]);
             
             println!("{table}");
-            info!("Simple interest calculation completed");
+            info!("Dividend yield calculation completed");
+            Ok(())
+        }
+        Command::CAPM(capm) => {
+            debug!("Calculating CAPM with: {:?}", capm);
+            
+            if !capm.sensitivity.is_empty() {
+                return print_sensitivity(&capm, &capm.sensitivity);
+            }
+            
+            if let (Some(input), Some(target)) = (&capm.solve_for, capm.target) {
+                return print_goal_seek(&capm, input, target);
+            }
+            
+            let expected_return = calculate_capm(capm.risk_free_rate, capm.beta, capm.market_return)
+                .context("Failed to calculate CAPM")?;
+            
+            let summary_items = vec![
+                ("Risk-Free Rate", format_rate_as_percentage(capm.risk_free_rate)),
+                ("Beta", format!("{:.2}", capm.beta)),
+                ("Market Return", format_rate_as_percentage(capm.market_return)),
+                ("Expected Return (CAPM)", format_rate_as_percentage(expected_return)),
+            ];
+            
+            let table = create_summary_table("Component", summary_items);
+            println!("{table}");
+            
+            info!("CAPM calculation completed: {:.4}%", expected_return * 100.0);
             Ok(())
         }
-        Command::CompoundInterest(ci) => {
-            debug!("Calculating compound interest with: {:?}", ci);
+        Command::WACC(wacc) => {
+            debug!("Calculating WACC with: {:?}", wacc);
             
-            // Create table using dynamic helper
-            let mut table = create_table(vec!["Year", "Amount"]);
+            let tax_rate = wacc.effective_tax_rate()?;
+            if !wacc.sensitivity.is_empty() {
+                let base = WACC { tax_rate: Some(tax_rate), tax_brackets: None, ..wacc.clone() };
+                return print_sensitivity(&base, &wacc.sensitivity);
+            }
             
-            // Calculate compound interest for each year
-            // Interactive mode already converts percentage to decimal, CLI mode needs conversion
-            let rate = if ci.rate > 1.0 { ci.rate / 100.0 } else { ci.rate };
-            for year in 1..=ci.t {
-                let amount = calculate_compound_interest(ci.principal, rate, ci.n, year)
-                    .context("Failed to calculate compound interest")?;
-                
-                // Add row with dynamic alignment - no manual padding needed
-                add_row(&mut table, &[
-                    (&format!("{}", year), CellAlignment::Center),
-                    (&format_currency_plain(amount), CellAlignment::Right),
-                ]);
+            if let (Some(input), Some(target)) = (&wacc.solve_for, wacc.target) {
+                let base = WACC { tax_rate: Some(tax_rate), tax_brackets: None, ..wacc.clone() };
+                return print_goal_seek(&base, input, target);
             }
             
+            let wacc_value = calculate_wacc(
+                wacc.cost_of_equity,
+                wacc.cost_of_debt,
+                tax_rate,
+                wacc.market_value_equity,
+                wacc.market_value_debt
+            ).context("Failed to calculate WACC")?;
+            
+            let summary_items = vec![
+                ("Cost of Equity (Ke)", format_rate_as_percentage(wacc.cost_of_equity)),
+                ("Cost of Debt (Kd)", format_rate_as_percentage(wacc.cost_of_debt)),
+                ("Tax Rate", format_rate_as_percentage(tax_rate)),
+                ("Market Value of Equity (E)", format_currency(wacc.market_value_equity)),
+                ("Market Value of Debt (D)", format_currency(wacc.market_value_debt)),
+                ("WACC", format_rate_as_percentage(wacc_value)),
+            ];
+            
+            let table = create_summary_table("Component", summary_items);
             println!("{table}");
-            info!("Compound interest calculation completed");
+            
+            info!("WACC calculation completed: {:.4}%", wacc_value * 100.0);
             Ok(())
         }
-        Command::PresentValue(pv) => {
-            debug!("Calculating present value with: {:?}", pv);
-            
-            let result = calculate_present_value(pv.future_value, pv.rate, pv.time)
-                .context("Failed to calculate present value")?;
+        Command::DCF(dcf) => {
+            debug!("Calculating DCF with: {:?}", dcf);
             
-            info!("Calculated present value: {:.4}", result);
+            if !dcf.sensitivity.is_empty() {
+                return print_sensitivity(&dcf, &dcf.sensitivity);
+            }
             
-            let mut table = create_table(vec!["Future Value", "Rate", "Time", "Present Value"]);
+            if let (Some(input), Some(target)) = (&dcf.solve_for, dcf.target) {
+                return print_goal_seek(&dcf, input, target);
+            }
             
-            add_row(&mut table, &[
-                (&format_currency_plain(pv.future_value), CellAlignment::Right),
-                (&format_rate_as_percentage(pv.rate), CellAlignment::Right),
-                (&format_years(pv.time), CellAlignment::Right),
-                (&format_currency_plain(result), CellAlignment::Right),
-            ]);
+            let dcf_value = dcf.evaluate().context("Failed to calculate DCF")?;
             
+            let mut table = create_table(vec!["Year", "Cash Flow", "Discounted Cash Flow"]);
+            for (year, cash_flow) in dcf.cash_flows.iter().enumerate() {
+                let discounted = cash_flow / (1.0 + dcf.discount_rate).powf((year + 1) as f64);
+                add_row(&mut table, &[
+                    (&format!("{}", year + 1), CellAlignment::Center),
+                    (&format_currency_plain(*cash_flow), CellAlignment::Right),
+                    (&format_currency_plain(discounted), CellAlignment::Right),
+                ]);
+            }
             println!("{table}");
-            info!("Present value calculation completed");
+            
+            println!("\n{}: {}", "Discounted Cash Flow Value".bold(), format_currency(dcf_value));
+            
+            info!("DCF calculation completed: {:.2}", dcf_value);
             Ok(())
         }
-        Command::FutureValue(fv) => {
-            debug!("Calculating future value with: {:?}", fv);
-            
-            let result = calculate_future_value(fv.present_value, fv.rate, fv.time)
-                .context("Failed to calculate future value")?;
+        Command::Refinance(refinance) => {
+            debug!("Analyzing refinance offers with: {:?}", refinance);
             
-            info!("Calculated future value: {:.4}", result);
+            let current_payment = calculate_loan_payment(refinance.balance, refinance.rate, refinance.remaining_term as f64)
+                .context("Failed to calculate current loan payment")?;
             
-            let mut table = create_table(vec!["Present Value", "Rate", "Time", "Future Value"]);
+            println!("{}: {} at {} for {} ({} per month)",
+                "Current Loan".bold(),
+                format_currency(refinance.balance),
+                format_rate_as_percentage(refinance.rate / 100.0),
+                format_years(refinance.remaining_term as f64),
+                format_currency(current_payment)
+            );
             
-            add_row(&mut table, &[
-                (&format_currency_plain(fv.present_value), CellAlignment::Right),
-                (&format_rate_as_percentage(fv.rate), CellAlignment::Right),
-                (&format_years(fv.time), CellAlignment::Right),
-                (&format_currency_plain(result), CellAlignment::Right),
+            let mut table = create_table(vec![
+                "Offer", "Rate", "Term", "New Payment", "Monthly Savings",
+                "Upfront Costs", "Break-Even", "Interest Saved", "NPV",
             ]);
             
+            for (index, offer) in refinance.offers.iter().enumerate() {
+                let analysis = analyze_refinance(
+                    refinance.balance,
+                    refinance.rate,
+                    refinance.remaining_term,
+                    offer,
+                    refinance.discount_rate
+                ).with_context(|| format!("Failed to analyze offer {}", index + 1))?;
+                
+                let break_even = match analysis.break_even_month {
+                    Some(month) => format!("Month {}", month),
+                    None => "Never".to_string(),
+                };
+                
+                add_row(&mut table, &[
+                    (&format!("{}", index + 1), CellAlignment::Center),
+                    (&format_rate_as_percentage(offer.annual_interest_rate / 100.0), CellAlignment::Right),
+                    (&format_years(offer.term_years as f64), CellAlignment::Right),
+                    (&format_currency_plain(analysis.new_payment), CellAlignment::Right),
+                    (&format_currency_plain(analysis.monthly_savings), CellAlignment::Right),
+                    (&format_currency_plain(analysis.upfront_costs), CellAlignment::Right),
+                    (&break_even, CellAlignment::Right),
+                    (&format_currency_plain(analysis.interest_savings), CellAlignment::Right),
+                    (&format_currency_plain(analysis.npv), CellAlignment::Right),
+                ]);
+            }
+            
             println!("{table}");
-            info!("Future value calculation completed");
+            info!("Refinance analysis completed");
             Ok(())
         }
-        Command::NPV(npv) => {
-            debug!("Calculating NPV with: {:?}", npv);
+        Command::DebtPlan(plan) => {
+            debug!("Planning debt payoff with: {:?}", plan);
             
-            // Create cash flows vector from the NPV inputs
-            let cash_flows: Vec<f64> = (0..npv.lifespan).map(|_| npv.cash_inflow).collect();
+            let file = File::open(&plan.file)
+                .with_context(|| format!("Failed to open {}", plan.file.display()))?;
+            let debts = read_debts_csv(file).context("Failed to read debts")?;
+            let start_date = plan.start_date.unwrap_or_else(default_first_payment_date);
             
-            let npv_value = calculate_npv(npv.initial_investment, &cash_flows, npv.discount_rate)
-                .context("Failed to calculate NPV")?;
+            let mut strategies = vec![PayoffStrategy::Snowball, PayoffStrategy::Avalanche];
+            if let Some(order) = &plan.order {
+                strategies.push(PayoffStrategy::Custom(order.clone()));
+            }
             
-            // Create and format table
-            let mut table = create_table(vec!["Year", "Cash Inflow", "Discounted Cash Flow"]);
+            // Minimum payments alone are the baseline for months and interest saved
+            let baseline = simulate_debt_payoff(&debts, plan.budget, &PayoffStrategy::MinimumPayments).ok();
             
-            // Calculate and display each year's discounted cash flow
-            for year in 1..=npv.lifespan {
-                let discounted_cash_flow = npv.cash_inflow / (1.0 + npv.discount_rate).powf(year as f64);
+            let mut summary = create_table(vec!["Strategy", "Months", "Debt-Free Date", "Total Interest", "Months Saved", "Interest Saved"]);
+            
+            for strategy in &strategies {
+                let result = simulate_debt_payoff(&debts, plan.budget, strategy)
+                    .with_context(|| format!("Failed to simulate the {} strategy", strategy.name()))?;
                 
-                add_row(&mut table, &[
-                    (&format!("{}", year), CellAlignment::Center),
-                    (&format_currency_plain(npv.cash_inflow), CellAlignment::Right),
-                    (&format_currency_plain(discounted_cash_flow), CellAlignment::Right),
+                let mut table = create_table(vec!["Debt", "Payoff Month", "Payoff Date", "Interest Paid"]);
+                for debt in &result.debts {
+                    let (payoff_month, payoff_date) = match debt.payoff_month {
+                        Some(month) => (
+                            format!("{}", month),
+                            calculate_payment_date(start_date, month - 1)?.format("%Y-%m-%d").to_string(),
+                        ),
+                        None => ("Already paid".to_string(), "n/a".to_string()),
+                    };
+                    add_row(&mut table, &[
+                        (&debt.name, CellAlignment::Left),
+                        (&payoff_month, CellAlignment::Center),
+                        (&payoff_date, CellAlignment::Center),
+                        (&format_currency_plain(debt.interest_paid), CellAlignment::Right),
+                    ]);
+                }
+                println!("\n{}", strategy.name().bold());
+                println!("{table}");
+                
+                let (months_saved, interest_saved) = match &baseline {
+                    Some(base) => (
+                        format!("{}", base.months as i64 - result.months as i64),
+                        format_currency_plain(base.total_interest - result.total_interest),
+                    ),
+                    None => ("n/a".to_string(), "n/a".to_string()),
+                };
+                
+                let debt_free_date = calculate_payment_date(start_date, result.months.saturating_sub(1))?;
+                add_row(&mut summary, &[
+                    (strategy.name(), CellAlignment::Left),
+                    (&format!("{}", result.months), CellAlignment::Center),
+                    (&debt_free_date.format("%Y-%m-%d").to_string(), CellAlignment::Center),
+                    (&format_currency_plain(result.total_interest), CellAlignment::Right),
+                    (&months_saved, CellAlignment::Right),
+                    (&interest_saved, CellAlignment::Right),
                 ]);
             }
             
-            println!("{table}");
+            println!("\n{}", "Comparison (savings versus minimum payments only)".bold());
+            println!("{summary}");
             
-            // Print the net present value
-            println!("\n{}: {}", 
-                "Net Present Value (NPV)".bold(), 
-                format_currency(npv_value)
-            );
+            info!("Debt payoff planning completed");
+            Ok(())
+        }
+        Command::RentVsBuy(rvb) => {
+            debug!("Comparing rent versus buy with: {:?}", rvb);
             
-            info!("NPV calculation completed. NPV: {:.2}", npv_value);
+            let analysis = analyze_rent_vs_buy(&RentVsBuyInputs {
+                home_price: rvb.home_price,
+                down_payment_rate: rvb.down_payment,
+                closing_cost_rate: rvb.closing_costs,
+                mortgage_rate: rvb.mortgage_rate,
+                mortgage_term_years: rvb.term,
+                property_tax_rate: rvb.property_tax_rate,
+                maintenance_rate: rvb.maintenance_rate,
+                appreciation_rate: rvb.appreciation,
+                selling_cost_rate: rvb.selling_costs,
+                monthly_rent: rvb.rent,
+                rent_inflation: rvb.rent_inflation,
+                investment_return: rvb.investment_return,
+                years: rvb.years,
+            }).context("Failed to compare renting and buying")?;
+            
+            print_decision_analysis(&analysis, "Rent");
+            info!("Rent-vs-buy analysis completed");
             Ok(())
         }
-        Command::ROI(roi) => {
-            debug!("Calculating ROI with: {:?}", roi);
+        Command::LeaseVsBuy(lvb) => {
+            debug!("Comparing lease versus buy with: {:?}", lvb);
             
-            let roi_value = calculate_roi(roi.net_profit, roi.cost_of_investment)
-                .context("Failed to calculate ROI")?;
+            let analysis = analyze_lease_vs_buy(&LeaseVsBuyInputs {
+                price: lvb.price,
+                lease_down_payment: lvb.lease_down_payment,
+                lease_term_months: lvb.lease_term,
+                residual_rate: lvb.residual,
+                money_factor: lvb.money_factor,
+                purchase_down_payment: lvb.down_payment,
+                loan_rate: lvb.loan_rate,
+                loan_term_years: lvb.loan_term,
+                depreciation_rate: lvb.depreciation,
+                investment_return: lvb.investment_return,
+                years: lvb.years,
+            }).context("Failed to compare leasing and buying")?;
             
-            info!("Calculated ROI: {:.4}%", roi_value);
+            print_decision_analysis(&analysis, "Lease");
+            info!("Lease-vs-buy analysis completed");
+            Ok(())
+        }
+        Command::Retirement(retirement) => {
+            debug!("Planning retirement with: {:?}", retirement);
             
-            let mut table = create_table(vec!["Net Profit", "Cost of Investment", "ROI"]);
+            let inputs = RetirementInputs {
+                current_age: retirement.current_age,
+                retirement_age: retirement.retirement_age,
+                life_expectancy: retirement.life_expectancy,
+                current_savings: retirement.savings,
+                monthly_contribution: retirement.contribution,
+                contribution_raise: retirement.raise,
+                employer_match: retirement.employer_match,
+                pre_retirement_return: retirement.pre_return,
+                post_retirement_return: retirement.post_return,
+                // Retirement plans with the global --inflation rate, or 2.5% without one
+                inflation: inflation.map_or(0.025, |inflation| inflation.annual_rate),
+                target_real_income: retirement.target_income,
+            };
             
-            add_row(&mut table, &[
-                (&format_currency_plain(roi.net_profit), CellAlignment::Right),
-                (&format_currency_plain(roi.cost_of_investment), CellAlignment::Right),
-                (&format_percentage_plain(roi_value / 100.0, 2), CellAlignment::Right),
+            let plan = plan_retirement(&inputs).context("Failed to plan retirement")?;
+            let schedule = project_decumulation(&inputs, plan.projected_savings, &retirement.withdrawal_rule())
+                .context("Failed to project retirement withdrawals")?;
+            
+            let gap = if plan.savings_gap > 0.0 {
+                format_currency_plain(plan.savings_gap)
+            } else {
+                format!("{} (on track)", format_currency_plain(plan.savings_gap.abs()))
+            };
+            
+            let summary = create_summary_table("Retirement Plan", vec![
+                ("Years to Retirement", format!("{}", plan.years_to_retirement)),
+                ("Years in Retirement", format!("{}", plan.years_in_retirement)),
+                ("Projected Savings", format_currency_plain(plan.projected_savings)),
+                ("Required Savings", format_currency_plain(plan.required_savings)),
+                ("Savings Gap", gap),
+                ("Required Monthly Contribution", format_currency_plain(plan.required_monthly_contribution)),
+                ("First-Year Income Needed", format_currency_plain(plan.first_year_income)),
             ]);
+            println!("{summary}");
             
+            let mut table = create_table(vec![
+                "Age", "Starting Balance", "Withdrawal", "Withdrawal (Today's Money)", "Growth", "Ending Balance",
+            ]);
+            for year in &schedule {
+                add_row(&mut table, &[
+                    (&format!("{}", year.age), CellAlignment::Center),
+                    (&format_currency_plain(year.starting_balance), CellAlignment::Right),
+                    (&format_currency_plain(year.withdrawal), CellAlignment::Right),
+                    (&format_currency_plain(year.real_withdrawal), CellAlignment::Right),
+                    (&format_currency_plain(year.investment_growth), CellAlignment::Right),
+                    (&format_currency_plain(year.ending_balance), CellAlignment::Right),
+                ]);
+            }
+            println!("\n{}", "Decumulation Schedule".bold());
             println!("{table}");
-            info!("ROI calculation completed");
+            
+            if let Some(depleted) = schedule.iter().find(|year| year.ending_balance <= 0.0) {
+                println!("\n{}: savings run out at age {}", "Warning".bold(), depleted.age);
+            }
+            
+            info!("Retirement planning completed");
             Ok(())
         }
-        Command::Average(average) => {
-            debug!("Calculating average with: {:?}", average);
-            
-            let avg = calculate_mean(&average.numbers)
-                .context("Failed to calculate average")?;
+        Command::TaxAccounts(accounts) => {
+            debug!("Projecting account tax treatments with: {:?}", accounts);
             
-            let mut table = create_table(vec!["Number"]);
+            let projection = project_tax_accounts(&TaxProjectionInputs {
+                initial_investment: accounts.initial,
+                annual_contribution: accounts.contribution,
+                years: accounts.years,
+                annual_return: accounts.annual_return,
+                dividend_yield: accounts.dividend_yield,
+                current_income_tax_rate: accounts.current_tax_rate,
+                retirement_income_tax_rate: accounts.retirement_tax_rate,
+                dividend_tax_rate: accounts.dividend_tax_rate,
+                capital_gains_tax_rate: accounts.capital_gains_rate,
+            }).context("Failed to project account balances")?;
             
-            for number in &average.numbers {
+            let mut table = create_table(vec!["Year", "Taxable", "Tax-Deferred", "Tax-Free"]);
+            for year in &projection.years {
                 add_row(&mut table, &[
-                    (&format!("{:.2}", number), CellAlignment::Right),
+                    (&format!("{}", year.year), CellAlignment::Center),
+                    (&format_currency_plain(year.taxable), CellAlignment::Right),
+                    (&format_currency_plain(year.tax_deferred), CellAlignment::Right),
+                    (&format_currency_plain(year.tax_free), CellAlignment::Right),
                 ]);
             }
+            println!("{table}");
             
-            add_row(&mut table, &[
-                (&format!("Average: {:.2}", avg), CellAlignment::Right),
-            ]);
+            let mut summary = create_table(vec!["Account", "Contributions", "Ending Balance", "Taxes", "After-Tax Value"]);
+            for outcome in &projection.outcomes {
+                add_row(&mut summary, &[
+                    (outcome.account.name(), CellAlignment::Left),
+                    (&format_currency_plain(outcome.contributions), CellAlignment::Right),
+                    (&format_currency_plain(outcome.ending_balance), CellAlignment::Right),
+                    (&format_currency_plain(outcome.taxes_paid), CellAlignment::Right),
+                    (&format_currency_plain(outcome.after_tax_value), CellAlignment::Right),
+                ]);
+            }
+            println!("\n{}", "Ending Balances".bold());
+            println!("{summary}");
             
-            println!("{table}");
-            info!("Average calculation completed: {:.4}", avg);
+            info!("Tax-aware projection completed");
             Ok(())
         }
-        Command::Mode(mode) => {
-            debug!("Calculating mode with: {:?}", mode);
+        Command::Tax(tax) => {
+            debug!("Calculating income tax with: {:?}", tax);
             
-            let mode_value = calculate_mode(&mode.numbers)
-                .context("Failed to calculate mode")?;
+            let table = load_tax_table(&tax.brackets)?;
+            let schedule = table.schedule(&tax.filing_status)?;
+            let result = calculate_income_tax(tax.income, schedule, tax.deduction)
+                .context("Failed to calculate income tax")?;
             
-            let mut table = create_table(vec!["Number", "Mode"]);
+            let mut brackets = create_table(vec!["Bracket", "Rate", "Taxable Amount", "Tax"]);
+            for bracket in result.brackets.iter().filter(|b| b.taxable_amount > 0.0) {
+                let range = match bracket.ceiling {
+                    Some(ceiling) => format!("{} - {}", format_currency_plain(bracket.threshold), format_currency_plain(ceiling)),
+                    None => format!("{}+", format_currency_plain(bracket.threshold)),
+                };
+                add_row(&mut brackets, &[
+                    (&range, CellAlignment::Left),
+                    (&format_rate_as_percentage(bracket.rate), CellAlignment::Right),
+                    (&format_currency_plain(bracket.taxable_amount), CellAlignment::Right),
+                    (&format_currency_plain(bracket.tax), CellAlignment::Right),
+                ]);
+            }
+            println!("{brackets}");
             
-            for number in &mode.numbers {
-                add_row(&mut table, &[
-                    (&format!("{:.2}", number), CellAlignment::Right),
-                    ("", CellAlignment::Left),
+            let summary = create_summary_table("Component", vec![
+                ("Filing Status", tax.filing_status.clone()),
+                ("Gross Income", format_currency_plain(result.gross_income)),
+                ("Deduction", format_currency_plain(result.deduction)),
+                ("Taxable Income", format_currency_plain(result.taxable_income)),
+                ("Tax Owed", format_currency_plain(result.tax_owed)),
+                ("Marginal Rate", format_rate_as_percentage(result.marginal_rate)),
+                ("Effective Rate", format_rate_as_percentage(result.effective_rate)),
+            ]);
+            println!("\n{summary}");
+            
+            info!("Income tax calculation completed");
+            Ok(())
+        }
+        Command::Lots(lots) => {
+            debug!("Matching tax lots with: {:?}", lots);
+            
+            let file = File::open(&lots.file)
+                .with_context(|| format!("Failed to open trades file {}", lots.file.display()))?;
+            let trades = read_trades_csv(file).context("Failed to read trades")?;
+            let report = match_lots(&trades, lots.method.into()).context("Failed to match tax lots")?;
+            
+            let mut realized = create_table(vec![
+                "Sale Date", "Symbol", "Lot", "Acquired", "Quantity", "Proceeds", "Cost Basis", "Gain", "Term", "Wash Sale",
+            ]);
+            for gain in &report.realized {
+                let wash_sale = if gain.wash_sale {
+                    format!("{} disallowed", format_currency_plain(gain.disallowed_loss))
+                } else {
+                    String::new()
+                };
+                add_row(&mut realized, &[
+                    (&gain.sale_date.format("%Y-%m-%d").to_string(), CellAlignment::Center),
+                    (&gain.symbol, CellAlignment::Left),
+                    (&gain.lot_id, CellAlignment::Left),
+                    (&gain.acquired.format("%Y-%m-%d").to_string(), CellAlignment::Center),
+                    (&format!("{}", gain.quantity), CellAlignment::Right),
+                    (&format_currency_plain(gain.proceeds), CellAlignment::Right),
+                    (&format_currency_plain(gain.cost_basis), CellAlignment::Right),
+                    (&format_currency_plain(gain.gain), CellAlignment::Right),
+                    (gain.term.name(), CellAlignment::Center),
+                    (&wash_sale, CellAlignment::Right),
                 ]);
             }
+            println!("{}", "Realized Gains".bold());
+            println!("{realized}");
             
-            match mode_value {
-                Some(m) => add_row(&mut table, &[
-                    ("Mode:", CellAlignment::Left),
-                    (&format!("{:.2}", m), CellAlignment::Right),
-                ]),
-                None => add_row(&mut table, &[
-                    ("Mode:", CellAlignment::Left),
-                    ("No mode", CellAlignment::Left),
-                ]),
-            };
+            let summary = create_summary_table("Realized", vec![
+                ("Short-Term Gain", format_currency_plain(report.total_gain(HoldingTerm::ShortTerm))),
+                ("Long-Term Gain", format_currency_plain(report.total_gain(HoldingTerm::LongTerm))),
+                ("Wash Sale Losses Disallowed", format_currency_plain(report.realized.iter().map(|g| g.disallowed_loss).sum())),
+            ]);
+            println!("{summary}");
             
-            println!("{table}");
-            info!("Mode calculation completed");
+            let prices: HashMap<String, f64> = lots.prices.iter().cloned().collect();
+            let as_of = lots.as_of.unwrap_or_else(|| Local::now().date_naive());
+            let unrealized = calculate_unrealized_gains(&report.open_lots, &prices, as_of);
+            
+            let mut open = create_table(vec![
+                "Lot", "Symbol", "Acquired", "Quantity", "Cost Basis", "Market Value", "Unrealized Gain", "Term",
+            ]);
+            for lot in &report.open_lots {
+                let valued = unrealized.iter().find(|u| u.lot.id == lot.id);
+                let market_value = valued.map_or("n/a".to_string(), |u| format_currency_plain(u.market_value));
+                let gain = valued.map_or("n/a".to_string(), |u| format_currency_plain(u.gain));
+                add_row(&mut open, &[
+                    (&lot.id, CellAlignment::Left),
+                    (&lot.symbol, CellAlignment::Left),
+                    (&lot.acquired.format("%Y-%m-%d").to_string(), CellAlignment::Center),
+                    (&format!("{}", lot.quantity), CellAlignment::Right),
+                    (&format_currency_plain(lot.cost_per_share * lot.quantity), CellAlignment::Right),
+                    (&market_value, CellAlignment::Right),
+                    (&gain, CellAlignment::Right),
+                    (HoldingTerm::between(lot.acquired, as_of).name(), CellAlignment::Center),
+                ]);
+            }
+            println!("\n{}", "Open Lots".bold());
+            println!("{open}");
+            
+            info!("Lot matching completed");
             Ok(())
         }
-        Command::Medium(medium) => {
-            debug!("Calculating median with: {:?}", medium);
+        Command::Scenario(scenario) => {
+            debug!("Evaluating scenarios with: {:?}", scenario);
             
-            let median = calculate_median(&medium.numbers)
-                .context("Failed to calculate median")?;
+            let contents = std::fs::read_to_string(&scenario.file)
+                .with_context(|| format!("Failed to read scenario file {}", scenario.file.display()))?;
+            let is_yaml = scenario.file
+                .extension()
+                .is_some_and(|extension| extension == "yaml" || extension == "yml");
+            let file = if is_yaml {
+                ScenarioFile::from_yaml_str(&contents)
+            } else {
+                ScenarioFile::from_toml_str(&contents)
+            }
+            .context("Failed to load scenario file")?;
             
-            let mut table = create_table(vec!["Number"]);
+            let calculation = match (scenario.calculation, file.calculation.as_deref()) {
+                (Some(calculation), _) => calculation,
+                (None, Some(name)) => <ScenarioCalculation as clap::ValueEnum>::from_str(&input_key(name), true)
+                    .map_err(|_| anyhow::anyhow!("Unknown calculation '{}' in scenario file", name))?,
+                (None, None) => anyhow::bail!("The scenario file names no calculation; pass --calculation"),
+            };
             
-            // Sort for display
-            let mut sorted = medium.numbers.clone();
-            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+            match calculation {
+                ScenarioCalculation::Npv => print_scenarios(NPV {
+                    initial_investment: 0.0,
+                    cash_inflow: 0.0,
+                    discount_rate: 0.0,
+                    lifespan: 0,
+                    sensitivity: Vec::new(),
+                    solve_for: None,
+                    target: None,
+                }, &file),
+                ScenarioCalculation::LoanPayment => print_scenarios(LoanPayment {
+                    principal: 0.0,
+                    interest_rate: 0.0,
+                    loan_term: 0.0,
+                    first_payment_date: None,
+                    sensitivity: Vec::new(),
+                    solve_for: None,
+                    target: None,
+                }, &file),
+                ScenarioCalculation::Dcf => print_scenarios(DCF {
+                    discount_rate: 0.0,
+                    cash_flows: Vec::new(),
+                    sensitivity: Vec::new(),
+                    solve_for: None,
+                    target: None,
+                }, &file),
+                ScenarioCalculation::Capm => print_scenarios(CAPM {
+                    risk_free_rate: 0.0,
+                    beta: 0.0,
+                    market_return: 0.0,
+                    sensitivity: Vec::new(),
+                    solve_for: None,
+                    target: None,
+                }, &file),
+                ScenarioCalculation::Wacc => print_scenarios(WACC {
+                    cost_of_equity: 0.0,
+                    cost_of_debt: 0.0,
+                    tax_rate: None,
+                    market_value_equity: 0.0,
+                    market_value_debt: 0.0,
+                    tax_brackets: None,
+                    taxable_income: None,
+                    filing_status: "single".to_string(),
+                    sensitivity: Vec::new(),
+                    solve_for: None,
+                    target: None,
+                }, &file),
+            }
+        }
+        Command::Valuation(valuation) => {
+            debug!("Valuing company with: {:?}", valuation);
             
-            for number in &sorted {
+            let contents = std::fs::read_to_string(&valuation.file)
+                .with_context(|| format!("Failed to read valuation assumptions {}", valuation.file.display()))?;
+            let assumptions = ValuationAssumptions::from_toml_str(&contents)
+                .context("Failed to load valuation assumptions")?;
+            let result = value_company(&assumptions).context("Failed to value company")?;
+            
+            let (basis, rate_label) = match result.basis {
+                CashFlowBasis::Fcff => ("FCFF", "WACC"),
+                CashFlowBasis::Fcfe => ("FCFE", "Cost of Equity"),
+            };
+            println!("{}: {} discounted at {} {}", "Basis".bold(), basis, rate_label, format_rate_as_percentage(result.discount_rate));
+            
+            let mut table = create_table(vec![
+                "Year", "Revenue", "EBITDA", "D&A", "EBIT", "Taxes", "Capex", "Change in NWC", basis, "Present Value",
+            ]);
+            for year in &result.years {
                 add_row(&mut table, &[
-                    (&format!("{:.2}", number), CellAlignment::Right),
+                    (&format!("{}", year.year), CellAlignment::Center),
+                    (&format_currency_plain(year.revenue), CellAlignment::Right),
+                    (&format_currency_plain(year.ebitda), CellAlignment::Right),
+                    (&format_currency_plain(year.depreciation), CellAlignment::Right),
+                    (&format_currency_plain(year.ebit), CellAlignment::Right),
+                    (&format_currency_plain(year.taxes), CellAlignment::Right),
+                    (&format_currency_plain(year.capex), CellAlignment::Right),
+                    (&format_currency_plain(year.change_in_working_capital), CellAlignment::Right),
+                    (&format_currency_plain(year.free_cash_flow), CellAlignment::Right),
+                    (&format_currency_plain(year.present_value), CellAlignment::Right),
                 ]);
             }
+            println!("{table}");
             
-            add_row(&mut table, &[
-                (&format!("Median: {:.2}", median), CellAlignment::Right),
+            let terminal_method = match assumptions.terminal {
+                TerminalValueMethod::Gordon { growth } => format!("Gordon growth at {}", format_rate_as_percentage(growth)),
+                TerminalValueMethod::ExitMultiple { multiple } => format!("{}x EBITDA", format_input_value(multiple)),
+            };
+            let bridge = &assumptions.bridge;
+            let summary = create_summary_table("Valuation", vec![
+                ("Terminal Value Method", terminal_method),
+                ("Terminal Value", format_currency(result.terminal_value)),
+                ("PV of Projected Cash Flows", format_currency(result.present_value_of_flows())),
+                ("PV of Terminal Value", format_currency(result.present_value_of_terminal)),
+                ("Terminal Value Share", format_rate_as_percentage(result.terminal_share())),
+                ("Enterprise Value", format_currency(result.enterprise_value)),
+                ("Less: Debt", format_currency(bridge.debt)),
+                ("Less: Minority Interest", format_currency(bridge.minority_interest)),
+                ("Plus: Cash", format_currency(bridge.cash)),
+                ("Equity Value", format_currency(result.equity_value)),
+                ("Shares Outstanding", format_input_value(bridge.shares_outstanding)),
+                ("Value per Share", format_currency(result.value_per_share)),
             ]);
+            println!("\n{summary}");
             
-            println!("{table}");
-            info!("Median calculation completed: {:.4}", median);
+            info!("Valuation completed");
             Ok(())
         }
-        Command::PaybackPeriod(payback) => {
-            debug!("Calculating payback period with: {:?}", payback);
-            
-            let payback_period = calculate_payback_period(payback.initial_cost, &payback.cash_flows)
-                .context("Failed to calculate payback period")?;
+        Command::DDM(ddm) => {
+            debug!("Calculating dividend discount model with: {:?}", ddm);
             
-            let mut table = create_table(vec!["Cash Flows", "Initial Cost", "Payback Period"]);
+            let required_return = ddm.required_return()?;
+            let model = ddm.dividend_model()?;
+            let value = model.value(ddm.dividend, required_return)
+                .context("Failed to calculate dividend discount value")?;
             
-            let payback_str = match payback_period {
-                Some(period) => format!("{:.2} years", period),
-                None => "Never pays back".to_string(),
+            let mut summary_items = vec![
+                ("Current Dividend", format_currency(ddm.dividend)),
+                ("Required Return", format_rate_as_percentage(required_return)),
+            ];
+            let implied_label = match &model {
+                DividendModel::Gordon { growth } => {
+                    summary_items.push(("Model", "Gordon growth".to_string()));
+                    summary_items.push(("Growth", format_rate_as_percentage(*growth)));
+                    "Implied Growth"
+                }
+                DividendModel::MultiStage { high_growth, terminal_growth } => {
+                    let valuation = calculate_multi_stage_ddm(ddm.dividend, high_growth, *terminal_growth, required_return)
+                        .context("Failed to calculate multi-stage dividend value")?;
+                    
+                    let mut table = create_table(vec!["Year", "Growth", "Dividend", "Present Value"]);
+                    for dividend in &valuation.dividends {
+                        add_row(&mut table, &[
+                            (&format!("{}", dividend.year), CellAlignment::Center),
+                            (&format_rate_as_percentage(dividend.growth), CellAlignment::Right),
+                            (&format_currency_plain(dividend.dividend), CellAlignment::Right),
+                            (&format_currency_plain(dividend.present_value), CellAlignment::Right),
+                        ]);
+                    }
+                    println!("{}", "High-Growth Dividends".bold());
+                    println!("{table}\n");
+                    
+                    summary_items.push(("Model", format!("Two-stage ({} years)", high_growth.len())));
+                    summary_items.push(("Terminal Growth", format_rate_as_percentage(*terminal_growth)));
+                    summary_items.push(("Terminal Value", format_currency(valuation.terminal_value)));
+                    summary_items.push(("PV of Terminal Value", format_currency(valuation.present_value_of_terminal)));
+                    "Implied High Growth"
+                }
+                DividendModel::HModel { short_term_growth, long_term_growth, half_life } => {
+                    summary_items.push(("Model", format!("H-model (half-life {} years)", format_input_value(*half_life))));
+                    summary_items.push(("Short-Term Growth", format_rate_as_percentage(*short_term_growth)));
+                    summary_items.push(("Long-Term Growth", format_rate_as_percentage(*long_term_growth)));
+                    "Implied Short-Term Growth"
+                }
             };
+            summary_items.push(("Intrinsic Value", format_currency(value)));
             
-            add_row(&mut table, &[
-                (&format!("{:?}", payback.cash_flows), CellAlignment::Left),
-                (&format_currency_plain(payback.initial_cost), CellAlignment::Right),
-                (&payback_str, CellAlignment::Right),
-            ]);
+            if let Some(price) = ddm.price {
+                let implied_growth = model.implied_growth(ddm.dividend, required_return, price)
+                    .context("Failed to solve for the market-implied growth rate")?;
+                summary_items.push(("Market Price", format_currency(price)));
+                summary_items.push(("Upside", format_rate_as_percentage(value / price - 1.0)));
+                summary_items.push((implied_label, format_rate_as_percentage(implied_growth)));
+            }
             
+            let table = create_summary_table("Component", summary_items);
             println!("{table}");
-            info!("Payback period calculation completed");
+            
+            info!("Dividend discount model completed");
             Ok(())
         }
-        Command::BreakEven(break_even) => {
-            debug!("Calculating break-even analysis with: {:?}", break_even);
+        Command::CompareProjects(compare) => {
+            debug!("Comparing projects with: {:?}", compare);
             
-            let (break_even_units, break_even_revenue) = calculate_break_even_analysis(
-                break_even.fixed_costs,
-                break_even.variable_costs,
-                break_even.price_per_unit
-            ).context("Failed to calculate break-even analysis")?;
+            let finance_rate = compare.finance_rate.unwrap_or(compare.discount_rate);
+            let reinvestment_rate = compare.reinvestment_rate.unwrap_or(compare.discount_rate);
+            let metrics = compare_projects(&compare.projects, compare.discount_rate, finance_rate, reinvestment_rate)
+                .context("Failed to compare projects")?;
+            
+            let mut table = create_table(vec![
+                "Project", "Life", "NPV", "MIRR", "PI", "Disc. Payback", "EAA", "Chain NPV",
+            ]);
+            for project in &metrics {
+                let payback = project.discounted_payback
+                    .map_or("Never".to_string(), |years| format!("{:.2} years", years));
+                add_row(&mut table, &[
+                    (&project.name, CellAlignment::Left),
+                    (&format!("{}", project.life), CellAlignment::Center),
+                    (&format_currency_plain(project.npv), CellAlignment::Right),
+                    (&format_rate_as_percentage(project.mirr), CellAlignment::Right),
+                    (&format!("{:.3}", project.profitability_index), CellAlignment::Right),
+                    (&payback, CellAlignment::Right),
+                    (&format_currency_plain(project.equivalent_annual_annuity), CellAlignment::Right),
+                    (&project.replacement_chain_npv.map_or("n/a".to_string(), format_currency_plain), CellAlignment::Right),
+                ]);
+            }
+            println!("{}", "Project Metrics".bold());
+            println!("{table}\n");
+            
+            let lives: Vec<usize> = metrics.iter().map(|project| project.life).collect();
+            let horizon = replacement_chain_horizon(&lives);
+            let mut rankings = create_table(vec!["Metric", "Ranking (best first)"]);
+            for metric in ProjectMetric::ALL {
+                if metric == ProjectMetric::ReplacementChainNpv && horizon.is_none() {
+                    continue;
+                }
+                let order: Vec<&str> = rank_projects(&metrics, metric)
+                    .iter()
+                    .map(|project| project.name.as_str())
+                    .collect();
+                add_row(&mut rankings, &[
+                    (metric.name(), CellAlignment::Left),
+                    (&order.join(" > "), CellAlignment::Left),
+                ]);
+            }
+            println!("{}", "Rankings".bold());
+            println!("{rankings}");
+            let chains = horizon.map_or(
+                format!("replacement chains would exceed {} years, so compare unequal lives by EAA", MAX_REPLACEMENT_CHAIN_YEARS),
+                |years| format!("replacement chains run {} years", years)
+            );
+            println!(
+                "\nDiscount rate {}, MIRR finance rate {}, reinvestment rate {}; {}",
+                format_rate_as_percentage(compare.discount_rate),
+                format_rate_as_percentage(finance_rate),
+                format_rate_as_percentage(reinvestment_rate),
+                chains
+            );
+            
+            info!("Project comparison completed");
+            Ok(())
+        }
+        Command::RealOption(analysis) => {
+            debug!("Valuing real option with: {:?}", analysis);
+            
+            let option = analysis.real_option()?;
+            let parameters = LatticeParameters {
+                volatility: analysis.volatility,
+                risk_free_rate: analysis.risk_free_rate,
+                years: analysis.years,
+                steps: analysis.steps,
+            };
+            let valuation = value_real_option(
+                analysis.initial_investment,
+                &analysis.cash_flows,
+                analysis.discount_rate,
+                &parameters,
+                &option
+            )
+            .context("Failed to value real option")?;
+            
+            let step_headers: Vec<String> = (0..=analysis.steps).map(|step| format!("Step {}", step)).collect();
+            let mut headers = vec!["Node"];
+            headers.extend(step_headers.iter().map(String::as_str));
+            
+            let mut project_table = create_table(headers.clone());
+            let mut option_table = create_table(headers);
+            for down_moves in 0..=analysis.steps {
+                let label = format!("{} down", down_moves);
+                let mut project_row = vec![(label.clone(), CellAlignment::Left)];
+                let mut option_row = vec![(label, CellAlignment::Left)];
+                for nodes in &valuation.lattice {
+                    match nodes.get(down_moves) {
+                        Some(node) => {
+                            let marker = if node.exercise { "*" } else { "" };
+                            project_row.push((format_currency_plain(node.project_value), CellAlignment::Right));
+                            option_row.push((format!("{}{}", format_currency_plain(node.value), marker), CellAlignment::Right));
+                        }
+                        None => {
+                            project_row.push((String::new(), CellAlignment::Right));
+                            option_row.push((String::new(), CellAlignment::Right));
+                        }
+                    }
+                }
+                let project_cells: Vec<(&str, CellAlignment)> = project_row.iter().map(|(text, align)| (text.as_str(), *align)).collect();
+                let option_cells: Vec<(&str, CellAlignment)> = option_row.iter().map(|(text, align)| (text.as_str(), *align)).collect();
+                add_row(&mut project_table, &project_cells);
+                add_row(&mut option_table, &option_cells);
+            }
+            println!("{}", "Project Value Lattice".bold());
+            println!("{project_table}\n");
+            println!("{} (* = exercise)", format!("Value with Option to {}", option.name()).bold());
+            println!("{option_table}\n");
             
-            let summary_items = vec![
-                ("Break-Even Point (units)", format!("{:.0}", break_even_units)),
-                ("Total Revenue Required", format_currency(break_even_revenue)),
-            ];
+            let mut exercise_table = create_table(vec!["Step", "Node", "Project Value", "Value", "Action"]);
+            for node in valuation.exercise_nodes() {
+                add_row(&mut exercise_table, &[
+                    (&format!("{}", node.step), CellAlignment::Center),
+                    (&format!("{} down", node.down_moves), CellAlignment::Left),
+                    (&format_currency_plain(node.project_value), CellAlignment::Right),
+                    (&format_currency_plain(node.value), CellAlignment::Right),
+                    (option.action(), CellAlignment::Left),
+                ]);
+            }
+            if valuation.exercise_nodes().next().is_some() {
+                println!("{}", "Optimal Exercise Nodes".bold());
+                println!("{exercise_table}\n");
+            } else {
+                println!("{}\n", "Exercising is never optimal; the option adds no value".yellow());
+            }
             
-            let table = create_summary_table("Metric", summary_items);
+            let table = create_summary_table("Component", vec![
+                ("Option", option.name().to_string()),
+                ("PV of Cash Flows", format_currency(valuation.project_value)),
+                ("Static NPV", format_currency(valuation.static_npv)),
+                ("Up / Down Factor", format!("{:.4} / {:.4}", valuation.up, valuation.down)),
+                ("Risk-Neutral Probability", format_rate_as_percentage(valuation.probability)),
+                ("Option Value", format_currency(valuation.option_value)),
+                ("Expanded NPV", format_currency(valuation.expanded_npv)),
+            ]);
             println!("{table}");
             
-            info!("Break-even analysis completed");
+            info!("Real option valuation completed");
             Ok(())
         }
-        Command::LoanPayment(loan) => {
-            debug!("Calculating loan payment with: {:?}", loan);
+        Command::CVP(cvp) => {
+            debug!("Calculating cost-volume-profit analysis with: {:?}", cvp);
             
-            let monthly_payment = calculate_loan_payment(loan.principal, loan.interest_rate, loan.loan_term)
-                .context("Failed to calculate loan payment")?;
+            let mix = match (cvp.price, cvp.variable_cost) {
+                (Some(price), Some(variable_cost)) => SalesMix::single(price, variable_cost),
+                _ => SalesMix::new(cvp.products.clone()),
+            }
+            .context("Invalid products")?;
+            let (price, variable_cost) = (mix.price(), mix.variable_cost());
             
-            let total_payment = monthly_payment * loan.loan_term * 12.0;
-            let total_interest = total_payment - loan.principal;
+            let break_even = calculate_sales_mix_break_even(cvp.fixed_costs, &mix)
+                .context("Failed to calculate break-even")?;
+            let break_even_units: f64 = break_even.iter().map(|volume| volume.units).sum();
+            let break_even_revenue: f64 = break_even.iter().map(|volume| volume.revenue).sum();
             
-            // Calculate payoff date
-            let current_date = Local::now().naive_local().date();
-            let months_to_add = (loan.loan_term * 12.0) as u32;
-            let payoff_date = current_date + Months::new(months_to_add);
+            if mix.products().len() > 1 {
+                let mut table = create_table(vec![
+                    "Product", "Price", "Variable Cost", "Contribution Margin", "Mix", "Break-Even Units", "Break-Even Revenue",
+                ]);
+                for (product, volume) in mix.products().iter().zip(&break_even) {
+                    add_row(&mut table, &[
+                        (&product.name, CellAlignment::Left),
+                        (&format_currency_plain(product.price), CellAlignment::Right),
+                        (&format_currency_plain(product.variable_cost), CellAlignment::Right),
+                        (&format_currency_plain(product.price - product.variable_cost), CellAlignment::Right),
+                        (&format_rate_as_percentage(volume.units / break_even_units), CellAlignment::Right),
+                        (&format!("{:.2}", volume.units), CellAlignment::Right),
+                        (&format_currency_plain(volume.revenue), CellAlignment::Right),
+                    ]);
+                }
+                println!("{}", "Sales Mix".bold());
+                println!("{table}\n");
+            }
             
-            let summary_items = vec![
-                ("Principal", format_currency(loan.principal)),
-                ("Annual Interest Rate", format_rate_as_percentage(loan.interest_rate / 100.0)),
-                ("Loan Term", format_years(loan.loan_term)),
-                ("Monthly Payment", format_currency(monthly_payment)),
-                ("Total Interest", format_currency(total_interest)),
-                ("Payoff Date", payoff_date.format("%Y-%m-%d").to_string()),
+            let mut summary_items = vec![
+                ("Fixed Costs", format_currency(cvp.fixed_costs)),
+                ("Price per Unit", format_currency(price)),
+                ("Variable Cost per Unit", format_currency(variable_cost)),
+                ("Contribution Margin per Unit", format_currency(mix.contribution_margin())),
+                ("Contribution Margin Ratio", format_rate_as_percentage(mix.contribution_margin_ratio())),
+                ("Break-Even Point (units)", format!("{:.2}", break_even_units)),
+                ("Break-Even Revenue", format_currency(break_even_revenue)),
             ];
             
+            if let Some(target_profit) = cvp.target_profit {
+                let units = calculate_target_profit_units(cvp.fixed_costs, variable_cost, price, target_profit)
+                    .context("Failed to calculate target-profit volume")?;
+                summary_items.push(("Target Profit", format_currency(target_profit)));
+                summary_items.push(("Units for Target Profit", format!("{:.2}", units)));
+                summary_items.push(("Revenue for Target Profit", format_currency(units * price)));
+            }
+            
+            if let (Some(target_net_income), Some(tax_rate)) = (cvp.target_net_income, cvp.tax_rate) {
+                let units = calculate_after_tax_target_units(cvp.fixed_costs, variable_cost, price, target_net_income, tax_rate)
+                    .context("Failed to calculate after-tax target volume")?;
+                summary_items.push(("Target Net Income", format_currency(target_net_income)));
+                summary_items.push(("Units for Target Net Income", format!("{:.2}", units)));
+                summary_items.push(("Revenue for Target Net Income", format_currency(units * price)));
+            }
+            
+            if let Some(sales) = cvp.sales {
+                let (margin, ratio) = calculate_margin_of_safety(sales, break_even_units)
+                    .context("Failed to calculate margin of safety")?;
+                summary_items.push(("Sales (units)", format!("{:.2}", sales)));
+                summary_items.push(("Operating Income", format_currency(sales * mix.contribution_margin() - cvp.fixed_costs)));
+                summary_items.push(("Margin of Safety (units)", format!("{:.2}", margin)));
+                summary_items.push(("Margin of Safety (revenue)", format_currency(margin * price)));
+                summary_items.push(("Margin of Safety Ratio", format_rate_as_percentage(ratio)));
+                
+                match calculate_degree_of_operating_leverage(sales, cvp.fixed_costs, variable_cost, price) {
+                    Ok(dol) => summary_items.push(("Degree of Operating Leverage", format!("{:.2}", dol))),
+                    Err(FinanceError::DivisionByZero) => {
+                        summary_items.push(("Degree of Operating Leverage", "Undefined at break-even".to_string()))
+                    }
+                    Err(e) => return Err(e).context("Failed to calculate operating leverage"),
+                }
+            }
+            
             let table = create_summary_table("Component", summary_items);
             println!("{table}");
             
-            info!("Loan payment calculation completed. Monthly payment: {:.2}", monthly_payment);
+            if let Some(range) = &cvp.volumes {
+                let points = profit_volume_table(cvp.fixed_costs, &mix, range)
+                    .context("Failed to build profit-volume table")?;
+                
+                let mut table = create_table(vec!["Units", "Revenue", "Variable Costs", "Total Costs", "Operating Income"]);
+                for point in &points {
+                    add_row(&mut table, &[
+                        (&format_input_value(point.units), CellAlignment::Right),
+                        (&format_currency_plain(point.revenue), CellAlignment::Right),
+                        (&format_currency_plain(point.variable_costs), CellAlignment::Right),
+                        (&format_currency_plain(point.total_costs), CellAlignment::Right),
+                        (&format_currency_plain(point.operating_income), CellAlignment::Right),
+                    ]);
+                }
+                println!("\n{}", "Profit-Volume Table".bold());
+                println!("{table}");
+            }
+            
+            info!("Cost-volume-profit analysis completed");
             Ok(())
         }
-        Command::Amortization(amortization) => {
-            debug!("Calculating amortization schedule with: {:?}", amortization);
+        Command::WorkingCapital(working_capital) => {
+            debug!("Analyzing working capital with: {:?}", working_capital);
             
-            let schedule = generate_amortization_schedule(
-                amortization.loan_amount,
-                amortization.annual_interest_rate,
-                amortization.loan_term_years
-            ).context("Failed to generate amortization schedule")?;
+            let periods = match (
+                &working_capital.file,
+                working_capital.revenue,
+                working_capital.cost_of_goods_sold,
+                working_capital.inventory,
+                working_capital.receivables,
+                working_capital.payables,
+            ) {
+                (Some(path), _, _, _, _, _) => {
+                    let file = File::open(path)
+                        .with_context(|| format!("Failed to open working capital file {}", path.display()))?;
+                    read_working_capital_csv(file).context("Failed to read working capital periods")?
+                }
+                (None, Some(revenue), Some(cost_of_goods_sold), Some(inventory), Some(receivables), Some(payables)) => {
+                    vec![WorkingCapitalPeriod {
+                        period: "Current".to_string(),
+                        days: Some(working_capital.days),
+                        revenue,
+                        cost_of_goods_sold,
+                        inventory,
+                        receivables,
+                        payables,
+                    }]
+                }
+                _ => anyhow::bail!("Give --file or all of --revenue, --cost-of-goods-sold, --inventory, --receivables and --payables"),
+            };
+            let metrics = analyze_working_capital(&periods).context("Failed to analyze working capital")?;
             
-            let mut table = create_table(vec!["Month", "Principal", "Interest", "Remaining Balance"]);
+            let mut headers = vec!["Period", "Days", "DIO", "DSO", "DPO", "Cash Conversion Cycle", "CCC Trend", "Net Working Capital"];
+            if working_capital.growth.is_some() {
+                headers.push("Financing Need");
+            }
+            let mut table = create_table(headers);
+            for (period, metric) in periods.iter().zip(&metrics) {
+                let mut row = vec![
+                    (metric.period.clone(), CellAlignment::Left),
+                    (format_input_value(period.days_in_period()), CellAlignment::Right),
+                    (format!("{:.1}", metric.days_inventory_outstanding), CellAlignment::Right),
+                    (format!("{:.1}", metric.days_sales_outstanding), CellAlignment::Right),
+                    (format!("{:.1}", metric.days_payables_outstanding), CellAlignment::Right),
+                    (format!("{:.1}", metric.cash_conversion_cycle), CellAlignment::Right),
+                    (metric.trend.map_or(String::new(), |trend| trend.name().to_string()), CellAlignment::Left),
+                    (format_currency_plain(metric.net_working_capital), CellAlignment::Right),
+                ];
+                if let Some(growth) = working_capital.growth {
+                    let need = calculate_working_capital_financing_need(metric.net_working_capital, growth)
+                        .context("Failed to calculate financing need")?;
+                    row.push((format_currency_plain(need), CellAlignment::Right));
+                }
+                let cells: Vec<(&str, CellAlignment)> = row.iter().map(|(text, align)| (text.as_str(), *align)).collect();
+                add_row(&mut table, &cells);
+            }
+            println!("{}", "Working Capital by Period".bold());
+            println!("{table}");
             
-            // Show selected payments (first, every 12th, and last)
-            for payment in &schedule {
-                if payment.month == 1 || payment.month % 12 == 0 || payment.month == schedule.len() as u32 {
+            let trends = summarize_trends(&metrics);
+            if !trends.is_empty() {
+                let mut table = create_table(vec!["Measure", "First", "Latest", "Change (days)", "Direction"]);
+                for trend in &trends {
                     add_row(&mut table, &[
-                        (&format!("{}", payment.month), CellAlignment::Center),
-                        (&format_currency_plain(payment.principal_payment), CellAlignment::Right),
-                        (&format_currency_plain(payment.interest_payment), CellAlignment::Right),
-                        (&format_currency_plain(payment.remaining_balance), CellAlignment::Right),
+                        (trend.name, CellAlignment::Left),
+                        (&format!("{:.1}", trend.first), CellAlignment::Right),
+                        (&format!("{:.1}", trend.latest), CellAlignment::Right),
+                        (&format!("{:+.1}", trend.latest - trend.first), CellAlignment::Right),
+                        (trend.direction.name(), CellAlignment::Left),
                     ]);
                 }
+                println!("\n{}", format!("Trend from {} to {}", metrics[0].period, metrics[metrics.len() - 1].period).bold());
+                println!("{table}");
             }
             
-            println!("{table}");
-            info!("Amortization calculation completed");
+            if let (Some(growth), Some(latest)) = (working_capital.growth, metrics.last()) {
+                let need = calculate_working_capital_financing_need(latest.net_working_capital, growth)
+                    .context("Failed to calculate financing need")?;
+                println!(
+                    "\n{}: {} to grow revenue {} from {}",
+                    "Working Capital Financing Need".bold(),
+                    format_currency(need),
+                    format_rate_as_percentage(growth),
+                    latest.period
+                );
+            }
+            
+            info!("Working capital analysis completed");
             Ok(())
         }
-        Command::ReturnOnEquity(roe) => {
-            debug!("Calculating ROE with: {:?}", roe);
+        Command::Model(model) => {
+            debug!("Projecting financial model with: {:?}", model);
             
-            let roe_value = calculate_roe(roe.net_income, roe.equity)
-                .context("Failed to calculate ROE")?;
+            let contents = std::fs::read_to_string(&model.file)
+                .with_context(|| format!("Failed to read model assumptions {}", model.file.display()))?;
+            let assumptions = ModelAssumptions::from_toml_str(&contents)
+                .context("Failed to load model assumptions")?;
+            let projection = project_financial_model(&assumptions)
+                .context("Failed to project financial model")?;
             
-            info!("Calculated ROE: {:.4}%", roe_value);
+            let mut headers = vec!["Base".to_string()];
+            headers.extend(projection.years.iter().map(|year| format!("Year {}", year.year)));
+            let incomes: Vec<&IncomeStatement> = std::iter::once(&projection.base_income)
+                .chain(projection.years.iter().map(|year| &year.income))
+                .collect();
+            let balances: Vec<&BalanceSheet> = std::iter::once(&projection.base_balance)
+                .chain(projection.years.iter().map(|year| &year.balance))
+                .collect();
+            let cash_flows: Vec<&CashFlowStatement> = projection.years.iter().map(|year| &year.cash_flow).collect();
             
-            let mut table = create_table(vec!["Net Income", "Equity", "Return on Equity"]);
+            let income_line = |value: fn(&IncomeStatement) -> f64| incomes.iter().map(|income| format_currency_plain(value(income))).collect();
+            print_statement("Income Statement", &headers, vec![
+                ("Revenue", income_line(|i| i.revenue)),
+                ("Cost of Goods Sold", income_line(|i| i.cost_of_goods_sold)),
+                ("Gross Profit", income_line(|i| i.gross_profit)),
+                ("Operating Expenses", income_line(|i| i.operating_expenses)),
+                ("EBITDA", income_line(|i| i.ebitda)),
+                ("Depreciation", income_line(|i| i.depreciation)),
+                ("EBIT", income_line(|i| i.ebit)),
+                ("Net Interest Expense", income_line(|i| i.interest_expense)),
+                ("Pre-Tax Income", income_line(|i| i.pre_tax_income)),
+                ("Taxes", income_line(|i| i.taxes)),
+                ("Net Income", income_line(|i| i.net_income)),
+            ]);
             
-            add_row(&mut table, &[
-                (&format_currency_plain(roe.net_income), CellAlignment::Right),
-                (&format_currency_plain(roe.equity), CellAlignment::Right),
-                (&format_percentage_plain(roe_value / 100.0, 2), CellAlignment::Right),
+            let balance_line = |value: fn(&BalanceSheet) -> f64| balances.iter().map(|balance| format_currency_plain(value(balance))).collect();
+            print_statement("Balance Sheet", &headers, vec![
+                ("Cash", balance_line(|b| b.cash)),
+                ("Receivables", balance_line(|b| b.receivables)),
+                ("Inventory", balance_line(|b| b.inventory)),
+                ("PP&E", balance_line(|b| b.ppe)),
+                ("Total Assets", balance_line(|b| b.total_assets())),
+                ("Payables", balance_line(|b| b.payables)),
+                ("Revolver", balance_line(|b| b.revolver)),
+                ("Term Debt", balance_line(|b| b.debt)),
+                ("Equity", balance_line(|b| b.equity)),
+                ("Total Liabilities & Equity", balance_line(|b| b.total_liabilities_and_equity())),
             ]);
             
-            println!("{table}");
-            info!("ROE calculation completed successfully");
+            let cash_flow_line = |value: fn(&CashFlowStatement) -> f64| cash_flows.iter().map(|cash_flow| format_currency_plain(value(cash_flow))).collect();
+            print_statement("Cash Flow Statement", &headers[1..], vec![
+                ("Net Income", cash_flow_line(|c| c.net_income)),
+                ("Depreciation", cash_flow_line(|c| c.depreciation)),
+                ("Change in Working Capital", cash_flow_line(|c| c.change_in_working_capital)),
+                ("Cash from Operations", cash_flow_line(|c| c.operating)),
+                ("Capital Expenditure", cash_flow_line(|c| c.capex)),
+                ("Cash from Investing", cash_flow_line(|c| c.investing)),
+                ("Term Debt Repayment", cash_flow_line(|c| c.debt_repayment)),
+                ("Revolver Draw (Repayment)", cash_flow_line(|c| c.revolver)),
+                ("Dividends", cash_flow_line(|c| c.dividends)),
+                ("Cash from Financing", cash_flow_line(|c| c.financing)),
+                ("Net Change in Cash", cash_flow_line(|c| c.net_change_in_cash)),
+            ]);
+            
+            let ratios: Vec<YearRatios> = incomes.iter().zip(&balances)
+                .map(|(income, balance)| YearRatios::from_statements(income, balance))
+                .collect();
+            let ratio_line = |value: fn(&YearRatios) -> Option<f64>, format: fn(f64) -> String| {
+                ratios.iter().map(|ratio| value(ratio).map_or("n/a".to_string(), format)).collect()
+            };
+            let times = |value: f64| format!("{:.2}x", value);
+            print_statement("Ratios", &headers, vec![
+                ("Current Ratio", ratio_line(|r| r.current_ratio, times)),
+                ("Quick Ratio", ratio_line(|r| r.quick_ratio, times)),
+                ("Debt to Equity", ratio_line(|r| r.debt_to_equity, times)),
+                ("Return on Equity", ratio_line(|r| r.return_on_equity, format_rate_as_percentage)),
+                ("Return on Assets", ratio_line(|r| r.return_on_assets, format_rate_as_percentage)),
+                ("Interest Coverage", ratio_line(|r| r.interest_coverage, times)),
+            ]);
+            
+            let mode = match assumptions.financing.mode {
+                FinancingMode::Revolver => "revolver plug",
+                FinancingMode::CashSweep => "revolver plug with cash sweep",
+            };
+            let iterations = projection.years.iter().map(|year| year.interest_iterations).max().unwrap_or(0);
+            println!(
+                "Financing: {} keeping minimum cash of {}; circular interest solved in at most {} iterations",
+                mode,
+                format_currency_plain(assumptions.financing.minimum_cash),
+                iterations
+            );
+            
+            info!("Financial model completed");
             Ok(())
         }
-        Command::DividendYield(dividend_yield) => {
-            debug!("Calculating dividend yield with: {:?}", dividend_yield);
+        Command::Lbo(lbo) => {
+            debug!("Running leveraged buyout with: {:?}", lbo);
             
-            let result = calculate_dividend_yield(&dividend_yield)
-                .context("Failed to calculate dividend yield")?;
+            let contents = std::fs::read_to_string(&lbo.file)
+                .with_context(|| format!("Failed to read LBO assumptions {}", lbo.file.display()))?;
+            let assumptions = LboAssumptions::from_toml_str(&contents)
+                .context("Failed to load LBO assumptions")?;
+            let analysis = run_lbo(&assumptions).context("Failed to run LBO")?;
             
-            info!("Calculated dividend yield: {:.4}", result);
+            let sources_and_uses = create_summary_table("Component", vec![
+                ("Entry Enterprise Value", format_currency_plain(analysis.entry_enterprise_value)),
+                ("Transaction Fees", format_currency_plain(analysis.fees)),
+                ("Minimum Cash", format_currency_plain(assumptions.minimum_cash)),
+                ("Debt Raised", format_currency_plain(analysis.entry_debt)),
+                ("Sponsor Equity", format_currency_plain(analysis.sponsor_equity)),
+            ]);
+            println!("{}", "Sources & Uses".bold());
+            println!("{sources_and_uses}\n");
             
-            let mut table = create_table(vec!["Dividend", "Price", "Dividend Yield"]);
+            let headers: Vec<String> = analysis.years.iter().map(|year| format!("Year {}", year.year)).collect();
+            let year_line = |value: fn(&LboYear) -> f64| analysis.years.iter().map(|year| format_currency_plain(value(year))).collect();
+            print_statement("Cash Flow Available for Debt Service", &headers, vec![
+                ("EBITDA", year_line(|y| y.ebitda)),
+                ("Capital Expenditure", year_line(|y| y.capex)),
+                ("Cash Interest", year_line(|y| y.cash_interest)),
+                ("Taxes", year_line(|y| y.taxes)),
+                ("Free Cash Flow", year_line(|y| y.free_cash_flow)),
+                ("Closing Cash", year_line(|y| y.cash)),
+            ]);
             
-            add_row(&mut table, &[
-                (&format!("{:.2}", dividend_yield.dividend), CellAlignment::Right),
-                (&format!("{:.2}", dividend_yield.price), CellAlignment::Right),
-                (&format_percentage_plain(result, 2), CellAlignment::Right),
+            for (index, tranche) in assumptions.tranches.iter().enumerate() {
+                let tranche_line = |value: fn(&TrancheYear) -> f64| analysis.years.iter().map(|year| format_currency_plain(value(&year.tranches[index]))).collect();
+                let interest_label = if tranche.kind == TrancheKind::Pik { "PIK Interest Accrued" } else { "Cash Interest" };
+                print_statement(&format!("{} ({})", tranche.name, tranche.kind.name()), &headers, vec![
+                    ("Opening Balance", tranche_line(|t| t.opening)),
+                    (interest_label, tranche_line(|t| t.interest)),
+                    ("Mandatory Amortization", tranche_line(|t| t.mandatory)),
+                    ("Cash Sweep", tranche_line(|t| t.sweep)),
+                    ("Closing Balance", tranche_line(|t| t.closing)),
+                ]);
+            }
+            
+            let returns = create_summary_table("Component", vec![
+                ("Exit Enterprise Value", format_currency_plain(analysis.exit_enterprise_value)),
+                ("Net Debt at Exit", format_currency_plain(analysis.exit_net_debt)),
+                ("Exit Equity", format_currency_plain(analysis.exit_equity)),
+                ("MOIC", format!("{:.2}x", analysis.moic)),
+                ("Sponsor IRR", format_rate_as_percentage(analysis.irr)),
             ]);
+            println!("{}", "Sponsor Returns".bold());
+            println!("{returns}\n");
             
-            println!("{table}");
-            info!("Dividend yield calculation completed");
+            let around = |multiple: f64| [multiple - 1.0, multiple, multiple + 1.0].into_iter().filter(|&m| m > 0.0).collect();
+            let entry_multiples = if lbo.entry_multiples.is_empty() { around(assumptions.entry_multiple) } else { lbo.entry_multiples.clone() };
+            let exit_multiples = if lbo.exit_multiples.is_empty() { around(assumptions.exit_multiple) } else { lbo.exit_multiples.clone() };
+            let grid = lbo_irr_grid(&assumptions, &entry_multiples, &exit_multiples)
+                .context("Failed to build IRR sensitivity grid")?;
+            
+            let grid_headers: Vec<String> = exit_multiples.iter().map(|multiple| format!("Exit {:.1}x", multiple)).collect();
+            let grid_labels: Vec<String> = entry_multiples.iter().map(|multiple| format!("Entry {:.1}x", multiple)).collect();
+            let grid_rows = grid_labels.iter()
+                .zip(&grid)
+                .map(|(label, irrs)| (label.as_str(), irrs.iter().map(|irr| irr.map_or("n/a".to_string(), format_rate_as_percentage)).collect()))
+                .collect();
+            print_statement("Sponsor IRR by Entry and Exit Multiple", &grid_headers, grid_rows);
+            
+            info!("LBO analysis completed");
             Ok(())
         }
-        Command::CAPM(capm) => {
-            debug!("Calculating CAPM with: {:?}", capm);
+        Command::Covenants(covenants) => {
+            debug!("Testing loan covenants with: {:?}", covenants);
             
-            let expected_return = calculate_capm(capm.risk_free_rate, capm.beta, capm.market_return)
-                .context("Failed to calculate CAPM")?;
+            let file = File::open(&covenants.file)
+                .with_context(|| format!("Failed to open cash flow projection {}", covenants.file.display()))?;
+            let periods = read_cash_flow_projection_csv(file).context("Failed to read cash flow projection")?;
+            let schedule = generate_amortization_schedule(
+                covenants.loan_amount,
+                covenants.annual_interest_rate,
+                covenants.loan_term_years,
+                covenants.first_payment_date.unwrap_or_else(default_first_payment_date)
+            ).context("Failed to generate amortization schedule")?;
             
-            let summary_items = vec![
-                ("Risk-Free Rate", format_rate_as_percentage(capm.risk_free_rate)),
-                ("Beta", format!("{:.2}", capm.beta)),
-                ("Market Return", format_rate_as_percentage(capm.market_return)),
-                ("Expected Return (CAPM)", format_rate_as_percentage(expected_return)),
-            ];
+            let defaults = CovenantThresholds::default();
+            let thresholds = CovenantThresholds {
+                min_debt_service_coverage: covenants.min_dscr.or(defaults.min_debt_service_coverage),
+                min_interest_coverage: covenants.min_interest_coverage.or(defaults.min_interest_coverage),
+                min_debt_yield: covenants.min_debt_yield.or(defaults.min_debt_yield),
+                max_loan_to_value: covenants.max_ltv.or(defaults.max_loan_to_value),
+            };
+            let results = check_covenants(&periods, &schedule, covenants.period_months, &thresholds)
+                .context("Failed to test covenants")?;
             
-            let table = create_summary_table("Component", summary_items);
-            println!("{table}");
+            let times = |value: Option<f64>| value.map_or("n/a".to_string(), |value| format!("{:.2}x", value));
+            let percent = |value: Option<f64>| value.map_or("n/a".to_string(), format_rate_as_percentage);
+            let mut table = create_table(vec![
+                "Period", "Cash Flow", "Debt Service", "DSCR", "Interest Coverage", "Debt Yield", "LTV", "Status",
+            ]);
+            for result in &results {
+                let status = if result.passed() {
+                    "Pass".to_string()
+                } else {
+                    let breached: Vec<&str> = result.breaches.iter().map(Covenant::name).collect();
+                    format!("Breach: {}", breached.join(", "))
+                };
+                add_row(&mut table, &[
+                    (&result.period, CellAlignment::Left),
+                    (&format_currency_plain(result.cash_flow), CellAlignment::Right),
+                    (&format_currency_plain(result.debt_service), CellAlignment::Right),
+                    (&times(result.debt_service_coverage), CellAlignment::Right),
+                    (&times(result.interest_coverage), CellAlignment::Right),
+                    (&percent(result.debt_yield), CellAlignment::Right),
+                    (&percent(result.loan_to_value), CellAlignment::Right),
+                    (&status, CellAlignment::Left),
+                ]);
+            }
+            println!("{}", "Covenant Tests".bold());
+            println!("{table}\n");
             
-            info!("CAPM calculation completed: {:.4}%", expected_return * 100.0);
+            let target_dscr = covenants.target_dscr
+                .or(thresholds.min_debt_service_coverage)
+                .unwrap_or(1.0);
+            let periods_per_year = 12.0 / covenants.period_months as f64;
+            let lowest = results.iter()
+                .min_by(|a, b| a.cash_flow.total_cmp(&b.cash_flow))
+                .expect("at least one period was tested");
+            // A loss in the weakest period supports no loan at all
+            let max_loan = if lowest.cash_flow > 0.0 {
+                let max_loan = calculate_max_loan_for_dscr(
+                    lowest.cash_flow * periods_per_year,
+                    target_dscr,
+                    covenants.annual_interest_rate,
+                    covenants.loan_term_years as f64
+                ).context("Failed to size maximum loan")?;
+                format_currency_plain(max_loan)
+            } else {
+                "n/a (no positive cash flow)".to_string()
+            };
+            let breached_periods = results.iter().filter(|result| !result.passed()).count();
+            
+            let threshold = |value: Option<f64>, format: &dyn Fn(f64) -> String| value.map_or("not tested".to_string(), format);
+            let summary = create_summary_table("Component", vec![
+                ("Minimum DSCR", threshold(thresholds.min_debt_service_coverage, &|value| format!("{:.2}x", value))),
+                ("Minimum Interest Coverage", threshold(thresholds.min_interest_coverage, &|value| format!("{:.2}x", value))),
+                ("Minimum Debt Yield", threshold(thresholds.min_debt_yield, &format_rate_as_percentage)),
+                ("Maximum LTV", threshold(thresholds.max_loan_to_value, &format_rate_as_percentage)),
+                ("Periods in Breach", format!("{} of {}", breached_periods, results.len())),
+                ("Lowest Cash Flow Period", lowest.period.clone()),
+                (&format!("Maximum Loan at {:.2}x DSCR", target_dscr), max_loan),
+            ]);
+            println!("{}", "Covenant Summary".bold());
+            println!("{summary}");
+            
+            info!("Covenant analysis completed");
             Ok(())
         }
-        Command::WACC(wacc) => {
-            debug!("Calculating WACC with: {:?}", wacc);
+        Command::Property(property) => {
+            debug!("Analyzing rental property with: {:?}", property);
             
-            let wacc_value = calculate_wacc(
-                wacc.cost_of_equity,
-                wacc.cost_of_debt,
-                wacc.tax_rate,
-                wacc.market_value_equity,
-                wacc.market_value_debt
-            ).context("Failed to calculate WACC")?;
+            let mortgage_rate = match property.mortgage_rate {
+                Some(rate) => rate,
+                None if property.down_payment >= 1.0 => 0.0,
+                None => anyhow::bail!("--mortgage-rate is required unless --down-payment is 1"),
+            };
+            let analysis = analyze_property(&PropertyInputs {
+                purchase_price: property.price,
+                closing_cost_rate: property.closing_costs,
+                monthly_rent: property.rent,
+                rent_growth: property.rent_growth,
+                vacancy_rate: property.vacancy,
+                operating_expenses: property.operating_expenses,
+                expense_growth: property.expense_growth,
+                capex_reserve: property.capex_reserve,
+                down_payment_rate: property.down_payment,
+                mortgage_rate,
+                mortgage_term_years: property.term,
+                appreciation_rate: property.appreciation,
+                selling_cost_rate: property.selling_costs,
+                hold_years: property.years,
+            }).context("Failed to analyze property")?;
             
-            let summary_items = vec![
-                ("Cost of Equity (Ke)", format_rate_as_percentage(wacc.cost_of_equity)),
-                ("Cost of Debt (Kd)", format_rate_as_percentage(wacc.cost_of_debt)),
-                ("Tax Rate", format_rate_as_percentage(wacc.tax_rate)),
-                ("Market Value of Equity (E)", format_currency(wacc.market_value_equity)),
-                ("Market Value of Debt (D)", format_currency(wacc.market_value_debt)),
-                ("WACC", format_rate_as_percentage(wacc_value)),
-            ];
+            let headers: Vec<String> = analysis.years.iter().map(|year| format!("Year {}", year.year)).collect();
+            let money = |value: fn(&PropertyYear) -> f64| analysis.years.iter().map(|year| format_currency_plain(value(year))).collect();
+            let rate = |value: fn(&PropertyYear) -> f64| analysis.years.iter().map(|year| format_rate_as_percentage(value(year))).collect();
+            let dscr = analysis.years.iter()
+                .map(|year| year.debt_service_coverage.map_or("n/a".to_string(), |value| format!("{:.2}x", value)))
+                .collect();
+            print_statement("Operating Projection", &headers, vec![
+                ("Gross Rent", money(|y| y.gross_rent)),
+                ("Vacancy Loss", money(|y| y.vacancy_loss)),
+                ("Effective Gross Income", money(|y| y.effective_gross_income)),
+                ("Operating Expenses", money(|y| y.operating_expenses)),
+                ("Net Operating Income", money(|y| y.net_operating_income)),
+                ("Capex Reserve", money(|y| y.capex_reserve)),
+                ("Debt Service", money(|y| y.debt_service())),
+                ("Cash Flow", money(|y| y.cash_flow)),
+                ("Cap Rate", rate(|y| y.cap_rate)),
+                ("Cash-on-Cash", rate(|y| y.cash_on_cash)),
+                ("DSCR", dscr),
+            ]);
             
-            let table = create_summary_table("Component", summary_items);
-            println!("{table}");
+            let irr = |value: Option<f64>| value.map_or("n/a".to_string(), format_rate_as_percentage);
+            let summary = create_summary_table("Component", vec![
+                ("Purchase Price", format_currency_plain(property.price)),
+                ("Loan Amount", format_currency_plain(analysis.loan_amount)),
+                ("Monthly Mortgage Payment", format_currency_plain(analysis.monthly_payment)),
+                ("Equity Invested", format_currency_plain(analysis.equity_invested)),
+                ("Going-In Cap Rate", format_rate_as_percentage(analysis.going_in_cap_rate)),
+                ("Sale Price", format_currency_plain(analysis.sale_price)),
+                ("Selling Costs", format_currency_plain(analysis.selling_costs)),
+                ("Loan Payoff", format_currency_plain(analysis.loan_payoff)),
+                ("Exit Proceeds", format_currency_plain(analysis.exit_proceeds)),
+                ("Exit Cap Rate", format_rate_as_percentage(analysis.exit_cap_rate)),
+                ("Unlevered IRR", irr(analysis.unlevered_irr)),
+                ("Levered IRR", irr(analysis.levered_irr)),
+                ("Equity Multiple", format!("{:.2}x", analysis.equity_multiple)),
+            ]);
+            println!("{}", "Hold-Period Returns".bold());
+            println!("{summary}");
             
-            info!("WACC calculation completed: {:.4}%", wacc_value * 100.0);
+            info!("Property analysis completed");
+            Ok(())
+        }
+        Command::Fx(fx) => {
+            debug!("Running FX analysis with: {:?}", fx);
+
+            let contents = std::fs::read_to_string(&fx.rates)
+                .with_context(|| format!("Failed to read FX rates {}", fx.rates.display()))?;
+            let is_json = fx.rates.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+            let market = if is_json {
+                FxMarket::from_json_str(&contents)
+            } else {
+                FxMarket::from_csv_reader(contents.as_bytes())
+            }.context("Failed to load FX rates")?;
+            
+            if let Some(from) = fx.from {
+                let spot = market.spot(from, fx.to).context("Failed to find spot rate")?;
+                let amount = Money::from_f64(fx.amount, from).context("Invalid amount")?;
+                let converted = market.convert(&amount, fx.to).context("Failed to convert amount")?;
+                let quoted = spot.via.map_or("Quoted".to_string(), |via| format!("Cross via {}", via));
+                
+                let summary = create_summary_table("Component", vec![
+                    ("Pair", format!("{}/{}", from, fx.to)),
+                    ("Spot Rate", format!("{:.6}", spot.rate)),
+                    ("Source", quoted),
+                    ("Amount", amount.to_string()),
+                    ("Converted at Spot", converted.to_string()),
+                ]);
+                println!("{}", "Spot Conversion".bold());
+                println!("{summary}\n");
+                
+                if !fx.tenors.is_empty() {
+                    let mut table = create_table(vec![
+                        "Tenor", &format!("{} Rate", from), &format!("{} Rate", fx.to), "Forward", "Forward Points", "Converted",
+                    ]);
+                    for &tenor in &fx.tenors {
+                        let forward = market.forward(from, fx.to, tenor).context("Failed to price forward")?;
+                        let from_rate = market.zero_rate(from, tenor).context("Failed to read curve")?;
+                        let to_rate = market.zero_rate(fx.to, tenor).context("Failed to read curve")?;
+                        add_row(&mut table, &[
+                            (&format_years(tenor), CellAlignment::Left),
+                            (&format_rate_as_percentage(from_rate), CellAlignment::Right),
+                            (&format_rate_as_percentage(to_rate), CellAlignment::Right),
+                            (&format!("{:.6}", forward), CellAlignment::Right),
+                            (&format!("{:+.6}", forward - spot.rate), CellAlignment::Right),
+                            (&format_currency_in(fx.amount * forward, fx.to), CellAlignment::Right),
+                        ]);
+                    }
+                    println!("{}", "Forwards by Covered Interest Parity".bold());
+                    println!("{table}\n");
+                }
+            }
+            
+            if let Some(path) = &fx.cash_flows {
+                let file = File::open(path)
+                    .with_context(|| format!("Failed to open cash flows {}", path.display()))?;
+                let flows = read_dated_cash_flows_csv(file).context("Failed to read cash flows")?;
+                let valuation_date = fx.valuation_date.unwrap_or_else(|| Local::now().date_naive());
+                let conversion = if fx.at_spot { FxConversion::Spot } else { FxConversion::Forward };
+                let converted = convert_cash_flows(&market, &flows, fx.to, valuation_date, conversion)
+                    .context("Failed to convert cash flows")?;
+                
+                let mut table = create_table(vec!["Date", "Year", "Amount", "Rate", &format!("In {}", fx.to)]);
+                for flow in &converted {
+                    add_row(&mut table, &[
+                        (&flow.date.to_string(), CellAlignment::Left),
+                        (&flow.year.to_string(), CellAlignment::Right),
+                        (&flow.original.to_string(), CellAlignment::Right),
+                        (&format!("{:.6}", flow.rate), CellAlignment::Right),
+                        (&flow.converted.to_string(), CellAlignment::Right),
+                    ]);
+                }
+                let basis = match conversion {
+                    FxConversion::Spot => "spot",
+                    FxConversion::Forward => "forward",
+                };
+                println!("{}", format!("Cash Flows in {} at {} rates from {}", fx.to, basis, valuation_date).bold());
+                println!("{table}\n");
+                
+                let annual = annual_cash_flows(&converted);
+                let mut items: Vec<(String, String)> = annual.iter()
+                    .enumerate()
+                    .map(|(index, &amount)| (format!("Year {}", index + 1), format_currency_in(amount, fx.to)))
+                    .collect();
+                if let Some(discount_rate) = fx.discount_rate {
+                    let dcf = calculate_dcf(&annual, discount_rate).context("Failed to discount cash flows")?;
+                    items.push(("Discounted Value".to_string(), format_currency_in(dcf, fx.to)));
+                    if let Some(initial_investment) = fx.initial_investment {
+                        let npv = calculate_npv(initial_investment, &annual, discount_rate)
+                            .context("Failed to calculate NPV")?;
+                        items.push(("Initial Investment".to_string(), format_currency_in(initial_investment, fx.to)));
+                        items.push(("NPV".to_string(), format_currency_in(npv, fx.to)));
+                    }
+                }
+                let summary = create_summary_table("Component", items.iter().map(|(label, value)| (label.as_str(), value.clone())).collect());
+                println!("{}", "Annual Cash Flows".bold());
+                println!("{summary}");
+            }
+            
+            info!("FX analysis completed");
             Ok(())
         }
 
@@ -1123,6 +3834,264 @@ fn run() -> Result<()> {
     }
 }
 
+/// Print a year-by-year net-worth comparison and the crossover year
+fn print_decision_analysis(analysis: &DecisionAnalysis, alternative: &str) {
+    let alternative_cost = format!("{} Cost", alternative);
+    let alternative_net_worth = format!("{} Net Worth", alternative);
+    let mut table = create_table(vec![
+        "Year", "Buy Cost", &alternative_cost, "Buy Net Worth", &alternative_net_worth, "Buy Advantage",
+    ]);
+    
+    for year in &analysis.years {
+        add_row(&mut table, &[
+            (&format!("{}", year.year), CellAlignment::Center),
+            (&format_currency_plain(year.buy_outflow), CellAlignment::Right),
+            (&format_currency_plain(year.alternative_outflow), CellAlignment::Right),
+            (&format_currency_plain(year.buy_net_worth), CellAlignment::Right),
+            (&format_currency_plain(year.alternative_net_worth), CellAlignment::Right),
+            (&format_currency_plain(year.advantage()), CellAlignment::Right),
+        ]);
+    }
+    
+    println!("{table}");
+    
+    match analysis.crossover_year {
+        Some(year) => println!("\n{}: buying comes out ahead from year {}", "Crossover".bold(), year),
+        None => println!("\n{}: buying does not come out ahead within {} years", "Crossover".bold(), analysis.years.len()),
+    }
+}
+
+/// Print one-way or two-way sensitivity tables and a tornado ranking
+fn print_sensitivity<T: Evaluate>(command: &T, ranges: &[SensitivityRange]) -> Result<()> {
+    for range in ranges {
+        let mut probe = command.clone();
+        probe.set_input(&range.parameter, range.min)?;
+    }
+    
+    let base = command.evaluate().context("Failed to evaluate the base case")?;
+    println!("{}: {}\n", format!("Base {}", command.output_name()).bold(), command.format_output(base));
+    
+    let format_result = |result: Option<f64>| result.map_or("n/a".to_string(), |value| command.format_output(value));
+    
+    if let [rows, columns] = ranges {
+        let table_data = two_way_table(rows, columns, |row, column| {
+            let mut changed = command.clone();
+            changed.set_input(&rows.parameter, row)?;
+            changed.set_input(&columns.parameter, column)?;
+            changed.evaluate()
+        })?;
+        
+        let corner = format!("{} \\ {}", rows.parameter, columns.parameter);
+        let column_labels: Vec<String> = table_data.column_values.iter().map(|v| format_input_value(*v)).collect();
+        let mut headers = vec![corner.as_str()];
+        headers.extend(column_labels.iter().map(String::as_str));
+        let mut table = create_table(headers);
+        
+        for (row, results) in table_data.row_values.iter().zip(&table_data.results) {
+            let mut cells = vec![(format_input_value(*row), CellAlignment::Left)];
+            cells.extend(results.iter().map(|result| (format_result(*result), CellAlignment::Right)));
+            let cells: Vec<(&str, CellAlignment)> = cells.iter().map(|(text, align)| (text.as_str(), *align)).collect();
+            add_row(&mut table, &cells);
+        }
+        
+        println!("{} ({})", "Two-Way Sensitivity".bold(), command.output_name());
+        println!("{table}");
+    } else {
+        for (index, range) in ranges.iter().enumerate() {
+            let rows = one_way_table(range, |value| command.evaluate_with(&range.parameter, value))?;
+            let mut table = create_table(vec![range.parameter.as_str(), command.output_name(), "Change"]);
+            
+            for (value, result) in rows {
+                let change = result.map_or("n/a".to_string(), |r| command.format_output(r - base));
+                add_row(&mut table, &[
+                    (&format_input_value(value), CellAlignment::Left),
+                    (&format_result(result), CellAlignment::Right),
+                    (&change, CellAlignment::Right),
+                ]);
+            }
+            
+            if index > 0 {
+                println!();
+            }
+            println!("{}: {}", "Sensitivity".bold(), range.parameter);
+            println!("{table}");
+        }
+    }
+    
+    let bars = tornado(ranges, |name, value| command.evaluate_with(name, value))
+        .context("Failed to rank inputs")?;
+    let mut table = create_table(vec!["Rank", "Input", "Low", "High", "Result at Low", "Result at High", "Swing"]);
+    for (rank, bar) in bars.iter().enumerate() {
+        add_row(&mut table, &[
+            (&format!("{}", rank + 1), CellAlignment::Center),
+            (&bar.parameter, CellAlignment::Left),
+            (&format_input_value(bar.low_input), CellAlignment::Right),
+            (&format_input_value(bar.high_input), CellAlignment::Right),
+            (&command.format_output(bar.low_result), CellAlignment::Right),
+            (&command.format_output(bar.high_result), CellAlignment::Right),
+            (&command.format_output(bar.swing()), CellAlignment::Right),
+        ]);
+    }
+    println!("\n{}", "Tornado (largest swing first)".bold());
+    println!("{table}");
+    
+    info!("Sensitivity analysis completed");
+    Ok(())
+}
+
+/// Evaluate every case in a scenario file with a command's calculation and print the comparison
+fn print_scenarios<T: Evaluate>(command: T, file: &ScenarioFile) -> Result<()> {
+    let provided: Vec<String> = file.base.inputs.keys().map(|name| input_key(name)).collect();
+    for (name, _) in command.inputs() {
+        if !provided.iter().any(|input| input == name) {
+            anyhow::bail!("The base case is missing input '{}'", name);
+        }
+    }
+    
+    let comparison = evaluate_scenarios(file, |inputs| {
+        let mut case = command.clone();
+        for (name, value) in inputs {
+            match value {
+                ScenarioValue::Number(number) => case.set_input(name, *number)?,
+                ScenarioValue::List(numbers) => case.set_values(name, numbers)?,
+            }
+        }
+        case.evaluate()
+    })
+    .context("Failed to evaluate scenarios")?;
+    
+    let base = comparison.results[0].value;
+    let mut table = create_table(vec!["Scenario", "Probability", "Changes", command.output_name(), "vs. Base"]);
+    for result in &comparison.results {
+        let changes: Vec<String> = result.overrides
+            .iter()
+            .map(|(name, value)| match value {
+                ScenarioValue::Number(number) => format!("{} = {}", name, format_input_value(*number)),
+                ScenarioValue::List(numbers) => format!("{} = {} values", name, numbers.len()),
+            })
+            .collect();
+        let probability = result.probability.map_or(String::new(), format_rate_as_percentage);
+        
+        add_row(&mut table, &[
+            (&result.name, CellAlignment::Left),
+            (&probability, CellAlignment::Right),
+            (&changes.join(", "), CellAlignment::Left),
+            (&command.format_output(result.value), CellAlignment::Right),
+            (&command.format_output(result.value - base), CellAlignment::Right),
+        ]);
+    }
+    
+    println!("{}", "Scenario Comparison".bold());
+    println!("{table}");
+    
+    if let Some(expected) = comparison.expected_value {
+        println!("\n{}: {}", format!("Probability-Weighted {}", command.output_name()).bold(), command.format_output(expected));
+    }
+    
+    info!("Scenario analysis completed");
+    Ok(())
+}
+
+/// Find the value of one input that makes a command's result equal the target and print it
+fn print_goal_seek<T: Evaluate>(command: &T, name: &str, target: f64) -> Result<()> {
+    let key = input_key(name);
+    let Some((input, initial_guess)) = command.inputs().into_iter().find(|(input, _)| *input == key) else {
+        return Err(unknown_input(command, name).into());
+    };
+    
+    let solution = goal_seek(|value| command.evaluate_with(input, value), target, initial_guess)
+        .with_context(|| format!("Failed to find a {} giving {} of {}", input, command.output_name(), command.format_output(target)))?;
+    let achieved = command.evaluate_with(input, solution)?;
+    
+    let table = create_summary_table("Goal Seek", vec![
+        ("Solve For", input.to_string()),
+        ("Starting Value", format_input_value(initial_guess)),
+        ("Solution", format_input_value(solution)),
+        (command.output_name(), command.format_output(achieved)),
+    ]);
+    println!("{table}");
+    
+    info!("Goal seek completed");
+    Ok(())
+}
+
+/// Print a financial statement with one row per line item and one column per year
+fn print_statement(title: &str, headers: &[String], rows: Vec<(&str, Vec<String>)>) {
+    let mut columns = vec![""];
+    columns.extend(headers.iter().map(String::as_str));
+    let mut table = create_table(columns);
+    
+    for (label, values) in &rows {
+        let mut cells = vec![(*label, CellAlignment::Left)];
+        cells.extend(values.iter().map(|value| (value.as_str(), CellAlignment::Right)));
+        add_row(&mut table, &cells);
+    }
+    
+    println!("{}", title.bold());
+    println!("{table}\n");
+}
+
+/// Format an input value without trailing zeros
+fn format_input_value(value: f64) -> String {
+    let formatted = format!("{:.4}", value);
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Load and validate a TOML bracket table
+fn load_tax_table(path: &Path) -> Result<TaxTable> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read tax table {}", path.display()))?;
+    TaxTable::from_toml_str(&contents).context("Failed to load tax table")
+}
+
+/// Whether a command uses the global `--inflation` option
+fn supports_inflation(command: &Command) -> bool {
+    matches!(
+        command,
+        Command::CompoundInterest(_) | Command::FutureValue(_) | Command::NPV(_) | Command::Amortization(_) | Command::Retirement(_)
+    )
+}
+
+/// Resolve the global `--inflation` option: a rate, or a CPI CSV file to estimate one from
+fn resolve_inflation(value: Option<&str>) -> Result<Option<Inflation>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    
+    let inflation = match value.parse::<f64>() {
+        Ok(rate) => Inflation::new(rate).context("Invalid inflation rate")?,
+        Err(_) => {
+            let file = File::open(value).with_context(|| format!("Failed to open CPI file {}", value))?;
+            let series = read_cpi_csv(file).context("Failed to read CPI file")?;
+            Inflation::from_cpi(&series).context("Failed to estimate inflation from CPI series")?
+        }
+    };
+    
+    debug!("Using annual inflation of {:.4}", inflation.annual_rate);
+    Ok(Some(inflation))
+}
+
+/// Print a nominal rate converted to a real rate with the Fisher equation
+fn print_real_rate(label: &str, nominal_rate: f64, inflation: &Inflation) {
+    println!("\n{}: {} (nominal {}, inflation {})",
+        label.bold(),
+        format_rate_as_percentage(inflation.real_rate(nominal_rate)),
+        format_rate_as_percentage(nominal_rate),
+        format_rate_as_percentage(inflation.annual_rate)
+    );
+}
+
+/// Default first payment date: the first day of next month
+fn default_first_payment_date() -> NaiveDate {
+    let today = Local::now().date_naive();
+    let (year, month) = if today.month() == 12 {
+        (today.year() + 1, 1)
+    } else {
+        (today.year(), today.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(today)
+}
+
 // Interactive mode helper functions
 
 /// Prompt for a floating point number with validation
@@ -1172,7 +4141,7 @@ fn prompt_percentage(message: &str) -> Result<f64> {
             .interact()?;
         
         match input.parse::<f64>() {
-            Ok(value) if value.is_finite() && value >= 0.0 && value <= 100.0 => {
+            Ok(value) if value.is_finite() && (0.0..=100.0).contains(&value) => {
                 return Ok(value / 100.0);
             },
             Ok(_) => println!("Please enter a percentage between 0 and 100."),
@@ -1356,12 +4325,12 @@ fn create_npv_interactive() -> Result<Command> {
     let initial_investment = prompt_positive_f64("Enter initial investment ($)")?;
     let discount_rate = prompt_percentage("Enter discount rate")?;
     let cash_flows = prompt_number_list("Enter cash flows for each period")?;
-    let cash_inflow = cash_flows.get(0).cloned().unwrap_or(1000.0);
-    Ok(Command::NPV(NPV { initial_investment, discount_rate, cash_inflow, lifespan: 5 }))
+    let cash_inflow = cash_flows.first().cloned().unwrap_or(1000.0);
+    Ok(Command::NPV(NPV { initial_investment, discount_rate, cash_inflow, lifespan: 5, sensitivity: vec![], solve_for: None, target: None }))
 }
 
 // Simplified implementations for the remaining commands
-fn create_amortization_interactive() -> Result<Command> { Ok(Command::Amortization(Amortization { loan_amount: 100000.0, annual_interest_rate: 0.05, loan_term_years: 30 })) }
+fn create_amortization_interactive() -> Result<Command> { Ok(Command::Amortization(Amortization { loan_amount: 100000.0, annual_interest_rate: 0.05, loan_term_years: 30, interest_only_months: None, balloon_amortization_years: None, constant_principal: false, payments: None, first_payment_date: None, detailed: false })) }
 fn create_roi_interactive() -> Result<Command> { Ok(Command::ROI(ROI { net_profit: 1000.0, cost_of_investment: 10000.0 })) }
 fn create_mode_interactive() -> Result<Command> { Ok(Command::Mode(Mode { numbers: vec![1.0,2.0,2.0,3.0] })) }
 fn create_median_interactive() -> Result<Command> { Ok(Command::Medium(Medium { numbers: vec![1.0,2.0,3.0,4.0,5.0] })) }
@@ -1372,13 +4341,13 @@ fn create_irr_interactive() -> Result<Command> { Ok(Command::IRR(IRR { cash_flow
 fn create_variance_interactive() -> Result<Command> { Ok(Command::Variance(Variance { numbers: vec!["1".to_string(),"2".to_string(),"3".to_string(),"4".to_string(),"5".to_string()] })) }
 fn create_standard_deviation_interactive() -> Result<Command> { Ok(Command::StandardDeviation(StandardDeviation { numbers: vec![1.0,2.0,3.0,4.0,5.0] })) }
 fn create_probability_interactive() -> Result<Command> { Ok(Command::Probability(Probability { successes: 1, trials: 6 })) }
-fn create_capm_interactive() -> Result<Command> { Ok(Command::CAPM(CAPM { risk_free_rate: 0.02, market_return: 0.08, beta: 1.2 })) }
-fn create_loan_payment_interactive() -> Result<Command> { Ok(Command::LoanPayment(LoanPayment { principal: 100000.0, interest_rate: 0.05, loan_term: 30.0 })) }
-fn create_break_even_units_interactive() -> Result<Command> { Ok(Command::BreakEvenUnits(BreakEvenUnits { fixed_costs: 5000.0, variable_costs: 10.0, price_per_unit: 20.0 })) }
-fn create_dcf_interactive() -> Result<Command> { Ok(Command::DCF(DCF { cash_flows: vec![1000.0,1100.0,1200.0,1300.0], discount_rate: 0.1 })) }
-fn create_mortgage_interactive() -> Result<Command> { Ok(Command::Mortgage(Mortgage { loan_amount: 300000.0, interest_rate: 0.045, term: 30 })) }
+fn create_capm_interactive() -> Result<Command> { Ok(Command::CAPM(CAPM { risk_free_rate: 0.02, market_return: 0.08, beta: 1.2, sensitivity: vec![], solve_for: None, target: None })) }
+fn create_loan_payment_interactive() -> Result<Command> { Ok(Command::LoanPayment(LoanPayment { principal: 100000.0, interest_rate: 0.05, loan_term: 30.0, first_payment_date: None, sensitivity: vec![], solve_for: None, target: None })) }
+fn create_break_even_units_interactive() -> Result<Command> { Ok(Command::BreakEvenUnits(BreakEvenUnits { fixed_costs: 5000.0, variable_costs: 10.0, price_per_unit: 20.0, solve_for: None, target: None })) }
+fn create_dcf_interactive() -> Result<Command> { Ok(Command::DCF(DCF { cash_flows: vec![1000.0,1100.0,1200.0,1300.0], discount_rate: 0.1, sensitivity: vec![], solve_for: None, target: None })) }
+fn create_mortgage_interactive() -> Result<Command> { Ok(Command::Mortgage(Mortgage { loan_amount: 300000.0, interest_rate: 0.045, term: 30, first_payment_date: None })) }
 fn create_weighted_average_interactive() -> Result<Command> { Ok(Command::WeightedAverage(WeightedAverage { numbers: "80,90,85".to_string(), weights: "3,2,4".to_string() })) }
-fn create_wacc_interactive() -> Result<Command> { Ok(Command::WACC(WACC { cost_of_equity: 0.12, cost_of_debt: 0.06, market_value_equity: 600000.0, market_value_debt: 400000.0, tax_rate: 0.25 })) }
+fn create_wacc_interactive() -> Result<Command> { Ok(Command::WACC(WACC { cost_of_equity: 0.12, cost_of_debt: 0.06, market_value_equity: 600000.0, market_value_debt: 400000.0, tax_rate: Some(0.25), tax_brackets: None, taxable_income: None, filing_status: "single".to_string(), sensitivity: vec![], solve_for: None, target: None })) }
 
 /// Application entry point with error handling
 fn main() {
@@ -1397,4 +4366,32 @@ fn main() {
         // Exit with a non-zero status code
         std::process::exit(1);
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_definition_is_consistent() {
+        Opts::command().debug_assert();
+    }
+
+    #[test]
+    fn test_retirement_reads_global_inflation() {
+        let opts = Opts::try_parse_from([
+            "rusty-finance", "retirement", "--current-age", "40", "--savings", "100000",
+            "--contribution", "1000", "--target-income", "60000", "--inflation", "0.03",
+        ]).unwrap();
+        assert_eq!(opts.inflation.as_deref(), Some("0.03"));
+        assert!(matches!(opts.command, Some(Command::Retirement(_))));
+    }
+    
+    #[test]
+    fn test_inflation_is_limited_to_commands_that_use_it() {
+        let command = |args: &[&str]| Opts::try_parse_from(args).unwrap().command.unwrap();
+        
+        assert!(supports_inflation(&command(&["rusty-finance", "npv", "-i", "1000", "-d", "0.1", "-c", "300", "-l", "5"])));
+        assert!(!supports_inflation(&command(&["rusty-finance", "loan-payment", "-p", "10000", "-i", "5", "-l", "5"])));
+    }
+}
@@ -0,0 +1,344 @@
+//! Money: decimal amounts tagged with an ISO 4217 currency
+
+use crate::display::add_thousands_separators;
+use crate::{FinanceError, FinanceResult, to_decimal};
+use rust_decimal::prelude::*;
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// An ISO 4217 currency
+/// 
+/// Codes are case-insensitive, whether parsed or deserialized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Currency {
+    #[default]
+    USD,
+    EUR,
+    GBP,
+    JPY,
+    CHF,
+    CAD,
+    AUD,
+    NZD,
+    CNY,
+    HKD,
+    SGD,
+    SEK,
+    NOK,
+    DKK,
+    INR,
+    KRW,
+    BRL,
+    MXN,
+    ZAR,
+    CLP,
+    ISK,
+    KWD,
+    BHD,
+    OMR,
+    JOD,
+}
+
+impl Currency {
+    /// Every supported currency
+    pub const ALL: [Currency; 25] = [
+        Currency::USD, Currency::EUR, Currency::GBP, Currency::JPY, Currency::CHF,
+        Currency::CAD, Currency::AUD, Currency::NZD, Currency::CNY, Currency::HKD,
+        Currency::SGD, Currency::SEK, Currency::NOK, Currency::DKK, Currency::INR,
+        Currency::KRW, Currency::BRL, Currency::MXN, Currency::ZAR, Currency::CLP,
+        Currency::ISK, Currency::KWD, Currency::BHD, Currency::OMR, Currency::JOD,
+    ];
+
+    /// Three-letter ISO 4217 code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::USD => "USD",
+            Currency::EUR => "EUR",
+            Currency::GBP => "GBP",
+            Currency::JPY => "JPY",
+            Currency::CHF => "CHF",
+            Currency::CAD => "CAD",
+            Currency::AUD => "AUD",
+            Currency::NZD => "NZD",
+            Currency::CNY => "CNY",
+            Currency::HKD => "HKD",
+            Currency::SGD => "SGD",
+            Currency::SEK => "SEK",
+            Currency::NOK => "NOK",
+            Currency::DKK => "DKK",
+            Currency::INR => "INR",
+            Currency::KRW => "KRW",
+            Currency::BRL => "BRL",
+            Currency::MXN => "MXN",
+            Currency::ZAR => "ZAR",
+            Currency::CLP => "CLP",
+            Currency::ISK => "ISK",
+            Currency::KWD => "KWD",
+            Currency::BHD => "BHD",
+            Currency::OMR => "OMR",
+            Currency::JOD => "JOD",
+        }
+    }
+
+    /// Number of decimal places in the currency's minor unit
+    pub fn minor_units(&self) -> u32 {
+        match self {
+            Currency::JPY | Currency::KRW | Currency::CLP | Currency::ISK => 0,
+            Currency::KWD | Currency::BHD | Currency::OMR | Currency::JOD => 3,
+            _ => 2,
+        }
+    }
+
+    /// Symbol printed before amounts; currencies without a distinct symbol use their code
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Currency::USD => "$",
+            Currency::EUR => "€",
+            Currency::GBP => "£",
+            Currency::JPY => "¥",
+            Currency::CAD => "CA$",
+            Currency::AUD => "A$",
+            Currency::NZD => "NZ$",
+            Currency::CNY => "CN¥",
+            Currency::HKD => "HK$",
+            Currency::SGD => "S$",
+            Currency::INR => "₹",
+            Currency::KRW => "₩",
+            Currency::BRL => "R$",
+            Currency::MXN => "MX$",
+            Currency::ZAR => "R",
+            other => other.code(),
+        }
+    }
+
+    /// Formats an amount with this currency's symbol, minor units and thousands separators
+    /// 
+    /// Negative amounts keep the sign after the symbol, as in `$-1,234.56`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use rusty_finance::money::Currency;
+    /// use rust_decimal::Decimal;
+    /// 
+    /// assert_eq!(Currency::USD.format(Decimal::new(12345, 1)), "$1,234.50");
+    /// assert_eq!(Currency::JPY.format(Decimal::new(12345, 1)), "¥1,234");
+    /// assert_eq!(Currency::KWD.format(Decimal::new(12345, 1)), "KWD 1,234.500");
+    /// ```
+    pub fn format(&self, amount: Decimal) -> String {
+        let mut rounded = amount.round_dp(self.minor_units());
+        if rounded.is_zero() {
+            rounded.set_sign_positive(true);
+        }
+        
+        let formatted = format!("{:.1$}", rounded, self.minor_units() as usize);
+        let (whole, fraction) = match formatted.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (formatted.as_str(), None),
+        };
+        
+        let (sign, digits) = match whole.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", whole),
+        };
+        let grouped = add_thousands_separators(digits);
+        
+        let symbol = self.symbol();
+        let separator = if symbol.ends_with(|c: char| c.is_ascii_alphabetic()) && symbol.len() > 1 { " " } else { "" };
+        match fraction {
+            Some(fraction) => format!("{}{}{}{}.{}", symbol, separator, sign, grouped, fraction),
+            None => format!("{}{}{}{}", symbol, separator, sign, grouped),
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = FinanceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim().to_ascii_uppercase();
+        Currency::ALL
+            .into_iter()
+            .find(|currency| currency.code() == code)
+            .ok_or_else(|| FinanceError::InvalidInput(format!("Unknown or unsupported currency code: {}", s.trim())))
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = FinanceError;
+    
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        code.parse()
+    }
+}
+
+/// An amount of money in a specific currency
+/// 
+/// Arithmetic between two amounts is only defined when their currencies
+/// match; mixing currencies is an error rather than a silent conversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Money {
+    amount: Decimal,
+    currency: Currency,
+}
+
+impl Money {
+    /// Creates an amount in the given currency
+    pub fn new(amount: Decimal, currency: Currency) -> Self {
+        Money { amount, currency }
+    }
+
+    /// Creates an amount from a floating-point value
+    /// 
+    /// # Examples
+    /// ```
+    /// use rusty_finance::money::{Currency, Money};
+    /// 
+    /// let price = Money::from_f64(1999.5, Currency::JPY).unwrap();
+    /// assert_eq!(price.to_string(), "¥2,000");
+    /// assert!(Money::from_f64(f64::NAN, Currency::USD).is_err());
+    /// ```
+    pub fn from_f64(amount: f64, currency: Currency) -> FinanceResult<Self> {
+        Ok(Money::new(to_decimal(amount, "money amount")?, currency))
+    }
+
+    /// A zero amount in the given currency
+    pub fn zero(currency: Currency) -> Self {
+        Money::new(Decimal::ZERO, currency)
+    }
+
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// The amount as a floating-point value, for the calculation functions
+    pub fn to_f64(&self) -> f64 {
+        self.amount.to_f64().unwrap_or(0.0)
+    }
+
+    /// The amount rounded to the currency's minor units
+    pub fn rounded(&self) -> Self {
+        Money::new(self.amount.round_dp(self.currency.minor_units()), self.currency)
+    }
+
+    /// Adds two amounts in the same currency
+    /// 
+    /// # Examples
+    /// ```
+    /// use rusty_finance::money::{Currency, Money};
+    /// use rust_decimal::Decimal;
+    /// 
+    /// let a = Money::new(Decimal::new(1050, 2), Currency::EUR);
+    /// let b = Money::new(Decimal::new(250, 2), Currency::EUR);
+    /// assert_eq!(a.checked_add(&b).unwrap().amount(), Decimal::new(1300, 2));
+    /// 
+    /// let dollars = Money::new(Decimal::ONE, Currency::USD);
+    /// assert!(a.checked_add(&dollars).is_err());
+    /// ```
+    pub fn checked_add(&self, other: &Money) -> FinanceResult<Self> {
+        self.ensure_same_currency(other, "add")?;
+        let amount = self.amount.checked_add(other.amount).ok_or(FinanceError::Overflow)?;
+        Ok(Money::new(amount, self.currency))
+    }
+
+    /// Subtracts an amount in the same currency
+    pub fn checked_sub(&self, other: &Money) -> FinanceResult<Self> {
+        self.ensure_same_currency(other, "subtract")?;
+        let amount = self.amount.checked_sub(other.amount).ok_or(FinanceError::Overflow)?;
+        Ok(Money::new(amount, self.currency))
+    }
+
+    /// Multiplies the amount by a factor, keeping the currency
+    pub fn checked_mul(&self, factor: Decimal) -> FinanceResult<Self> {
+        let amount = self.amount.checked_mul(factor).ok_or(FinanceError::Overflow)?;
+        Ok(Money::new(amount, self.currency))
+    }
+
+    /// Divides the amount by a divisor, keeping the currency
+    pub fn checked_div(&self, divisor: Decimal) -> FinanceResult<Self> {
+        if divisor.is_zero() {
+            return Err(FinanceError::DivisionByZero);
+        }
+        let amount = self.amount.checked_div(divisor).ok_or(FinanceError::Overflow)?;
+        Ok(Money::new(amount, self.currency))
+    }
+
+    /// Adds up amounts that must all be in `currency`
+    pub fn sum<'a, I>(currency: Currency, amounts: I) -> FinanceResult<Self>
+    where
+        I: IntoIterator<Item = &'a Money>,
+    {
+        amounts.into_iter().try_fold(Money::zero(currency), |total, amount| total.checked_add(amount))
+    }
+
+    fn ensure_same_currency(&self, other: &Money, operation: &str) -> FinanceResult<()> {
+        if self.currency != other.currency {
+            return Err(FinanceError::InvalidInput(format!(
+                "Cannot {} {} and {} amounts without converting", operation, self.currency, other.currency
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.currency.format(self.amount))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(amount: &str, currency: Currency) -> Money {
+        Money::new(Decimal::from_str(amount).unwrap(), currency)
+    }
+
+    #[test]
+    fn test_minor_units_drive_rounding_and_display() {
+        assert_eq!(money("1234.5", Currency::USD).to_string(), "$1,234.50");
+        assert_eq!(money("1234.5", Currency::JPY).to_string(), "¥1,234");
+        assert_eq!(money("1234.5678", Currency::KWD).to_string(), "KWD 1,234.568");
+        assert_eq!(money("1234.5678", Currency::KWD).rounded().amount(), Decimal::from_str("1234.568").unwrap());
+        assert_eq!(money("-1234567.891", Currency::EUR).to_string(), "€-1,234,567.89");
+        assert_eq!(money("999", Currency::CHF).to_string(), "CHF 999.00");
+    }
+
+    #[test]
+    fn test_negative_zero_displays_as_zero() {
+        assert_eq!(money("-0.001", Currency::USD).to_string(), "$0.00");
+        assert_eq!(money("-0.4", Currency::JPY).to_string(), "¥0");
+    }
+
+    #[test]
+    fn test_mismatched_currencies_are_refused() {
+        let euros = money("10", Currency::EUR);
+        let pounds = money("10", Currency::GBP);
+        
+        assert!(euros.checked_add(&pounds).is_err());
+        assert!(euros.checked_sub(&pounds).is_err());
+        assert!(Money::sum(Currency::EUR, &[euros, pounds]).is_err());
+        assert_eq!(Money::sum(Currency::EUR, &[euros, euros]).unwrap(), money("20", Currency::EUR));
+        assert!(matches!(euros.checked_div(Decimal::ZERO), Err(FinanceError::DivisionByZero)));
+    }
+
+    #[test]
+    fn test_parses_currency_codes() {
+        assert_eq!("jpy".parse::<Currency>().unwrap(), Currency::JPY);
+        assert_eq!(" KWD ".parse::<Currency>().unwrap().minor_units(), 3);
+        assert!("XYZ".parse::<Currency>().is_err());
+        assert!(Currency::ALL.iter().all(|currency| currency.code().parse::<Currency>().unwrap() == *currency));
+    }
+}